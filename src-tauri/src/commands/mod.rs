@@ -1,13 +1,17 @@
 // src-tauri/src/commands/mod.rs
 
-use std::sync::Mutex;
-use crate::services::{PrinterDetector, AirPrintServer};
+use std::sync::{Arc, Mutex};
+use crate::services::{PrinterDetector, PrinterMonitor, AirPrintServer, SettingsStore};
 
 
 // 1. 定义共享的应用状态 (所有命令都需要访问它)
 pub struct AppState {
-    pub detector: Mutex<PrinterDetector>,
+    /// 与 monitor 共用，修改远程 CUPS 服务器时更新
+    pub detector: Arc<PrinterDetector>,
+    /// 后台检测的打印机缓存 (本机和远程 CUPS，内部加锁)
+    pub monitor: PrinterMonitor,
     pub server: Mutex<AirPrintServer>,
+    pub settings: Mutex<SettingsStore>,
 }
 
 // 2. 声明子模块
@@ -1,30 +1,132 @@
 // src-tauri/src/commands/printer.rs
 
-use tauri::State;
-use crate::models::Printer;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, State};
+use crate::models::{
+    AppSettings, AppSocketConfig, DiscoveredPrinter, LpdConfig, MdnsConfig, Printer, PrinterProfile, ProxyPrinterConfig,
+    ReflectorConfig, VirtualPrinterConfig,
+};
+use crate::services::{AirPrintServer, BackendKind, InterfaceProvider, PrinterDetector, PrinterMonitor, SettingsStore};
+use crate::services::printer_monitor::{PrinterEvent, PRINTER_POLL_INTERVAL};
+use crate::services::{cups, dns_sd_export};
+use crate::services::printer_icons::PrinterIcons;
+use crate::services::network_monitor::{NetworkInterface, SystemInterfaces};
+use crate::services::backend::IppProxyBackend;
+use crate::services::backend::folder::{self, FolderBackend, VIRTUAL_PRINTER_ID};
 use super::AppState; // 从父模块 (mod.rs) 导入 AppState
 use rust_i18n::t;    // 引入翻译宏
 
+/// 启动后台打印机监视，变化以 `printer-added` / `printer-removed` / `printer-status-changed`
+/// 事件发送给前端，状态变化同时同步到共享中的队列 (IPP printer-state、marker-levels)
+pub fn start_printer_monitor(app: &AppHandle, detector: Arc<PrinterDetector>) -> PrinterMonitor {
+    let app = app.clone();
+    PrinterMonitor::start(detector, PRINTER_POLL_INTERVAL, move |event| {
+        if let (PrinterEvent::StatusChanged(printer), Some(state)) = (event, app.try_state::<AppState>()) {
+            if let Ok(mut server) = state.server.lock() {
+                server.update_printer_state(printer);
+            }
+        }
+        if let Err(e) = app.emit(event.name(), event.printer()) {
+            eprintln!("{}", t!("errors.printer_event_failed", event = event.name(), error = e.to_string()));
+        }
+    })
+}
+
+/// 按保存的设置创建共享服务。某项设置无法应用时 (端口被占用、反射的网卡已不存在等)
+/// 记录错误，该服务保持停用，应用照常启动
+pub fn restore_server(settings: &AppSettings) -> AirPrintServer {
+    let mut server = AirPrintServer::new();
+    let results = [
+        ("AppSocket", server.set_app_socket_config(settings.app_socket.clone())),
+        ("LPD", server.set_lpd_config(settings.lpd.clone())),
+        ("mDNS", server.set_mdns_config(settings.mdns.clone())),
+    ];
+    for (service, result) in results {
+        if let Err(e) = result {
+            eprintln!("{}", t!("errors.service_restore_failed", service = service, error = e));
+        }
+    }
+    server.set_printer_uuids(settings.printer_uuids.clone());
+    server.set_printer_profiles(settings.printer_profiles.clone());
+    if let Err(e) = server.set_reflector_config(settings.reflector.clone()) {
+        eprintln!("{}", t!("errors.service_restore_failed", service = "mDNS reflector", error = e));
+    }
+    server
+}
+
+/// 读取缓存，不重新检测。后台首次检测完成前只有本应用提供的打印机，
+/// 系统打印机随后以 `printer-added` 事件送达
 #[tauri::command]
 pub fn get_printers(state: State<AppState>) -> Result<Vec<Printer>, String> {
-    let detector = state.detector.lock().map_err(|e| e.to_string())?;
-    Ok(detector.detect())
+    with_app_printers(state.monitor.printers(), &state)
+}
+
+/// 立即重新检测 (刷新按钮)
+#[tauri::command]
+pub fn refresh_printers(state: State<AppState>) -> Result<Vec<Printer>, String> {
+    with_app_printers(state.monitor.refresh(), &state)
+}
+
+/// 系统打印机之后附加本应用提供的打印机
+fn with_app_printers(mut printers: Vec<Printer>, state: &State<AppState>) -> Result<Vec<Printer>, String> {
+    // 虚拟 "保存到文件夹" 打印机
+    let settings = state.settings.lock().map_err(|e| e.to_string())?;
+    if settings.get().virtual_printer.enabled {
+        printers.push(folder::virtual_printer(&settings.get().virtual_printer));
+    }
+    // IPP 代理打印机
+    printers.extend(settings.get().proxy_printers.iter().map(|p| p.printer()));
+    Ok(printers)
 }
 
 #[tauri::command]
 pub fn share_printer(
     printer_id: String,
+    backend: Option<BackendKind>,
     state: State<AppState>
 ) -> Result<String, String> {
-    let detector = state.detector.lock().map_err(|e| e.to_string())?;
-    
-    // 使用 t! 宏替换硬编码中文
-    let printer = detector
-        .detect_one(&printer_id)
+    let result = share_by_id(&printer_id, backend, &state);
+
+    // 新生成的打印机 UUID 写回设置
+    let mut settings = state.settings.lock().map_err(|e| e.to_string())?;
+    let server = state.server.lock().map_err(|e| e.to_string())?;
+    save_printer_uuids(&mut settings, &server)?;
+    result
+}
+
+fn share_by_id(printer_id: &str, backend: Option<BackendKind>, state: &State<AppState>) -> Result<String, String> {
+    if printer_id == VIRTUAL_PRINTER_ID {
+        let config = state.settings.lock().map_err(|e| e.to_string())?.get().virtual_printer.clone();
+        let mut server = state.server.lock().map_err(|e| e.to_string())?;
+        return share_virtual_printer(&mut server, config);
+    }
+    if printer_id.starts_with(ProxyPrinterConfig::ID_PREFIX) {
+        let config = state
+            .settings
+            .lock()
+            .map_err(|e| e.to_string())?
+            .get()
+            .proxy_printers
+            .iter()
+            .find(|p| p.printer_id() == printer_id)
+            .cloned()
+            .ok_or_else(|| t!("errors.printer_not_found", id = printer_id).to_string())?;
+        let mut server = state.server.lock().map_err(|e| e.to_string())?;
+        return share_proxy_printer(&mut server, &config);
+    }
+
+    let printer = state
+        .monitor
+        .find(printer_id)
         .ok_or_else(|| t!("errors.printer_not_found", id = printer_id).to_string())?;
     
     let mut server = state.server.lock().map_err(|e| e.to_string())?;
-    server.share(printer)
+    match backend {
+        Some(kind) => server.share_as(printer, kind),
+        None => server.share(printer),
+    }
 }
 
 #[tauri::command]
@@ -49,4 +151,270 @@ pub fn unshare_printer(
 ) -> Result<(), String> {
     let mut server = state.server.lock().map_err(|e| e.to_string())?;
     server.stop(&printer_id)
-}
\ No newline at end of file
+}
+
+#[tauri::command]
+pub fn get_cups_hosts(state: State<AppState>) -> Result<Vec<String>, String> {
+    let settings = state.settings.lock().map_err(|e| e.to_string())?;
+    Ok(settings.get().cups_hosts.clone())
+}
+
+/// 更换远程 CUPS 服务器并立即重新检测，新增和消失的队列通过打印机事件通知前端
+#[tauri::command]
+pub fn set_cups_hosts(
+    hosts: Vec<String>,
+    state: State<AppState>
+) -> Result<(), String> {
+    let hosts = hosts
+        .iter()
+        .map(|host| cups::parse_host(host).ok_or_else(|| t!("errors.cups_host_invalid", host = host).to_string()))
+        .collect::<Result<Vec<String>, String>>()?;
+
+    state.settings.lock().map_err(|e| e.to_string())?.update(|s| s.cups_hosts = hosts.clone())?;
+    state.detector.set_cups_hosts(hosts);
+    state.monitor.refresh();
+    Ok(())
+}
+
+/// 打印机 ID -> 共享时对外显示的信息
+#[tauri::command]
+pub fn get_printer_profiles(state: State<AppState>) -> Result<HashMap<String, PrinterProfile>, String> {
+    let settings = state.settings.lock().map_err(|e| e.to_string())?;
+    Ok(settings.get().printer_profiles.clone())
+}
+
+/// 修改打印机的显示名称、位置、图标等，全部为空时恢复为系统报告的值和默认图标
+#[tauri::command]
+pub fn set_printer_profile(
+    printer_id: String,
+    profile: PrinterProfile,
+    state: State<AppState>
+) -> Result<(), String> {
+    let profile = PrinterProfile {
+        display_name: profile.display_name.trim().to_string(),
+        location: profile.location.trim().to_string(),
+        info: profile.info.trim().to_string(),
+        geo_location: profile.geo_location.trim().to_string(),
+        more_info: profile.more_info.trim().to_string(),
+        icon: profile.icon.trim().to_string(),
+    };
+    if !profile.geo_location.is_empty() && !is_geo_uri(&profile.geo_location) {
+        return Err(t!("errors.profile_invalid_geo_location", uri = profile.geo_location).to_string());
+    }
+    if !profile.more_info.is_empty() && !is_http_url(&profile.more_info) {
+        return Err(t!("errors.profile_invalid_more_info", uri = profile.more_info).to_string());
+    }
+    if !profile.icon.is_empty() {
+        PrinterIcons::load(Path::new(&profile.icon))?;
+    }
+
+    let mut settings = state.settings.lock().map_err(|e| e.to_string())?;
+    let mut server = state.server.lock().map_err(|e| e.to_string())?;
+    // 重新广播失败时设置仍然保存，下次共享时生效
+    let result = server.set_printer_profile(&printer_id, profile);
+    settings.update(|s| s.printer_profiles = server.printer_profiles().clone())?;
+    result
+}
+
+/// RFC 5870：`geo:纬度,经度[,海拔]`，可带 `;u=精度` 等参数
+fn is_geo_uri(uri: &str) -> bool {
+    let Some(coordinates) = uri.strip_prefix("geo:") else {
+        return false;
+    };
+    let coordinates: Vec<&str> = coordinates.split(';').next().unwrap_or("").split(',').collect();
+    (2..=3).contains(&coordinates.len()) && coordinates.iter().all(|c| c.parse::<f64>().is_ok_and(f64::is_finite))
+}
+
+fn is_http_url(uri: &str) -> bool {
+    ["http://", "https://"]
+        .iter()
+        .any(|scheme| uri.starts_with(scheme) && uri.len() > scheme.len())
+}
+
+/// 打印机 UUID 必须跨重启保持不变，共享后把服务器中的 UUID 表同步到设置
+fn save_printer_uuids(settings: &mut SettingsStore, server: &AirPrintServer) -> Result<(), String> {
+    if settings.get().printer_uuids == *server.printer_uuids() {
+        return Ok(());
+    }
+    settings.update(|s| s.printer_uuids = server.printer_uuids().clone())
+}
+
+fn share_virtual_printer(server: &mut AirPrintServer, config: VirtualPrinterConfig) -> Result<String, String> {
+    if !config.enabled {
+        return Err(t!("errors.printer_not_found", id = VIRTUAL_PRINTER_ID).to_string());
+    }
+    let printer = folder::virtual_printer(&config);
+    server.share_with_backend(printer, Arc::new(FolderBackend::new(config)))
+}
+
+#[tauri::command]
+pub fn get_virtual_printer_config(state: State<AppState>) -> Result<VirtualPrinterConfig, String> {
+    let settings = state.settings.lock().map_err(|e| e.to_string())?;
+    Ok(settings.get().virtual_printer.clone())
+}
+
+#[tauri::command]
+pub fn set_virtual_printer_config(
+    config: VirtualPrinterConfig,
+    state: State<AppState>
+) -> Result<(), String> {
+    let mut settings = state.settings.lock().map_err(|e| e.to_string())?;
+    settings.update(|s| s.virtual_printer = config.clone())?;
+
+    // 已共享时用新配置重新共享
+    let mut server = state.server.lock().map_err(|e| e.to_string())?;
+    if server.is_shared(VIRTUAL_PRINTER_ID) {
+        server.stop(VIRTUAL_PRINTER_ID)?;
+        if config.enabled {
+            share_virtual_printer(&mut server, config)?;
+        }
+    }
+    save_printer_uuids(&mut settings, &server)
+}
+fn share_proxy_printer(server: &mut AirPrintServer, config: &ProxyPrinterConfig) -> Result<String, String> {
+    server.share_with_backend(config.printer(), Arc::new(IppProxyBackend::new(&config.uri)))
+}
+
+#[tauri::command]
+pub fn get_proxy_printers(state: State<AppState>) -> Result<Vec<ProxyPrinterConfig>, String> {
+    let settings = state.settings.lock().map_err(|e| e.to_string())?;
+    Ok(settings.get().proxy_printers.clone())
+}
+
+#[tauri::command]
+pub fn set_proxy_printers(
+    printers: Vec<ProxyPrinterConfig>,
+    state: State<AppState>
+) -> Result<(), String> {
+    if let Some(invalid) = printers.iter().find(|p| !is_ipp_uri(&p.uri)) {
+        return Err(t!("errors.proxy_invalid_uri", uri = invalid.uri).to_string());
+    }
+
+    let mut settings = state.settings.lock().map_err(|e| e.to_string())?;
+    let previous = settings.get().proxy_printers.clone();
+    settings.update(|s| s.proxy_printers = printers.clone())?;
+
+    // 已共享的代理打印机：删除的停止共享，保留的用新配置重新共享
+    let mut server = state.server.lock().map_err(|e| e.to_string())?;
+    for old in previous {
+        let id = old.printer_id();
+        if !server.is_shared(&id) {
+            continue;
+        }
+        server.stop(&id)?;
+        if let Some(config) = printers.iter().find(|p| p.printer_id() == id) {
+            share_proxy_printer(&mut server, config)?;
+        }
+    }
+    save_printer_uuids(&mut settings, &server)
+}
+
+fn is_ipp_uri(uri: &str) -> bool {
+    ["ipp://", "ipps://", "http://", "https://"]
+        .iter()
+        .any(|scheme| uri.starts_with(scheme) && uri.len() > scheme.len())
+}
+
+#[tauri::command]
+pub fn get_app_socket_config(state: State<AppState>) -> Result<AppSocketConfig, String> {
+    let settings = state.settings.lock().map_err(|e| e.to_string())?;
+    Ok(settings.get().app_socket.clone())
+}
+
+#[tauri::command]
+pub fn set_app_socket_config(
+    config: AppSocketConfig,
+    state: State<AppState>
+) -> Result<(), String> {
+    let mut settings = state.settings.lock().map_err(|e| e.to_string())?;
+    settings.update(|s| s.app_socket = config.clone())?;
+
+    let mut server = state.server.lock().map_err(|e| e.to_string())?;
+    server.set_app_socket_config(config)
+}
+
+#[tauri::command]
+pub fn get_lpd_config(state: State<AppState>) -> Result<LpdConfig, String> {
+    let settings = state.settings.lock().map_err(|e| e.to_string())?;
+    Ok(settings.get().lpd.clone())
+}
+
+#[tauri::command]
+pub fn set_lpd_config(
+    config: LpdConfig,
+    state: State<AppState>
+) -> Result<(), String> {
+    let mut settings = state.settings.lock().map_err(|e| e.to_string())?;
+    settings.update(|s| s.lpd = config.clone())?;
+
+    let mut server = state.server.lock().map_err(|e| e.to_string())?;
+    server.set_lpd_config(config)
+}
+
+#[tauri::command]
+pub fn get_mdns_config(state: State<AppState>) -> Result<MdnsConfig, String> {
+    let settings = state.settings.lock().map_err(|e| e.to_string())?;
+    Ok(settings.get().mdns.clone())
+}
+
+#[tauri::command]
+pub fn set_mdns_config(
+    config: MdnsConfig,
+    state: State<AppState>
+) -> Result<(), String> {
+    let mut settings = state.settings.lock().map_err(|e| e.to_string())?;
+    settings.update(|s| s.mdns = config.clone())?;
+
+    let mut server = state.server.lock().map_err(|e| e.to_string())?;
+    server.set_mdns_config(config)
+}
+
+/// 本机网卡，供选择广播的网卡
+#[tauri::command]
+pub fn get_network_interfaces() -> Result<Vec<NetworkInterface>, String> {
+    let mut interfaces = SystemInterfaces.interfaces()?;
+    interfaces.retain(|i| !i.addr.is_loopback());
+    interfaces.sort();
+    Ok(interfaces)
+}
+
+/// 共享打印机的 DNS-SD 记录，区域文件格式 (RFC 6763)
+#[tauri::command]
+pub fn export_dns_sd_zone(domain: String, state: State<AppState>) -> Result<String, String> {
+    let server = state.server.lock().map_err(|e| e.to_string())?;
+    let records = server.export_dns_sd(&domain)?;
+    Ok(dns_sd_export::zone_file(&records))
+}
+
+/// 共享打印机的 DNS-SD 记录，DNS UPDATE 报文 (RFC 2136)，`zone` 为要更新的区域
+#[tauri::command]
+pub fn export_dns_sd_update(domain: String, zone: String, state: State<AppState>) -> Result<Vec<u8>, String> {
+    let server = state.server.lock().map_err(|e| e.to_string())?;
+    let records = server.export_dns_sd(&domain)?;
+    dns_sd_export::dns_update(&records, &zone)
+}
+
+#[tauri::command]
+pub fn get_reflector_config(state: State<AppState>) -> Result<ReflectorConfig, String> {
+    let settings = state.settings.lock().map_err(|e| e.to_string())?;
+    Ok(settings.get().reflector.clone())
+}
+
+#[tauri::command]
+pub fn set_reflector_config(
+    config: ReflectorConfig,
+    state: State<AppState>
+) -> Result<(), String> {
+    let mut settings = state.settings.lock().map_err(|e| e.to_string())?;
+    settings.update(|s| s.reflector = config.clone())?;
+
+    let mut server = state.server.lock().map_err(|e| e.to_string())?;
+    server.set_reflector_config(config)
+}
+
+/// mDNS 反射在源网卡上发现的打印机
+#[tauri::command]
+pub fn get_discovered_printers(state: State<AppState>) -> Result<Vec<DiscoveredPrinter>, String> {
+    let server = state.server.lock().map_err(|e| e.to_string())?;
+    Ok(server.discovered_printers())
+}
@@ -2,19 +2,42 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use tauri::Manager;
 
 use airprinter::*;
-use airprinter::services::{PrinterDetector, AirPrintServer};
+use airprinter::services::{PrinterDetector, SettingsStore};
 
 // 导入命令
 use airprinter::commands::{
     get_printers, 
+    refresh_printers,
+    start_printer_monitor,
+    restore_server,
+    get_cups_hosts,
+    set_cups_hosts,
+    get_printer_profiles,
+    set_printer_profile,
     share_printer, 
     stop_printer, 
     get_shared_printers, 
     unshare_printer, 
+    get_virtual_printer_config,
+    set_virtual_printer_config,
+    get_proxy_printers,
+    set_proxy_printers,
+    get_app_socket_config,
+    set_app_socket_config,
+    get_lpd_config,
+    set_lpd_config,
+    get_mdns_config,
+    set_mdns_config,
+    get_network_interfaces,
+    export_dns_sd_zone,
+    export_dns_sd_update,
+    get_reflector_config,
+    set_reflector_config,
+    get_discovered_printers,
     set_language, // 确保这里引入了
     AppState
 };
@@ -24,9 +47,32 @@ fn main() {
         .plugin(tauri_plugin_opener::init())
         
         .setup(|app| {
+            // 设置文件保存在应用配置目录
+            let config_dir = app.path().app_config_dir()?;
+            let mut settings = SettingsStore::load(config_dir.join("settings.json"));
+
+            // 虚拟打印机默认输出到 文档/AirPrinter
+            if settings.get().virtual_printer.output_dir.is_empty() {
+                let output_dir = app.path().document_dir()?.join("AirPrinter");
+                settings.update(|s| s.virtual_printer.output_dir = output_dir.to_string_lossy().to_string())?;
+            }
+
+            // 旧版本按行号生成的打印机 ID 换成稳定 ID
+            let detector = Arc::new(PrinterDetector::new());
+            settings.migrate_printer_ids(&detector)?;
+            detector.set_cups_hosts(settings.get().cups_hosts.clone());
+
+            // 无法应用的设置只停用对应的服务，不影响窗口打开
+            let server = restore_server(settings.get());
+
+            // 后台检测本机和远程 CUPS 上的打印机，变化通过事件通知前端
+            let monitor = start_printer_monitor(app.handle(), detector.clone());
+
             app.manage(AppState {
-                detector: Mutex::new(PrinterDetector::new()),
-                server: Mutex::new(AirPrintServer::new()),
+                detector,
+                monitor,
+                server: Mutex::new(server),
+                settings: Mutex::new(settings),
             });
             
             // 👇 修复：使用 .to_string() 或 {:?}
@@ -41,10 +87,31 @@ fn main() {
         
         .invoke_handler(tauri::generate_handler![
             get_printers,
+            refresh_printers,
+            get_cups_hosts,
+            set_cups_hosts,
+            get_printer_profiles,
+            set_printer_profile,
             share_printer,
             stop_printer,
             get_shared_printers,
             unshare_printer,
+            get_virtual_printer_config,
+            set_virtual_printer_config,
+            get_proxy_printers,
+            set_proxy_printers,
+            get_app_socket_config,
+            set_app_socket_config,
+            get_lpd_config,
+            set_lpd_config,
+            get_mdns_config,
+            set_mdns_config,
+            get_network_interfaces,
+            export_dns_sd_zone,
+            export_dns_sd_update,
+            get_reflector_config,
+            set_reflector_config,
+            get_discovered_printers,
             set_language,
         ])
         
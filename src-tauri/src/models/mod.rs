@@ -1,4 +1,10 @@
 pub mod printer;
+pub mod settings;
 
-pub use printer::{Printer, PrinterStatus};
-
+pub use printer::{
+    Printer, PrinterStatus, PrinterCapabilities, PrinterDetails, PrinterState, StateReason, ReasonSeverity, Marker,
+    DiscoveredPrinter,
+};
+pub use settings::{
+    AppSettings, VirtualPrinterConfig, ProxyPrinterConfig, AppSocketConfig, LpdConfig, MdnsConfig, ReflectorConfig, PrinterProfile,
+};
@@ -6,13 +6,43 @@ pub struct Printer {
     pub name: String,
     pub id: String,
     pub status: PrinterStatus,
+    #[serde(default)]
+    pub details: PrinterDetails,
+    /// 系统报告的运行状态细节 (原因、任务数、耗材)
+    #[serde(default)]
+    pub state: PrinterState,
+    /// 检测到的打印能力，未知时为 None (共享时由打印后端查询)
+    #[serde(default)]
+    pub capabilities: Option<PrinterCapabilities>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// 系统报告的打印机信息，未知时为空
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct PrinterDetails {
+    /// printer-make-and-model
+    pub make_and_model: String,
+    /// printer-location
+    pub location: String,
+    /// printer-info (描述)
+    pub info: String,
+    /// 设备地址，例如 usb://HP/LaserJet?serial=...
+    pub device_uri: String,
+    /// 所在的远程 CUPS 服务器 (host[:port])，本机打印机为空
+    pub origin: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum PrinterStatus {
+    /// 空闲
     Online,
     Offline,
+    /// 正在处理任务
     Busy,
+    /// 已暂停 (cupsdisable)，恢复后继续打印
+    Paused,
+    /// 因故障停止 (缺纸、卡纸、开盖等)，需要处理
+    Stopped,
     Error(String),
 }
 
@@ -22,7 +52,157 @@ impl PrinterStatus {
             PrinterStatus::Online => "online",
             PrinterStatus::Offline => "offline",
             PrinterStatus::Busy => "busy",
+            PrinterStatus::Paused => "paused",
+            PrinterStatus::Stopped => "stopped",
             PrinterStatus::Error(_) => "error",
         }
     }
-}
\ No newline at end of file
+
+    /// 对应 IPP printer-state：3 idle，4 processing，5 stopped
+    pub fn ipp_state(&self) -> i32 {
+        match self {
+            PrinterStatus::Online => 3,
+            PrinterStatus::Busy => 4,
+            _ => 5,
+        }
+    }
+}
+
+/// 打印机运行状态细节，未知时为空
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct PrinterState {
+    /// printer-is-accepting-jobs
+    pub accepting_jobs: bool,
+    /// 排队和正在处理的任务数 (queued-job-count)
+    pub active_jobs: u32,
+    /// printer-state-reasons，不含 "none"
+    pub reasons: Vec<StateReason>,
+    /// 耗材 (墨盒、硒鼓等)，来自 CUPS marker-* 属性
+    pub markers: Vec<Marker>,
+}
+
+impl Default for PrinterState {
+    fn default() -> Self {
+        Self {
+            accepting_jobs: true,
+            active_jobs: 0,
+            reasons: Vec::new(),
+            markers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasonSeverity {
+    Report,
+    Warning,
+    Error,
+}
+
+/// 一条 PWG printer-state-reasons，例如 `toner-low-warning`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StateReason {
+    /// 原始关键字 (含严重程度后缀)
+    pub keyword: String,
+    /// 去掉后缀的原因，例如 `toner-low`
+    pub reason: String,
+    pub severity: ReasonSeverity,
+}
+
+impl StateReason {
+    /// 解析关键字，"none" 返回 None。没有后缀的按错误处理 (RFC 8011 5.4.12)，
+    /// 但 `paused` 只是状态说明
+    pub fn parse(keyword: &str) -> Option<Self> {
+        let keyword = keyword.trim();
+        if keyword.is_empty() || keyword == "none" {
+            return None;
+        }
+        let (reason, severity) = [("-error", ReasonSeverity::Error), ("-warning", ReasonSeverity::Warning), ("-report", ReasonSeverity::Report)]
+            .into_iter()
+            .find_map(|(suffix, severity)| keyword.strip_suffix(suffix).map(|reason| (reason, severity)))
+            .unwrap_or_else(|| {
+                let severity = if keyword == "paused" { ReasonSeverity::Report } else { ReasonSeverity::Error };
+                (keyword, severity)
+            });
+        Some(Self { keyword: keyword.to_string(), reason: reason.to_string(), severity })
+    }
+}
+
+/// 一个耗材
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Marker {
+    /// marker-names，例如 "Black Toner"
+    pub name: String,
+    /// marker-colors，例如 "#000000"，多色耗材为 "#00FFFF#FF00FF"，未知为 "none"
+    pub color: String,
+    /// marker-types，例如 toner、ink-cartridge
+    pub kind: String,
+    /// 剩余百分比 0..=100；-1 不可用，-2 未知，-3 尚有余量
+    pub level: i32,
+    /// marker-low-levels，低于此值视为不足
+    pub low_level: i32,
+    pub high_level: i32,
+}
+
+impl Marker {
+    pub fn is_low(&self) -> bool {
+        self.level >= 0 && self.level <= self.low_level
+    }
+}
+
+/// mDNS 反射在源网卡上发现的网络打印机
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DiscoveredPrinter {
+    /// DNS-SD 实例名
+    pub name: String,
+    /// `_ipp._tcp` 或 `_ipps._tcp`
+    pub service_type: String,
+    pub host: String,
+    pub addresses: Vec<String>,
+    pub port: u16,
+    /// 下游地址，反射后由 IPP 代理转发到这里
+    pub uri: String,
+    /// TXT 中的型号 (`ty`)
+    pub model: String,
+    /// 是否符合选择规则
+    pub selected: bool,
+    /// 是否已在目标网卡上重新广播
+    pub reflected: bool,
+}
+
+/// 打印机能力 (由打印后端提供)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct PrinterCapabilities {
+    pub color: bool,
+    pub duplex: bool,
+    /// 支持的文档格式 (MIME 类型)
+    pub document_formats: Vec<String>,
+    /// 最大纸张，AirPrint PaperMax 取值：<legal-A4 / legal-A4 / isoC-A2 / >isoC-A2
+    pub max_paper: String,
+    /// 支持的分辨率 (dpi)
+    pub resolutions: Vec<u32>,
+    /// 支持的纸张 (PWG 媒体名，如 `iso_a3_297x420mm`)
+    pub media: Vec<String>,
+}
+
+/// 能力未知或查询失败时的保守取值：黑白、单面、只接受 PDF，
+/// 宁可少广播也不声明打印机做不到的功能
+impl Default for PrinterCapabilities {
+    fn default() -> Self {
+        Self {
+            color: false,
+            duplex: false,
+            document_formats: vec!["application/pdf".to_string()],
+            max_paper: "legal-A4".to_string(),
+            resolutions: vec![300],
+            media: vec![
+                "iso_a4_210x297mm".to_string(),
+                "na_letter_8.5x11in".to_string(),
+                "na_legal_8.5x14in".to_string(),
+            ],
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Printer, PrinterDetails, PrinterState, PrinterStatus};
+
+/// 持久化的应用设置 (settings.json)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AppSettings {
+    #[serde(default)]
+    pub virtual_printer: VirtualPrinterConfig,
+    /// IPP 代理打印机
+    #[serde(default)]
+    pub proxy_printers: Vec<ProxyPrinterConfig>,
+    /// AppSocket (RAW 9100) 监听
+    #[serde(default)]
+    pub app_socket: AppSocketConfig,
+    /// LPD (RFC 1179) 监听
+    #[serde(default)]
+    pub lpd: LpdConfig,
+    #[serde(default)]
+    pub mdns: MdnsConfig,
+    /// mDNS 反射
+    #[serde(default)]
+    pub reflector: ReflectorConfig,
+    /// 远程 CUPS 服务器 (host 或 host:port)，其上的队列与本机打印机一起列出
+    #[serde(default)]
+    pub cups_hosts: Vec<String>,
+    /// 打印机 ID -> UUID。iOS 按 UUID 缓存打印机，必须跨重启保持不变
+    #[serde(default)]
+    pub printer_uuids: HashMap<String, String>,
+    /// 打印机 ID -> 共享时对外显示的信息
+    #[serde(default)]
+    pub printer_profiles: HashMap<String, PrinterProfile>,
+}
+
+impl AppSettings {
+    /// 设置中引用的打印机 ID
+    pub fn printer_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.printer_uuids.keys().chain(self.printer_profiles.keys()).cloned().collect();
+        if !self.app_socket.printer_id.is_empty() {
+            ids.push(self.app_socket.printer_id.clone());
+        }
+        ids
+    }
+
+    /// 按 旧 ID -> 新 ID 替换设置中的打印机 ID，UUID 随打印机保留
+    pub fn rename_printer_ids(&mut self, renamed: &HashMap<String, String>) {
+        for (old, new) in renamed {
+            if let Some(uuid) = self.printer_uuids.remove(old) {
+                self.printer_uuids.entry(new.clone()).or_insert(uuid);
+            }
+            if let Some(profile) = self.printer_profiles.remove(old) {
+                self.printer_profiles.entry(new.clone()).or_insert(profile);
+            }
+        }
+        if let Some(new) = renamed.get(&self.app_socket.printer_id) {
+            self.app_socket.printer_id = new.clone();
+        }
+    }
+}
+
+/// 共享打印机对外显示的信息 (mDNS 和 IPP 属性)，为空的项使用系统报告的值
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct PrinterProfile {
+    /// 显示名称：mDNS 实例名、TXT `ty` 和 printer-name
+    pub display_name: String,
+    /// printer-location，同时作为 TXT `note`
+    pub location: String,
+    /// printer-info
+    pub info: String,
+    /// printer-geo-location，RFC 5870 的 geo: URI，例如 geo:31.2304,121.4737
+    pub geo_location: String,
+    /// printer-more-info，打印机说明页的 http(s) 地址
+    pub more_info: String,
+    /// 图标图片 (PNG) 的路径，为空时使用由应用图标生成的默认图标
+    pub icon: String,
+}
+
+impl PrinterProfile {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// 虚拟 "保存到文件夹" 打印机
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct VirtualPrinterConfig {
+    pub enabled: bool,
+    pub name: String,
+    /// 输出目录，为空时由启动流程填入 文档/AirPrinter
+    pub output_dir: String,
+    /// 文件名模板，可用 {job} {user} {device} {timestamp} {date} {time} {id}
+    pub file_name_template: String,
+}
+
+impl Default for VirtualPrinterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            name: "Save to Folder (PDF)".to_string(),
+            output_dir: String::new(),
+            file_name_template: "{timestamp}_{job}".to_string(),
+        }
+    }
+}
+
+/// AppSocket / JetDirect 监听：旧 Windows 客户端和标签软件只能走 TCP 9100
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AppSocketConfig {
+    pub enabled: bool,
+    pub port: u16,
+    /// 接收 RAW 任务的打印机 ID，为空时使用第一台共享的打印机
+    pub printer_id: String,
+}
+
+impl Default for AppSocketConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 9100,
+            printer_id: String::new(),
+        }
+    }
+}
+
+/// LPD 服务：供只支持 LPD 的旧 Unix 主机和嵌入式设备使用
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LpdConfig {
+    pub enabled: bool,
+    /// 标准端口 515 在 Unix 上需要 root 权限
+    pub port: u16,
+}
+
+impl Default for LpdConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 515,
+        }
+    }
+}
+
+/// mDNS 广播的可选服务
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MdnsConfig {
+    /// 额外的 `_print._sub` 子类型 (IPP Everywhere 客户端)，只有 Avahi 后端会注册
+    pub print_subtype: bool,
+    /// 广播 `_ipps._tcp` 的端口，None 表示不广播。本程序不提供 TLS，需要外部 TLS 终结
+    pub ipps_port: Option<u16>,
+    /// 广播的网卡名，为空时使用全部非 VPN / 虚拟网卡
+    pub interfaces: Vec<String>,
+    /// 重发通告的间隔 (秒)，0 表示只在注册时通告。应小于主机记录的 TTL (120 秒)
+    pub announce_interval_secs: u64,
+}
+
+impl Default for MdnsConfig {
+    fn default() -> Self {
+        Self {
+            print_subtype: false,
+            ipps_port: None,
+            interfaces: Vec::new(),
+            announce_interval_secs: 60,
+        }
+    }
+}
+
+/// mDNS 反射：在源网卡上浏览网络打印机，选中的通过 IPP 代理在目标网卡上重新广播
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ReflectorConfig {
+    pub enabled: bool,
+    /// 浏览的网卡名，为空时浏览全部网卡
+    pub source_interface: String,
+    /// 重新广播的网卡名，为空时使用 mDNS 设置中的网卡
+    pub target_interface: String,
+    /// 要反射的打印机名称，支持 `*` 通配，不区分大小写。为空时不反射
+    pub include: Vec<String>,
+    /// 排除的打印机名称，优先于 include
+    pub exclude: Vec<String>,
+}
+
+impl ReflectorConfig {
+    /// 打印机是否符合选择规则
+    pub fn selects(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        let matches = |pattern: &String| wildcard_match(&pattern.to_lowercase(), &name);
+        self.include.iter().any(matches) && !self.exclude.iter().any(matches)
+    }
+}
+
+/// `*` 匹配任意多个字符
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let Some((first, rest)) = pattern.split_once('*') else {
+        return pattern == text;
+    };
+    let Some(mut remaining) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = rest.split('*').collect();
+    let last = parts.pop().unwrap_or("");
+    for part in parts {
+        match remaining.find(part) {
+            Some(pos) => remaining = &remaining[pos + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.ends_with(last)
+}
+
+/// 代理打印机：把任务转发给手机无法直接访问的下游 IPP 打印机
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProxyPrinterConfig {
+    pub name: String,
+    /// 下游打印机地址，例如 ipp://192.168.10.20/ipp/print
+    pub uri: String,
+}
+
+impl ProxyPrinterConfig {
+    pub const ID_PREFIX: &'static str = "proxy-";
+
+    /// 由下游地址生成的打印机 ID
+    pub fn printer_id(&self) -> String {
+        let target = self.uri.split_once("://").map(|(_, rest)| rest).unwrap_or(&self.uri);
+        let target: String = target
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+        format!("{}{}", Self::ID_PREFIX, target)
+    }
+
+    pub fn printer(&self) -> Printer {
+        Printer {
+            name: self.name.clone(),
+            id: self.printer_id(),
+            status: PrinterStatus::Online,
+            details: PrinterDetails::default(),
+            state: PrinterState::default(),
+            capabilities: None,
+        }
+    }
+}
@@ -1,14 +1,33 @@
 use std::collections::HashMap;
-use crate::models::Printer;
-use crate::services::MdnsBroadcaster;
+use std::sync::Arc;
+use crate::models::{AppSocketConfig, DiscoveredPrinter, LpdConfig, MdnsConfig, Printer, PrinterProfile, ReflectorConfig};
+use crate::services::{mdns_backend, MdnsBackend, MdnsReflector};
+use crate::services::dns_sd_export::{self, Record};
+use crate::services::mdns_broadcaster::{LPD_SERVICE, PDL_DATASTREAM_SERVICE};
 use crate::services::ipp::IppServer;
+use crate::services::socket_server::AppSocketServer;
+use crate::services::lpd_server::LpdServer;
+use crate::services::backend::{self, BackendKind, PrinterBackend};
+use crate::services::print_queue::{queue_name, QueueRegistry};
 // 引入 t! 宏用于翻译
 use rust_i18n::t;
 
 pub struct AirPrintServer {
     shared_printers: HashMap<String, Printer>,
-    mdns: Option<MdnsBroadcaster>,
+    mdns: Option<Box<dyn MdnsBackend>>,
     ipp_server: Option<IppServer>,
+    queues: QueueRegistry,
+    app_socket: AppSocketConfig,
+    socket_server: Option<AppSocketServer>,
+    lpd: LpdConfig,
+    lpd_server: Option<LpdServer>,
+    mdns_config: MdnsConfig,
+    /// 打印机 ID -> UUID，由设置加载，新生成的需要写回设置
+    printer_uuids: HashMap<String, String>,
+    /// 打印机 ID -> 对外显示的信息，由设置加载
+    printer_profiles: HashMap<String, PrinterProfile>,
+    reflector_config: ReflectorConfig,
+    reflector: Option<MdnsReflector>,
 }
 
 impl AirPrintServer {
@@ -17,10 +36,31 @@ impl AirPrintServer {
             shared_printers: HashMap::new(),
             mdns: None,
             ipp_server: None,
+            queues: QueueRegistry::new(),
+            app_socket: AppSocketConfig::default(),
+            socket_server: None,
+            lpd: LpdConfig::default(),
+            lpd_server: None,
+            mdns_config: MdnsConfig::default(),
+            printer_uuids: HashMap::new(),
+            printer_profiles: HashMap::new(),
+            reflector_config: ReflectorConfig::default(),
+            reflector: None,
         }
     }
 
+    /// 使用平台默认后端共享打印机
     pub fn share(&mut self, printer: Printer) -> Result<String, String> {
+        let kind = BackendKind::default_for(&printer);
+        self.share_as(printer, kind)
+    }
+
+    pub fn share_as(&mut self, printer: Printer, kind: BackendKind) -> Result<String, String> {
+        let backend = backend::create(kind, &printer).map_err(|e| e.to_string())?;
+        self.share_with_backend(printer, backend)
+    }
+
+    pub fn share_with_backend(&mut self, printer: Printer, backend: Arc<dyn PrinterBackend>) -> Result<String, String> {
         let printer_id = printer.id.clone();
         
         if self.shared_printers.contains_key(&printer_id) {
@@ -30,41 +70,77 @@ impl AirPrintServer {
         
         // 替换 println!
         println!("{}", t!("messages.start_sharing", name = printer.name));
+        println!("{}", t!("logs.backend_selected", name = printer.name, backend = backend.name()));
 
-        // 启动 IPP 服务器
-        if self.ipp_server.is_none() {
-            let ipp = IppServer::new("0.0.0.0", 631);
-            ipp.start();
-            self.ipp_server = Some(ipp);
-            println!("{}", t!("messages.ipp_started"));
-        }
+        self.start_ipp_server();
         
         // 初始化 mDNS 广播
         if self.mdns.is_none() {
-            self.mdns = Some(MdnsBroadcaster::new().map_err(|e| {
+            let mut mdns = mdns_backend::create().map_err(|e| {
                 t!("messages.mdns_error", error = e.to_string()).to_string()
-            })?);
+            })?;
+            mdns.set_config(self.mdns_config.clone());
+            self.mdns = Some(mdns);
         }
         
+        let uuid = self.printer_uuid(&printer_id);
+        let queue = self.queues.add(printer.clone(), backend, &uuid);
+        queue.set_profile(self.printer_profiles.get(&printer_id).cloned().unwrap_or_default());
+
         // 广播 AirPrint 服务
         if let Some(ref mut mdns) = self.mdns {
-            mdns.broadcast_airprint(&printer.name, 631).map_err(|e| {
-                t!("messages.mdns_error", error = e.to_string()).to_string()
-            })?;
+            if let Err(e) = mdns.broadcast_airprint(&queue, 631) {
+                self.queues.remove(&printer_id);
+                return Err(t!("messages.mdns_error", error = e.to_string()).to_string());
+            }
         }
         
+        // RAW 9100 / LPD 失败不影响 AirPrint 共享
+        if let Err(e) = self.start_app_socket(&printer) {
+            eprintln!("{}", e);
+        }
+        if let Err(e) = self.start_lpd(&printer) {
+            eprintln!("{}", e);
+        }
+
         self.shared_printers.insert(printer_id.clone(), printer);
         
         // 返回成功消息也使用翻译
         Ok(t!("messages.share_success", id = printer_id).to_string())
     }
 
+    /// 启动 IPP 服务器 (共享打印机和 mDNS 反射共用)
+    fn start_ipp_server(&mut self) {
+        if self.ipp_server.is_none() {
+            let ipp = IppServer::new("::", 631, self.queues.clone());
+            ipp.start();
+            self.ipp_server = Some(ipp);
+            println!("{}", t!("messages.ipp_started"));
+        }
+    }
+
     pub fn stop(&mut self, printer_id: &str) -> Result<(), String> {
         match self.shared_printers.remove(printer_id) {
             Some(_) => {
                 println!("{}", t!("messages.stop_sharing", id = printer_id));
+                self.queues.remove(printer_id);
+                // 单独撤回这台打印机的所有服务 (发送 goodbye)
+                if let Some(ref mut mdns) = self.mdns {
+                    mdns.withdraw(printer_id);
+                }
+
+                if self.socket_server.as_ref().is_some_and(|s| s.printer_id() == printer_id) {
+                    self.stop_app_socket();
+                    // 未指定打印机时转给其余共享的打印机
+                    if let Some(next) = self.shared_printers.values().next().cloned() {
+                        if let Err(e) = self.start_app_socket(&next) {
+                            eprintln!("{}", e);
+                        }
+                    }
+                }
                 
                 if self.shared_printers.is_empty() {
+                    self.stop_lpd();
                     self.mdns = None; 
                 }
                 
@@ -81,4 +157,242 @@ impl AirPrintServer {
     pub fn get_shared_printers(&self) -> Vec<&Printer> {
         self.shared_printers.values().collect()
     }
-}
\ No newline at end of file
+
+    pub fn queues(&self) -> &QueueRegistry {
+        &self.queues
+    }
+
+    /// 打印机监视检测到状态变化时更新共享中的打印机，未共享时忽略
+    pub fn update_printer_state(&mut self, printer: &Printer) {
+        let Some(shared) = self.shared_printers.get_mut(&printer.id) else {
+            return;
+        };
+        shared.status = printer.status.clone();
+        shared.state = printer.state.clone();
+        if let Some(queue) = self.queues.for_printer(&printer.id) {
+            queue.set_state(printer.status.clone(), printer.state.clone());
+        }
+    }
+
+    pub fn app_socket_config(&self) -> &AppSocketConfig {
+        &self.app_socket
+    }
+
+    /// 更新 AppSocket 设置，正在运行的监听按新设置重启
+    pub fn set_app_socket_config(&mut self, config: AppSocketConfig) -> Result<(), String> {
+        self.stop_app_socket();
+        self.app_socket = config;
+
+        let target = if self.app_socket.printer_id.is_empty() {
+            self.shared_printers.values().next().cloned()
+        } else {
+            self.shared_printers.get(&self.app_socket.printer_id).cloned()
+        };
+        match target {
+            Some(printer) => self.start_app_socket(&printer),
+            None => Ok(()),
+        }
+    }
+
+    /// 为打印机启动 RAW 9100 监听并广播 `_pdl-datastream._tcp`
+    fn start_app_socket(&mut self, printer: &Printer) -> Result<(), String> {
+        let config = &self.app_socket;
+        if !config.enabled || self.socket_server.is_some() {
+            return Ok(());
+        }
+        if !config.printer_id.is_empty() && config.printer_id != printer.id {
+            return Ok(());
+        }
+
+        let server = AppSocketServer::new("0.0.0.0", config.port, &printer.id, self.queues.clone());
+        server.start()?;
+
+        let name = self.display_name(printer);
+        if let Some(ref mut mdns) = self.mdns {
+            mdns.broadcast_pdl_datastream(&printer.id, &name, config.port)
+                .map_err(|e| t!("messages.mdns_error", error = e).to_string())?;
+        }
+        self.socket_server = Some(server);
+        Ok(())
+    }
+
+    fn stop_app_socket(&mut self) {
+        if let Some(server) = self.socket_server.take() {
+            server.stop();
+            if let Some(ref mut mdns) = self.mdns {
+                mdns.withdraw_service(server.printer_id(), PDL_DATASTREAM_SERVICE);
+            }
+        }
+    }
+
+    pub fn printer_uuids(&self) -> &HashMap<String, String> {
+        &self.printer_uuids
+    }
+
+    pub fn set_printer_uuids(&mut self, uuids: HashMap<String, String>) {
+        self.printer_uuids = uuids;
+    }
+
+    /// 打印机的 UUID，没有时生成一个新的 (RFC 4122 v4)
+    fn printer_uuid(&mut self, printer_id: &str) -> String {
+        self.printer_uuids
+            .entry(printer_id.to_string())
+            .or_insert_with(|| uuid::Uuid::new_v4().to_string())
+            .clone()
+    }
+
+    pub fn printer_profiles(&self) -> &HashMap<String, PrinterProfile> {
+        &self.printer_profiles
+    }
+
+    pub fn set_printer_profiles(&mut self, profiles: HashMap<String, PrinterProfile>) {
+        self.printer_profiles = profiles;
+    }
+
+    /// 修改打印机对外显示的信息，正在共享时立即更新 IPP 属性并重新广播
+    pub fn set_printer_profile(&mut self, printer_id: &str, profile: PrinterProfile) -> Result<(), String> {
+        if profile.is_empty() {
+            self.printer_profiles.remove(printer_id);
+        } else {
+            self.printer_profiles.insert(printer_id.to_string(), profile.clone());
+        }
+
+        let Some(printer) = self.shared_printers.get(printer_id).cloned() else {
+            return Ok(());
+        };
+        let Some(queue) = self.queues.for_printer(printer_id) else {
+            return Ok(());
+        };
+        queue.set_profile(profile);
+
+        // 同一打印机的各类服务使用同一实例名，名称变化时全部重新注册
+        if let Some(ref mut mdns) = self.mdns {
+            mdns.broadcast_airprint(&queue, 631)
+                .map_err(|e| t!("messages.mdns_error", error = e).to_string())?;
+            if self.socket_server.as_ref().is_some_and(|s| s.printer_id() == printer_id) {
+                mdns.broadcast_pdl_datastream(printer_id, &queue.display_name(), self.app_socket.port)
+                    .map_err(|e| t!("messages.mdns_error", error = e).to_string())?;
+            }
+        }
+        if self.lpd_server.is_some() {
+            self.start_lpd(&printer)?;
+        }
+        Ok(())
+    }
+
+    /// 共享中打印机对外显示的名称
+    fn display_name(&self, printer: &Printer) -> String {
+        self.queues
+            .for_printer(&printer.id)
+            .map(|queue| queue.display_name())
+            .unwrap_or_else(|| printer.name.clone())
+    }
+
+    pub fn mdns_config(&self) -> &MdnsConfig {
+        &self.mdns_config
+    }
+
+    /// 更新 mDNS 设置并重新广播所有共享的打印机
+    pub fn set_mdns_config(&mut self, config: MdnsConfig) -> Result<(), String> {
+        self.mdns_config = config.clone();
+        let Some(ref mut mdns) = self.mdns else {
+            return Ok(());
+        };
+        mdns.set_config(config);
+
+        for printer in self.shared_printers.values() {
+            if let Some(queue) = self.queues.for_printer(&printer.id) {
+                mdns.broadcast_airprint(&queue, 631)
+                    .map_err(|e| t!("messages.mdns_error", error = e).to_string())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 把正在广播的服务导出为 `domain` 下的单播 DNS-SD 记录
+    pub fn export_dns_sd(&self, domain: &str) -> Result<Vec<Record>, String> {
+        let services = self.mdns.as_ref().map(|m| m.services()).unwrap_or_default();
+        if services.is_empty() {
+            return Err(t!("errors.dns_sd_nothing_to_export").to_string());
+        }
+        dns_sd_export::records(&services, domain)
+    }
+
+    pub fn reflector_config(&self) -> &ReflectorConfig {
+        &self.reflector_config
+    }
+
+    /// 更新 mDNS 反射设置，按新设置重新浏览和广播
+    pub fn set_reflector_config(&mut self, config: ReflectorConfig) -> Result<(), String> {
+        if let Some(mut reflector) = self.reflector.take() {
+            reflector.stop();
+        }
+        self.reflector_config = config;
+        if !self.reflector_config.enabled {
+            return Ok(());
+        }
+
+        self.start_ipp_server();
+        let reflector = MdnsReflector::start(self.reflector_config.clone(), self.mdns_config.clone(), self.queues.clone())?;
+        self.reflector = Some(reflector);
+        Ok(())
+    }
+
+    /// mDNS 反射发现的打印机，未启用时为空
+    pub fn discovered_printers(&self) -> Vec<DiscoveredPrinter> {
+        self.reflector.as_ref().map(|r| r.discovered()).unwrap_or_default()
+    }
+
+    pub fn lpd_config(&self) -> &LpdConfig {
+        &self.lpd
+    }
+
+    /// 更新 LPD 设置，按新设置重新监听并广播所有共享的打印机
+    pub fn set_lpd_config(&mut self, config: LpdConfig) -> Result<(), String> {
+        self.stop_lpd();
+        self.lpd = config;
+
+        let printers: Vec<Printer> = self.shared_printers.values().cloned().collect();
+        for printer in printers {
+            self.start_lpd(&printer)?;
+        }
+        Ok(())
+    }
+
+    /// 需要时启动 LPD 监听，并以 `_printer._tcp` 广播打印机的队列
+    fn start_lpd(&mut self, printer: &Printer) -> Result<(), String> {
+        if !self.lpd.enabled {
+            return Ok(());
+        }
+        if self.lpd_server.is_none() {
+            let server = LpdServer::new("0.0.0.0", self.lpd.port, self.queues.clone());
+            server.start()?;
+            self.lpd_server = Some(server);
+        }
+
+        let name = self.display_name(printer);
+        let queue = self.queues.for_printer(&printer.id).map(|q| q.name()).unwrap_or_else(|| queue_name(&printer.id));
+        if let Some(ref mut mdns) = self.mdns {
+            mdns.broadcast_lpd(&printer.id, &name, &queue, self.lpd.port)
+                .map_err(|e| t!("messages.mdns_error", error = e).to_string())?;
+        }
+        Ok(())
+    }
+
+    fn stop_lpd(&mut self) {
+        if let Some(server) = self.lpd_server.take() {
+            server.stop();
+        }
+        if let Some(ref mut mdns) = self.mdns {
+            for id in self.shared_printers.keys() {
+                mdns.withdraw_service(id, LPD_SERVICE);
+            }
+        }
+    }
+}
+
+impl Default for AirPrintServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,603 @@
+// src-tauri/src/services/avahi_broadcaster.rs
+//
+// 通过 avahi-daemon 的 D-Bus 接口注册服务 (仅 Linux)。
+// 每台打印机一个 EntryGroup，包含它的全部服务类型；探测、重发通告和网络变化都由 Avahi 处理。
+// 提交后不等待探测结果，EntryGroup 的状态变化 (探测完成、与网络上的服务重名) 由后台线程处理。
+// 浏览同样通过 Avahi 的 ServiceBrowser。
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use mdns_sd::{ServiceEvent, ServiceInfo};
+use rust_i18n::t;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use zbus::blocking::{Connection, MessageIterator, Proxy};
+use zbus::message::Type as MessageType;
+use zbus::zvariant::{DynamicType, OwnedObjectPath};
+use zbus::MatchRule;
+
+use crate::models::MdnsConfig;
+use crate::services::mdns_backend::{MdnsBackend, ServiceBrowser};
+use crate::services::mdns_broadcaster::{instance_name, resolve_addresses, with_suffix, MAX_RENAME};
+use crate::services::network_monitor::SystemInterfaces;
+
+const AVAHI_SERVICE: &str = "org.freedesktop.Avahi";
+const SERVER_INTERFACE: &str = "org.freedesktop.Avahi.Server";
+const ENTRY_GROUP_INTERFACE: &str = "org.freedesktop.Avahi.EntryGroup";
+const SERVICE_BROWSER_INTERFACE: &str = "org.freedesktop.Avahi.ServiceBrowser";
+
+/// AVAHI_IF_UNSPEC / AVAHI_PROTO_UNSPEC：所有网卡、IPv4 和 IPv6
+const IF_UNSPEC: i32 = -1;
+const PROTO_UNSPEC: i32 = -1;
+
+/// EntryGroup 状态 (AvahiEntryGroupState)
+const GROUP_ESTABLISHED: i32 = 2;
+const GROUP_COLLISION: i32 = 3;
+const GROUP_FAILURE: i32 = 4;
+
+/// 一个服务类型的注册参数
+#[derive(Clone, PartialEq)]
+struct Service {
+    subtypes: Vec<String>,
+    port: u16,
+    txt_records: HashMap<String, String>,
+}
+
+/// 一台打印机的 EntryGroup
+struct Registration {
+    /// 由打印机名生成的实例名 (未加 " (n)")
+    base_name: String,
+    /// 实际注册的实例名
+    instance: String,
+    group: OwnedObjectPath,
+    /// 提交时使用的 Avahi 网卡索引
+    interfaces: Vec<i32>,
+    /// 服务类型 ("_ipp._tcp.local.") -> 服务
+    services: HashMap<String, Service>,
+}
+
+type Registrations = Arc<Mutex<HashMap<String, Registration>>>;
+
+enum Committed {
+    Done,
+    Collision,
+}
+
+pub struct AvahiBroadcaster {
+    connection: Connection,
+    /// Avahi 使用的主机名 ("host.local")
+    host_name: String,
+    config: MdnsConfig,
+    /// 打印机 ID -> 注册的服务，与状态线程共用
+    registrations: Registrations,
+    /// 处理 EntryGroup 状态变化的线程，关闭连接时结束
+    watcher: Option<thread::JoinHandle<()>>,
+}
+
+impl AvahiBroadcaster {
+    /// 连接系统总线上的 avahi-daemon，未运行时返回 None
+    pub fn connect() -> Option<Self> {
+        let connection = Connection::system().ok()?;
+        let server = Proxy::new(&connection, AVAHI_SERVICE, "/", SERVER_INTERFACE).ok()?;
+        let version: String = server.call("GetVersionString", &()).ok()?;
+        let host_name: String = server.call("GetHostNameFqdn", &()).ok()?;
+        println!("{}", t!("logs.avahi_connected", version = version, host = host_name));
+
+        let registrations = Registrations::default();
+        let watcher = watch_groups(&connection, &host_name, registrations.clone())
+            .map_err(|e| eprintln!("{}", e))
+            .ok();
+        Some(Self {
+            connection,
+            host_name,
+            config: MdnsConfig::default(),
+            registrations,
+            watcher,
+        })
+    }
+
+    /// 选中网卡的 Avahi 网卡索引。未选择时交给 Avahi 自己的 allow/deny-interfaces 设置
+    fn interface_indexes(&self) -> Vec<i32> {
+        let indexes: Vec<i32> = self.config.interfaces.iter().filter_map(|name| interface_index(name)).collect();
+        if indexes.is_empty() {
+            vec![IF_UNSPEC]
+        } else {
+            indexes
+        }
+    }
+
+    /// 按当前网卡选择重建打印机的 EntryGroup 并提交
+    fn commit(&self, printer_id: &str) -> Result<(), String> {
+        let interfaces = self.interface_indexes();
+        let mut registrations = self.registrations.lock().unwrap();
+        let Some(registration) = registrations.get_mut(printer_id) else {
+            return Ok(());
+        };
+        registration.interfaces = interfaces;
+        let current = instance_index(registration);
+        commit_group(&self.connection, registration, current)
+    }
+}
+
+impl MdnsBackend for AvahiBroadcaster {
+    fn name(&self) -> &'static str {
+        "avahi"
+    }
+
+    fn config(&self) -> &MdnsConfig {
+        &self.config
+    }
+
+    /// 网卡选择变化时按新网卡重新提交所有打印机
+    fn set_config(&mut self, config: MdnsConfig) {
+        let interfaces_changed = config.interfaces != self.config.interfaces;
+        self.config = config;
+        if !interfaces_changed {
+            return;
+        }
+        let printer_ids: Vec<String> = self.registrations.lock().unwrap().keys().cloned().collect();
+        for printer_id in printer_ids {
+            if let Err(e) = self.commit(&printer_id) {
+                eprintln!("{}", e);
+            }
+        }
+    }
+
+    /// 使用 Avahi 的主机名，地址变化后仍然有效
+    fn admin_url(&self) -> Result<String, String> {
+        Ok(format!("http://{}:631/", self.host_name))
+    }
+
+    fn register_service(
+        &mut self,
+        printer_id: &str,
+        service_type: &str,
+        subtypes: &[&str],
+        printer_name: &str,
+        port: u16,
+        txt_records: HashMap<String, String>,
+    ) -> Result<(), String> {
+        let base_name = instance_name(printer_name);
+        let service = Service {
+            subtypes: subtypes.iter().map(|s| s.to_string()).collect(),
+            port,
+            txt_records,
+        };
+
+        let mut registrations = self.registrations.lock().unwrap();
+        if !registrations.contains_key(printer_id) {
+            let group: OwnedObjectPath = call(&self.connection, "/", SERVER_INTERFACE, "EntryGroupNew", &())?;
+            registrations.insert(
+                printer_id.to_string(),
+                Registration {
+                    base_name: base_name.clone(),
+                    instance: base_name.clone(),
+                    group,
+                    interfaces: self.interface_indexes(),
+                    services: HashMap::new(),
+                },
+            );
+        }
+        let registration = registrations.get_mut(printer_id).unwrap();
+
+        let txt_only = match registration.services.get(service_type) {
+            Some(old) if *old == service => return Ok(()),
+            Some(old) => registration.base_name == base_name && old.subtypes == service.subtypes && old.port == port,
+            None => false,
+        };
+        if txt_only {
+            update_txt(&self.connection, registration, service_type, &service)?;
+            registration.services.insert(service_type.to_string(), service);
+            return Ok(());
+        }
+        // 打印机改名后重新从原名开始探测
+        if registration.base_name != base_name {
+            registration.base_name = base_name.clone();
+            registration.instance = base_name;
+        }
+        registration.services.insert(service_type.to_string(), service);
+        drop(registrations);
+        self.commit(printer_id)
+    }
+
+    fn withdraw_service(&mut self, printer_id: &str, service_type: &str) {
+        let now_empty = {
+            let mut registrations = self.registrations.lock().unwrap();
+            let Some(registration) = registrations.get_mut(printer_id) else {
+                return;
+            };
+            if registration.services.remove(service_type).is_none() {
+                return;
+            }
+            println!("{}", t!("logs.mdns_service_withdrawn", name = format!("{}.{}", registration.instance, service_type)));
+            registration.services.is_empty()
+        };
+        if now_empty {
+            self.withdraw(printer_id);
+        } else if let Err(e) = self.commit(printer_id) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// 释放 EntryGroup，Avahi 发送 goodbye 包
+    fn withdraw(&mut self, printer_id: &str) {
+        let registration = self.registrations.lock().unwrap().remove(printer_id);
+        if let Some(registration) = registration {
+            let _ = group_call::<_, ()>(&self.connection, &registration.group, "Free", &());
+            for service_type in registration.services.keys() {
+                println!("{}", t!("logs.mdns_service_withdrawn", name = format!("{}.{}", registration.instance, service_type)));
+            }
+        }
+    }
+
+    fn is_registered(&self, printer_id: &str) -> bool {
+        self.registrations.lock().unwrap().contains_key(printer_id)
+    }
+
+    /// 通过 Avahi 浏览，不再启动第二个 mDNS 响应者
+    fn browse(&self, service_types: &[&str], interface: &str) -> Result<Box<dyn ServiceBrowser>, String> {
+        let interface = match interface {
+            "" => IF_UNSPEC,
+            name => interface_index(name).unwrap_or(IF_UNSPEC),
+        };
+        Ok(Box::new(AvahiBrowser::start(service_types, interface)?))
+    }
+
+    /// Avahi 用系统主机名注册本机的全部服务
+    fn is_own_host(&self, host_name: &str) -> bool {
+        host_name.trim_end_matches('.').eq_ignore_ascii_case(&self.host_name)
+    }
+
+    /// 每个子类型一个 ServiceInfo
+    fn services(&self) -> Vec<ServiceInfo> {
+        let addrs = resolve_addresses(&SystemInterfaces, &self.config.interfaces).unwrap_or_default();
+        let host_name = format!("{}.", self.host_name);
+        let registrations = self.registrations.lock().unwrap();
+        registrations
+            .values()
+            .flat_map(|r| r.services.iter().map(move |(service_type, service)| (r, service_type, service)))
+            .flat_map(|(r, service_type, service)| {
+                let ty_domains: Vec<String> = match service.subtypes.as_slice() {
+                    [] => vec![service_type.clone()],
+                    subtypes => subtypes.iter().map(|subtype| format!("{}._sub.{}", subtype, service_type)).collect(),
+                };
+                let (addrs, host_name) = (&addrs, &host_name);
+                ty_domains.into_iter().filter_map(move |ty_domain| {
+                    ServiceInfo::new(&ty_domain, &r.instance, host_name, &addrs[..], service.port, service.txt_records.clone()).ok()
+                })
+            })
+            .collect()
+    }
+
+    fn stop(&mut self) {
+        let printer_ids: Vec<String> = self.registrations.lock().unwrap().keys().cloned().collect();
+        if !printer_ids.is_empty() {
+            for printer_id in printer_ids {
+                self.withdraw(&printer_id);
+            }
+            println!("{}", t!("logs.mdns_broadcast_stopped"));
+        }
+    }
+}
+
+impl Drop for AvahiBroadcaster {
+    fn drop(&mut self) {
+        self.stop();
+        // 关闭连接，状态线程随之结束
+        let _ = self.connection.clone().close();
+        if let Some(watcher) = self.watcher.take() {
+            let _ = watcher.join();
+        }
+    }
+}
+
+fn call<B, R>(connection: &Connection, path: &str, interface: &'static str, method: &'static str, body: &B) -> Result<R, String>
+where
+    B: Serialize + DynamicType,
+    R: DeserializeOwned + zbus::zvariant::Type,
+{
+    let failed = |e: zbus::Error| t!("errors.avahi_call_failed", method = method, error = e.to_string()).to_string();
+    let proxy = Proxy::new(connection, AVAHI_SERVICE, path, interface).map_err(failed)?;
+    proxy.call(method, body).map_err(failed)
+}
+
+fn group_call<B, R>(connection: &Connection, group: &OwnedObjectPath, method: &'static str, body: &B) -> Result<R, String>
+where
+    B: Serialize + DynamicType,
+    R: DeserializeOwned + zbus::zvariant::Type,
+{
+    call(connection, group.as_str(), ENTRY_GROUP_INTERFACE, method, body)
+}
+
+/// 当前实例名是第几个候选名 ("Name" 为 1，"Name (2)" 为 2…)
+fn instance_index(registration: &Registration) -> usize {
+    (1..=MAX_RENAME).find(|&n| with_suffix(&registration.base_name, n) == registration.instance).unwrap_or(1)
+}
+
+/// 从第 `first` 个候选名开始重建 EntryGroup 并提交，不等待探测结果。
+/// 本机其他程序已注册同名服务时依次尝试 "Name (n+1)"…；与网络上的服务重名由状态线程处理
+fn commit_group(connection: &Connection, registration: &mut Registration, first: usize) -> Result<(), String> {
+    for n in first..=MAX_RENAME {
+        let instance = with_suffix(&registration.base_name, n);
+        group_call::<_, ()>(connection, &registration.group, "Reset", &())?;
+        match add_services(connection, registration, &instance)? {
+            Committed::Done => {
+                if instance != registration.instance {
+                    println!("{}", t!("logs.mdns_name_conflict", name = registration.instance, renamed = instance));
+                }
+                registration.instance = instance;
+                return Ok(());
+            }
+            Committed::Collision => continue,
+        }
+    }
+    Err(t!("errors.avahi_call_failed", method = "Commit", error = "Collision").to_string())
+}
+
+/// 向 EntryGroup 添加全部服务和子类型并提交
+fn add_services(connection: &Connection, registration: &Registration, instance: &str) -> Result<Committed, String> {
+    let group = &registration.group;
+    for &interface in &registration.interfaces {
+        for (service_type, service) in &registration.services {
+            let avahi_type = avahi_type(service_type);
+            let added = group_call::<_, ()>(
+                connection,
+                group,
+                "AddService",
+                &(interface, PROTO_UNSPEC, 0u32, instance, avahi_type, "", "", service.port, txt_bytes(&service.txt_records)),
+            );
+            // 本机其他程序已注册同名服务
+            if matches!(&added, Err(e) if e.contains("Collision")) {
+                return Ok(Committed::Collision);
+            }
+            added?;
+            // Avahi 支持多个子类型 (mdns-sd 只支持一个)
+            for subtype in &service.subtypes {
+                group_call::<_, ()>(
+                    connection,
+                    group,
+                    "AddServiceSubtype",
+                    &(interface, PROTO_UNSPEC, 0u32, instance, avahi_type, "", format!("{}._sub.{}", subtype, avahi_type)),
+                )?;
+            }
+        }
+    }
+    group_call::<_, ()>(connection, group, "Commit", &())?;
+    Ok(Committed::Done)
+}
+
+/// 只有 TXT 记录变化时原地更新，不重新探测
+fn update_txt(connection: &Connection, registration: &Registration, service_type: &str, service: &Service) -> Result<(), String> {
+    for &interface in &registration.interfaces {
+        group_call::<_, ()>(
+            connection,
+            &registration.group,
+            "UpdateServiceTxt",
+            &(interface, PROTO_UNSPEC, 0u32, registration.instance.as_str(), avahi_type(service_type), "", txt_bytes(&service.txt_records)),
+        )?;
+    }
+    Ok(())
+}
+
+/// 处理 EntryGroup 的 StateChanged 信号 (Avahi 只发给创建它的连接)：探测完成时记录日志，
+/// 与网络上的服务重名时改用下一个名称重新提交。只在处理信号时短暂持有注册表的锁
+fn watch_groups(connection: &Connection, host_name: &str, registrations: Registrations) -> Result<thread::JoinHandle<()>, String> {
+    let failed = |e: zbus::Error| t!("errors.avahi_call_failed", method = "AddMatch", error = e.to_string()).to_string();
+    let rule = MatchRule::builder()
+        .msg_type(MessageType::Signal)
+        .interface(ENTRY_GROUP_INTERFACE)
+        .map_err(failed)?
+        .member("StateChanged")
+        .map_err(failed)?
+        .build();
+    let signals = MessageIterator::for_match_rule(rule, connection, None).map_err(failed)?;
+    let connection = connection.clone();
+    let host_name = host_name.to_string();
+
+    Ok(thread::spawn(move || {
+        for message in signals {
+            let Ok(message) = message else {
+                break;
+            };
+            let Some(path) = message.header().path().map(|path| path.to_string()) else {
+                continue;
+            };
+            let Ok((state, error)) = message.body().deserialize::<(i32, String)>() else {
+                continue;
+            };
+            let mut registrations = registrations.lock().unwrap();
+            let Some(registration) = registrations.values_mut().find(|r| r.group.as_str() == path) else {
+                continue;
+            };
+            match state {
+                GROUP_ESTABLISHED => {
+                    for (service_type, service) in &registration.services {
+                        println!(
+                            "{}",
+                            t!("logs.mdns_broadcast_success", name = format!("{}.{}", registration.instance, service_type), ip = host_name, port = service.port)
+                        );
+                    }
+                }
+                // 信号可能来自已被重新提交的旧状态，以当前状态为准
+                GROUP_COLLISION => {
+                    if !matches!(group_call::<_, i32>(&connection, &registration.group, "GetState", &()), Ok(GROUP_COLLISION)) {
+                        continue;
+                    }
+                    let next = instance_index(registration) + 1;
+                    if let Err(e) = commit_group(&connection, registration, next) {
+                        eprintln!("{}", e);
+                    }
+                }
+                GROUP_FAILURE => eprintln!("{}", t!("errors.mdns_register_failed", error = error)),
+                _ => {}
+            }
+        }
+    }))
+}
+
+/// ResolveService 的返回值：网卡、协议、名称、类型、域、主机名、地址协议、地址、端口、TXT、标志
+type Resolved = (i32, i32, String, String, String, String, i32, String, u16, Vec<Vec<u8>>, u32);
+
+/// 通过 Avahi ServiceBrowser 浏览，解析结果转换为 mdns-sd 的事件。
+/// 使用单独的连接，stop 时关闭连接即结束信号线程
+pub struct AvahiBrowser {
+    connection: Option<Connection>,
+    events: mpsc::Receiver<ServiceEvent>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl AvahiBrowser {
+    fn start(service_types: &[&str], interface: i32) -> Result<Self, String> {
+        let failed = |method: &str, e: zbus::Error| t!("errors.avahi_call_failed", method = method, error = e.to_string()).to_string();
+        let connection = Connection::system().map_err(|e| failed("Connect", e))?;
+        // 先订阅信号再创建浏览器，不会错过最初的 ItemNew
+        let rule = MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .interface(SERVICE_BROWSER_INTERFACE)
+            .map_err(|e| failed("AddMatch", e))?
+            .build();
+        let signals = MessageIterator::for_match_rule(rule, &connection, None).map_err(|e| failed("AddMatch", e))?;
+
+        let server = Proxy::new(&connection, AVAHI_SERVICE, "/", SERVER_INTERFACE).map_err(|e| failed("ServiceBrowserNew", e))?;
+        for service_type in service_types {
+            let _: OwnedObjectPath = server
+                .call("ServiceBrowserNew", &(interface, PROTO_UNSPEC, avahi_type(service_type), "", 0u32))
+                .map_err(|e| failed("ServiceBrowserNew", e))?;
+        }
+
+        let (sender, events) = mpsc::channel();
+        let worker = thread::spawn(move || forward_events(&server, signals, &sender));
+        Ok(Self { connection: Some(connection), events, worker: Some(worker) })
+    }
+}
+
+impl ServiceBrowser for AvahiBrowser {
+    fn recv_timeout(&self, timeout: Duration) -> Option<ServiceEvent> {
+        match self.events.recv_timeout(timeout) {
+            Ok(event) => Some(event),
+            // 与 avahi-daemon 的连接已断开
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                thread::sleep(timeout);
+                None
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => None,
+        }
+    }
+
+    /// 关闭连接，Avahi 随之释放浏览器
+    fn stop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            let _ = connection.close();
+        }
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for AvahiBrowser {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// 处理 ItemNew / ItemRemove 信号直到连接关闭。同一服务在每个网卡和协议上各报告一次，
+/// 合并地址后发出 ServiceResolved，全部消失时才发出 ServiceRemoved
+fn forward_events(server: &Proxy, signals: MessageIterator, events: &mpsc::Sender<ServiceEvent>) {
+    // 完整服务名 -> (网卡, 协议) -> 解析结果
+    let mut found: HashMap<String, HashMap<(i32, i32), ServiceInfo>> = HashMap::new();
+
+    for message in signals {
+        let Ok(message) = message else {
+            break;
+        };
+        let header = message.header();
+        let Some(member) = header.member() else {
+            continue;
+        };
+        let member = member.as_str().to_string();
+        if member != "ItemNew" && member != "ItemRemove" {
+            continue;
+        }
+        let Ok((interface, protocol, name, service_type, domain, _flags)) =
+            message.body().deserialize::<(i32, i32, String, String, String, u32)>()
+        else {
+            continue;
+        };
+        let ty_domain = format!("{}.{}.", service_type, domain);
+        let fullname = format!("{}.{}", name, ty_domain);
+
+        if member == "ItemRemove" {
+            let Some(entries) = found.get_mut(&fullname) else {
+                continue;
+            };
+            entries.remove(&(interface, protocol));
+            if entries.is_empty() {
+                found.remove(&fullname);
+                let _ = events.send(ServiceEvent::ServiceRemoved(ty_domain, fullname));
+            }
+            continue;
+        }
+
+        let resolved: Result<Resolved, _> = server.call("ResolveService", &(interface, protocol, name.as_str(), service_type.as_str(), domain.as_str(), PROTO_UNSPEC, 0u32));
+        let info = match resolved {
+            Ok((_, _, _, _, _, host, _, address, port, txt, _)) => {
+                ServiceInfo::new(&ty_domain, &name, &format!("{}.", host), address.as_str(), port, txt_records(&txt))
+            }
+            Err(e) => {
+                eprintln!("{}", t!("errors.avahi_call_failed", method = "ResolveService", error = e.to_string()));
+                continue;
+            }
+        };
+        let Ok(info) = info else {
+            continue;
+        };
+        let entries = found.entry(fullname).or_default();
+        entries.insert((interface, protocol), info.clone());
+
+        // 所有网卡和协议上解析到的地址
+        let mut addrs: Vec<IpAddr> = entries.values().flat_map(|i| i.get_addresses().iter().copied()).collect();
+        addrs.sort();
+        addrs.dedup();
+        let properties: HashMap<String, String> =
+            info.get_properties().iter().map(|p| (p.key().to_string(), p.val_str().to_string())).collect();
+        if let Ok(merged) = ServiceInfo::new(&ty_domain, &name, info.get_hostname(), &addrs[..], info.get_port(), properties) {
+            let _ = events.send(ServiceEvent::ServiceResolved(merged));
+        }
+    }
+}
+
+/// Avahi 的网卡索引 (与内核相同)
+fn interface_index(name: &str) -> Option<i32> {
+    std::fs::read_to_string(format!("/sys/class/net/{}/ifindex", name)).ok()?.trim().parse().ok()
+}
+
+/// TXT 字符串 "key=value" -> 键值，没有 '=' 的为布尔属性
+fn txt_records(txt: &[Vec<u8>]) -> HashMap<String, String> {
+    txt.iter()
+        .map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            match entry.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (entry.to_string(), String::new()),
+            }
+        })
+        .collect()
+}
+
+/// "_ipp._tcp.local." -> "_ipp._tcp"
+fn avahi_type(service_type: &str) -> &str {
+    let service_type = service_type.trim_end_matches('.');
+    service_type.strip_suffix(".local").unwrap_or(service_type)
+}
+
+/// TXT 字符串 "key=value"，txtvers 在前 (RFC 6763 6.7)，其余按键排序
+fn txt_bytes(txt_records: &HashMap<String, String>) -> Vec<Vec<u8>> {
+    let mut entries: Vec<(&String, &String)> = txt_records.iter().collect();
+    entries.sort_by(|a, b| (a.0 != "txtvers", a.0).cmp(&(b.0 != "txtvers", b.0)));
+    entries.into_iter().map(|(key, value)| format!("{}={}", key, value).into_bytes()).collect()
+}
@@ -0,0 +1,135 @@
+// src-tauri/src/services/backend/cups_ipp.rs
+use std::fs::File;
+use std::time::Duration;
+
+use ipp::prelude::*;
+use ipp::operation::IppOperation;
+
+use super::{BackendError, BackendJob, JobState, PrinterBackend};
+use crate::models::{Printer, PrinterCapabilities};
+use crate::services::{capabilities, cups};
+use crate::services::ipp::attributes;
+
+/// 查询超时，打印机停止响应时不一直占住请求
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// 提交任务的超时，需要上传整个文档
+const SUBMIT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 通过 IPP 直接与 CUPS 队列 (或任意 IPP 打印机) 通信，不依赖 `lp` 命令
+pub struct CupsIppBackend {
+    printer_uri: String,
+}
+
+impl CupsIppBackend {
+    pub fn new(printer_uri: &str) -> Self {
+        Self {
+            printer_uri: printer_uri.to_string(),
+        }
+    }
+
+    /// 本机 CUPS 上的队列
+    pub fn local(queue: &str) -> Self {
+        Self::new(&cups::printer_uri(&cups::local_server(), queue))
+    }
+
+    /// 打印机所在 CUPS 服务器上的队列，远程队列的任务转发给其服务器
+    pub fn for_printer(printer: &Printer) -> Self {
+        if printer.details.origin.is_empty() {
+            Self::local(&printer.name)
+        } else {
+            Self::new(&cups::printer_uri(&printer.details.origin, &printer.name))
+        }
+    }
+
+    fn uri(&self) -> Result<Uri, BackendError> {
+        self.printer_uri
+            .parse::<Uri>()
+            .map_err(|e| BackendError::Unavailable(e.to_string()))
+    }
+
+    fn send(&self, op: impl IppOperation, timeout: Duration) -> Result<IppRequestResponse, BackendError> {
+        let client = IppClient::builder(self.uri()?).request_timeout(timeout).build();
+        let resp = client
+            .send(op)
+            .map_err(|e| BackendError::Unavailable(e.to_string()))?;
+
+        let status = resp.header().status_code();
+        if status.is_success() {
+            Ok(resp)
+        } else if status == StatusCode::ClientErrorNotFound {
+            Err(BackendError::JobNotFound(format!("{:?}", status)))
+        } else {
+            Err(BackendError::Rejected(format!("{:?}", status)))
+        }
+    }
+
+    pub fn printer_uri(&self) -> &str {
+        &self.printer_uri
+    }
+
+    /// Get-Printer-Attributes，返回 printer-attributes 组中的全部属性
+    pub fn query_printer_attributes(&self) -> Result<Vec<IppAttribute>, BackendError> {
+        let op = IppOperationBuilder::get_printer_attributes(self.uri()?).build();
+        let resp = self.send(op, REQUEST_TIMEOUT)?;
+
+        Ok(resp
+            .attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .flat_map(|group| group.attributes().values().cloned())
+            .collect())
+    }
+
+    fn parse_job_id(job_id: &str) -> Result<i32, BackendError> {
+        job_id
+            .parse()
+            .map_err(|_| BackendError::JobNotFound(job_id.to_string()))
+    }
+}
+
+impl PrinterBackend for CupsIppBackend {
+    fn name(&self) -> &'static str {
+        "cups-ipp"
+    }
+
+    fn submit(&self, job: &BackendJob) -> Result<String, BackendError> {
+        let payload = IppPayload::new(File::open(&job.document)?);
+        let mut builder = IppOperationBuilder::print_job(self.uri()?, payload)
+            .user_name(&job.user)
+            .job_title(&job.name)
+            .document_format(&job.document_format)
+            .attribute(IppAttribute::new("copies", IppValue::Integer(job.options.copies)))
+            .attribute(IppAttribute::new("sides", IppValue::Keyword(job.options.sides.clone())))
+            .attribute(IppAttribute::new("print-color-mode", IppValue::Keyword(job.options.color_mode.clone())));
+        if !job.options.media.is_empty() {
+            builder = builder.attribute(IppAttribute::new("media", IppValue::Keyword(job.options.media.clone())));
+        }
+        let op = builder.build();
+
+        let resp = self.send(op, SUBMIT_TIMEOUT)?;
+        let job_id = attributes::find(&resp, DelimiterTag::JobAttributes, "job-id")
+            .and_then(attributes::as_int)
+            .ok_or_else(|| BackendError::Rejected("missing job-id".to_string()))?;
+
+        Ok(job_id.to_string())
+    }
+
+    fn status(&self, job_id: &str) -> Result<JobState, BackendError> {
+        let op = IppOperationBuilder::get_job_attributes(self.uri()?, Self::parse_job_id(job_id)?).build();
+        let resp = self.send(op, REQUEST_TIMEOUT)?;
+
+        Ok(attributes::find(&resp, DelimiterTag::JobAttributes, "job-state")
+            .and_then(attributes::as_int)
+            .map(JobState::from_ipp_value)
+            .unwrap_or(JobState::Processing))
+    }
+
+    fn cancel(&self, job_id: &str) -> Result<(), BackendError> {
+        let op = IppOperationBuilder::cancel_job(self.uri()?, Self::parse_job_id(job_id)?).build();
+        self.send(op, REQUEST_TIMEOUT).map(|_| ())
+    }
+
+    fn capabilities(&self) -> Result<PrinterCapabilities, BackendError> {
+        let attrs = self.query_printer_attributes()?;
+        Ok(capabilities::from_ipp_attributes(|name| attrs.iter().find(|a| a.name() == name).map(|a| a.value())))
+    }
+}
@@ -0,0 +1,115 @@
+// src-tauri/src/services/backend/folder.rs
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use rust_i18n::t;
+
+use super::{BackendError, BackendJob, JobState, PrinterBackend};
+use crate::models::{Printer, PrinterCapabilities, PrinterDetails, PrinterState, PrinterStatus, VirtualPrinterConfig};
+use crate::services::pdf;
+
+/// 虚拟打印机的固定 ID
+pub const VIRTUAL_PRINTER_ID: &str = "virtual-pdf";
+
+/// 虚拟打印机在打印机列表中的条目
+pub fn virtual_printer(config: &VirtualPrinterConfig) -> Printer {
+    Printer {
+        name: config.name.clone(),
+        id: VIRTUAL_PRINTER_ID.to_string(),
+        status: PrinterStatus::Online,
+        details: PrinterDetails::default(),
+        state: PrinterState::default(),
+        capabilities: None,
+    }
+}
+
+/// "保存到文件夹"：任务转换成 PDF 后写入输出目录
+pub struct FolderBackend {
+    config: VirtualPrinterConfig,
+}
+
+impl FolderBackend {
+    pub fn new(config: VirtualPrinterConfig) -> Self {
+        Self { config }
+    }
+
+    /// 按模板生成文件名 (不含扩展名)
+    pub fn render_file_name(template: &str, job: &BackendJob) -> String {
+        let now = Local::now();
+        let name = template
+            .replace("{job}", &job.name)
+            .replace("{user}", &job.user)
+            .replace("{device}", &job.device)
+            .replace("{timestamp}", &now.format("%Y%m%d-%H%M%S").to_string())
+            .replace("{date}", &now.format("%Y-%m-%d").to_string())
+            .replace("{time}", &now.format("%H-%M-%S").to_string())
+            .replace("{id}", &job.id.to_string());
+
+        let name: String = name
+            .chars()
+            .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control() { '_' } else { c })
+            .collect();
+        let name = name.trim().trim_matches('.').to_string();
+
+        if name.is_empty() { format!("job-{}", job.id) } else { name }
+    }
+
+    /// 同名文件已存在时追加 " (2)"、" (3)"…
+    fn unique_path(dir: &Path, stem: &str) -> PathBuf {
+        let mut path = dir.join(format!("{}.pdf", stem));
+        let mut n = 2;
+        while path.exists() {
+            path = dir.join(format!("{} ({}).pdf", stem, n));
+            n += 1;
+        }
+        path
+    }
+}
+
+impl PrinterBackend for FolderBackend {
+    fn name(&self) -> &'static str {
+        "folder"
+    }
+
+    fn submit(&self, job: &BackendJob) -> Result<String, BackendError> {
+        let data = fs::read(&job.document)?;
+        let pdf_data = pdf::to_pdf(&job.document_format, &data).map_err(BackendError::Rejected)?;
+
+        let dir = PathBuf::from(&self.config.output_dir);
+        fs::create_dir_all(&dir)?;
+
+        let stem = Self::render_file_name(&self.config.file_name_template, job);
+        let path = Self::unique_path(&dir, &stem);
+        fs::write(&path, pdf_data)?;
+
+        println!("{}", t!("logs.folder_job_saved", path = path.display()));
+        Ok(path.to_string_lossy().to_string())
+    }
+
+    fn status(&self, job_id: &str) -> Result<JobState, BackendError> {
+        // 写入即完成
+        if Path::new(job_id).exists() {
+            Ok(JobState::Completed)
+        } else {
+            Err(BackendError::JobNotFound(job_id.to_string()))
+        }
+    }
+
+    fn cancel(&self, _job_id: &str) -> Result<(), BackendError> {
+        Err(BackendError::Unsupported("cancel"))
+    }
+
+    fn capabilities(&self) -> Result<PrinterCapabilities, BackendError> {
+        Ok(PrinterCapabilities {
+            color: true,
+            duplex: false,
+            document_formats: vec![
+                "application/pdf".to_string(),
+                "image/urf".to_string(),
+                "image/jpeg".to_string(),
+            ],
+            ..PrinterCapabilities::default()
+        })
+    }
+}
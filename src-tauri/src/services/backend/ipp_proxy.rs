@@ -0,0 +1,85 @@
+// src-tauri/src/services/backend/ipp_proxy.rs
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use ipp::attribute::IppAttribute;
+
+use super::{BackendError, BackendJob, CupsIppBackend, JobState, PrinterBackend};
+use crate::models::PrinterCapabilities;
+use crate::services::capabilities;
+
+/// 下游打印机属性的缓存时间
+const ATTRIBUTE_CACHE_TTL: Duration = Duration::from_secs(30);
+/// 查询失败的缓存时间，下游打印机无响应时不让每个请求都等到超时
+const FAILURE_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// 最近一次查询下游打印机属性的结果
+enum Cached {
+    Attributes(Vec<IppAttribute>),
+    Failed(String),
+}
+
+/// IPP 代理：把任务转发给手机无法直接访问的下游 IPP 打印机，
+/// 并把下游的打印机属性和任务状态转述给客户端
+pub struct IppProxyBackend {
+    downstream: CupsIppBackend,
+    attributes: Mutex<Option<(Instant, Cached)>>,
+}
+
+impl IppProxyBackend {
+    pub fn new(downstream_uri: &str) -> Self {
+        Self {
+            downstream: CupsIppBackend::new(downstream_uri),
+            attributes: Mutex::new(None),
+        }
+    }
+
+    pub fn downstream_uri(&self) -> &str {
+        self.downstream.printer_uri()
+    }
+}
+
+impl PrinterBackend for IppProxyBackend {
+    fn name(&self) -> &'static str {
+        "ipp-proxy"
+    }
+
+    fn submit(&self, job: &BackendJob) -> Result<String, BackendError> {
+        self.downstream.submit(job)
+    }
+
+    fn status(&self, job_id: &str) -> Result<JobState, BackendError> {
+        self.downstream.status(job_id)
+    }
+
+    fn cancel(&self, job_id: &str) -> Result<(), BackendError> {
+        self.downstream.cancel(job_id)
+    }
+
+    /// 由缓存的下游属性推导
+    fn capabilities(&self) -> Result<PrinterCapabilities, BackendError> {
+        let attrs = self.printer_attributes()?;
+        Ok(capabilities::from_ipp_attributes(|name| attrs.iter().find(|a| a.name() == name).map(|a| a.value())))
+    }
+
+    /// 查询下游打印机时不持有缓存的锁，其他请求不必等待
+    fn printer_attributes(&self) -> Result<Vec<IppAttribute>, BackendError> {
+        match self.attributes.lock().unwrap().as_ref() {
+            Some((fetched_at, Cached::Attributes(attrs))) if fetched_at.elapsed() < ATTRIBUTE_CACHE_TTL => {
+                return Ok(attrs.clone())
+            }
+            Some((fetched_at, Cached::Failed(e))) if fetched_at.elapsed() < FAILURE_CACHE_TTL => {
+                return Err(BackendError::Unavailable(e.clone()))
+            }
+            _ => {}
+        }
+
+        let result = self.downstream.query_printer_attributes();
+        let cached = match &result {
+            Ok(attrs) => Cached::Attributes(attrs.clone()),
+            Err(e) => Cached::Failed(e.to_string()),
+        };
+        *self.attributes.lock().unwrap() = Some((Instant::now(), cached));
+        result
+    }
+}
@@ -0,0 +1,104 @@
+// src-tauri/src/services/backend/lp.rs
+use std::process::Command;
+
+use rust_i18n::t;
+
+use super::{BackendError, BackendJob, CupsIppBackend, JobState, PrinterBackend};
+use crate::models::PrinterCapabilities;
+use crate::services::{capabilities, ppd};
+
+/// CUPS `lp` 命令后端 (macOS / Linux)
+pub struct LpBackend {
+    queue: String,
+}
+
+impl LpBackend {
+    pub fn new(queue: &str) -> Self {
+        Self {
+            queue: queue.to_string(),
+        }
+    }
+
+    /// 固定使用 C locale，`lp` 的 "request id is" 和 `lpstat -o` 的输出不随系统语言变化
+    fn run(cmd: &mut Command) -> Result<String, BackendError> {
+        let out = cmd
+            .env("LC_ALL", "C")
+            .env("LANG", "C")
+            .output()
+            .map_err(|e| BackendError::Unavailable(e.to_string()))?;
+
+        if out.status.success() {
+            Ok(String::from_utf8_lossy(&out.stdout).to_string())
+        } else {
+            Err(BackendError::Rejected(String::from_utf8_lossy(&out.stderr).trim().to_string()))
+        }
+    }
+}
+
+/// 解析 `lp` 输出中的任务 ID，例如 "request id is HP-12 (1 file(s))"
+fn parse_request_id(stdout: &str) -> Option<String> {
+    stdout
+        .split_whitespace()
+        .find(|word| word.rsplit_once('-').is_some_and(|(_, n)| n.parse::<u32>().is_ok()))
+        .map(|s| s.to_string())
+}
+
+impl PrinterBackend for LpBackend {
+    fn name(&self) -> &'static str {
+        "lp"
+    }
+
+    fn submit(&self, job: &BackendJob) -> Result<String, BackendError> {
+        let mut cmd = Command::new("lp");
+        cmd.arg("-d").arg(&self.queue);
+        cmd.arg("-t").arg(&job.name);
+        if job.options.copies > 1 {
+            cmd.arg("-n").arg(job.options.copies.to_string());
+        }
+        if job.options.sides.starts_with("two-sided") {
+            cmd.arg("-o").arg(format!("sides={}", job.options.sides));
+        }
+        if job.options.color_mode == "monochrome" {
+            cmd.arg("-o").arg("print-color-mode=monochrome");
+        }
+        if !job.options.media.is_empty() {
+            cmd.arg("-o").arg(format!("media={}", job.options.media));
+        }
+        cmd.arg(&job.document);
+
+        let stdout = Self::run(&mut cmd)?;
+        println!("{}", t!("logs.ipp_print_success_lp"));
+
+        Ok(parse_request_id(&stdout).unwrap_or_else(|| format!("{}-{}", self.queue, job.id)))
+    }
+
+    fn status(&self, job_id: &str) -> Result<JobState, BackendError> {
+        // 未完成的任务会出现在 `lpstat -o` 中，否则视为已完成
+        let stdout = Self::run(Command::new("lpstat").arg("-o").arg(&self.queue))?;
+        let active = stdout
+            .lines()
+            .any(|line| line.split_whitespace().next() == Some(job_id));
+
+        Ok(if active { JobState::Processing } else { JobState::Completed })
+    }
+
+    fn cancel(&self, job_id: &str) -> Result<(), BackendError> {
+        Self::run(Command::new("cancel").arg(job_id)).map(|_| ())
+    }
+
+    /// 向本机 CUPS 查询队列的能力，CUPS 没有报告时读 PPD / lpoptions
+    fn capabilities(&self) -> Result<PrinterCapabilities, BackendError> {
+        let reported = CupsIppBackend::local(&self.queue).query_printer_attributes().map(|attrs| {
+            attrs
+                .iter()
+                .any(|a| capabilities::IPP_ATTRIBUTE_NAMES.contains(&a.name()))
+                .then(|| capabilities::from_ipp_attributes(|name| attrs.iter().find(|a| a.name() == name).map(|a| a.value())))
+        });
+        match reported {
+            Ok(Some(caps)) => Ok(caps),
+            Ok(None) => ppd::queue_capabilities(&self.queue)
+                .ok_or_else(|| BackendError::Unavailable(format!("no capabilities reported for {}", self.queue))),
+            Err(e) => ppd::queue_capabilities(&self.queue).ok_or(e),
+        }
+    }
+}
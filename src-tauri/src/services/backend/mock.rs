@@ -0,0 +1,105 @@
+// src-tauri/src/services/backend/mock.rs
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use super::{BackendError, BackendJob, JobState, PrinterBackend};
+use crate::models::PrinterCapabilities;
+
+/// 模拟后端收到的任务 (文档内容在提交时读入内存)
+#[derive(Debug, Clone)]
+pub struct MockJob {
+    pub job: BackendJob,
+    pub data: Vec<u8>,
+    pub state: JobState,
+}
+
+#[derive(Default)]
+struct MockState {
+    jobs: HashMap<String, MockJob>,
+    fail_with: Option<String>,
+}
+
+/// 不输出到任何设备的后端，在没有真实打印机的机器上跑通整个 IPP 流程。
+/// 可以 clone 一份留在外面观察收到的任务。
+#[derive(Clone, Default)]
+pub struct MockBackend {
+    state: Arc<Mutex<MockState>>,
+    capabilities: PrinterCapabilities,
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capabilities(capabilities: PrinterCapabilities) -> Self {
+        Self {
+            capabilities,
+            ..Self::default()
+        }
+    }
+
+    /// 之后的提交都以给定错误拒绝，传 None 恢复正常
+    pub fn fail_with(&self, error: Option<&str>) {
+        self.state.lock().unwrap().fail_with = error.map(|e| e.to_string());
+    }
+
+    pub fn jobs(&self) -> Vec<MockJob> {
+        let mut jobs: Vec<MockJob> = self.state.lock().unwrap().jobs.values().cloned().collect();
+        jobs.sort_by_key(|j| j.job.id);
+        jobs
+    }
+
+    /// 手动推进任务状态
+    pub fn set_state(&self, job_id: &str, state: JobState) {
+        if let Some(job) = self.state.lock().unwrap().jobs.get_mut(job_id) {
+            job.state = state;
+        }
+    }
+}
+
+impl PrinterBackend for MockBackend {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn submit(&self, job: &BackendJob) -> Result<String, BackendError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(err) = &state.fail_with {
+            return Err(BackendError::Rejected(err.clone()));
+        }
+
+        let data = std::fs::read(&job.document)?;
+        let id = format!("mock-{}", job.id);
+        state.jobs.insert(id.clone(), MockJob {
+            job: job.clone(),
+            data,
+            state: JobState::Completed,
+        });
+        Ok(id)
+    }
+
+    fn status(&self, job_id: &str) -> Result<JobState, BackendError> {
+        self.state
+            .lock()
+            .unwrap()
+            .jobs
+            .get(job_id)
+            .map(|j| j.state)
+            .ok_or_else(|| BackendError::JobNotFound(job_id.to_string()))
+    }
+
+    fn cancel(&self, job_id: &str) -> Result<(), BackendError> {
+        match self.state.lock().unwrap().jobs.get_mut(job_id) {
+            Some(job) => {
+                job.state = JobState::Cancelled;
+                Ok(())
+            }
+            None => Err(BackendError::JobNotFound(job_id.to_string())),
+        }
+    }
+
+    fn capabilities(&self) -> Result<PrinterCapabilities, BackendError> {
+        Ok(self.capabilities.clone())
+    }
+}
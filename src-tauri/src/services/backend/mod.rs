@@ -0,0 +1,222 @@
+// src-tauri/src/services/backend/mod.rs
+//
+// 打印后端：IPP 服务器收到的任务最终交给某个 PrinterBackend 输出。
+// 每台共享的打印机各自绑定一个后端。
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use ipp::attribute::IppAttribute;
+use serde::{Deserialize, Serialize};
+use rust_i18n::t;
+
+use crate::models::{Printer, PrinterCapabilities};
+use crate::services::printer_detector::USB_PRINTER_ID_PREFIX;
+
+pub mod lp;
+pub mod cups_ipp;
+pub mod mock;
+pub mod folder;
+pub mod ipp_proxy;
+pub mod usb_raw;
+#[cfg(target_os = "windows")]
+pub mod windows;
+
+pub use lp::LpBackend;
+pub use cups_ipp::CupsIppBackend;
+pub use mock::MockBackend;
+pub use folder::FolderBackend;
+pub use ipp_proxy::IppProxyBackend;
+pub use usb_raw::UsbRawBackend;
+#[cfg(target_os = "windows")]
+pub use windows::WindowsBackend;
+
+/// 从 IPP 请求中解析出的打印选项
+#[derive(Debug, Clone)]
+pub struct PrintOptions {
+    pub copies: i32,
+    pub sides: String,
+    pub color_mode: String,
+    /// 纸张 (如 "iso_a4_210x297mm")，为空时使用打印机的默认纸张
+    pub media: String,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            copies: 1,
+            sides: "one-sided".to_string(),
+            color_mode: "auto".to_string(),
+            media: String::new(),
+        }
+    }
+}
+
+/// 交给后端的打印任务，文档已落盘到 `document`
+#[derive(Debug, Clone)]
+pub struct BackendJob {
+    /// 本地 job-id (由 QueueRegistry 分配)
+    pub id: u32,
+    pub name: String,
+    pub user: String,
+    /// 发起打印的设备 (客户端地址)
+    pub device: String,
+    pub document_format: String,
+    pub document: PathBuf,
+    pub options: PrintOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    Pending,
+    Processing,
+    Completed,
+    Cancelled,
+    Aborted,
+}
+
+impl JobState {
+    /// 对应 IPP job-state 枚举值 (RFC 8011 5.3.7)
+    pub fn ipp_value(&self) -> i32 {
+        match self {
+            JobState::Pending => 3,
+            JobState::Processing => 5,
+            JobState::Cancelled => 7,
+            JobState::Aborted => 8,
+            JobState::Completed => 9,
+        }
+    }
+
+    pub fn from_ipp_value(value: i32) -> Self {
+        match value {
+            3 | 4 => JobState::Pending,
+            5 | 6 => JobState::Processing,
+            7 => JobState::Cancelled,
+            8 => JobState::Aborted,
+            _ => JobState::Completed,
+        }
+    }
+
+    /// job-state-reasons 的默认关键字
+    pub fn ipp_reason(&self) -> &'static str {
+        match self {
+            JobState::Pending => "none",
+            JobState::Processing => "job-printing",
+            JobState::Completed => "job-completed-successfully",
+            JobState::Cancelled => "job-canceled-by-user",
+            JobState::Aborted => "aborted-by-system",
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobState::Completed | JobState::Cancelled | JobState::Aborted)
+    }
+}
+
+#[derive(Debug)]
+pub enum BackendError {
+    /// 后端不可用：命令不存在、连接失败等
+    Unavailable(String),
+    /// 打印系统拒绝了任务
+    Rejected(String),
+    JobNotFound(String),
+    /// 该后端不支持此操作
+    Unsupported(&'static str),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            BackendError::Unavailable(e) => t!("errors.backend_unavailable", error = e),
+            BackendError::Rejected(e) => t!("errors.backend_rejected", error = e),
+            BackendError::JobNotFound(id) => t!("errors.backend_job_not_found", id = id),
+            BackendError::Unsupported(op) => t!("errors.backend_unsupported", op = op),
+            BackendError::Io(e) => t!("errors.backend_io", error = e.to_string()),
+        };
+        f.write_str(&msg)
+    }
+}
+
+impl std::error::Error for BackendError {}
+
+impl From<std::io::Error> for BackendError {
+    fn from(e: std::io::Error) -> Self {
+        BackendError::Io(e)
+    }
+}
+
+pub trait PrinterBackend: Send + Sync {
+    /// 后端名称，用于日志
+    fn name(&self) -> &'static str;
+
+    /// 提交任务，返回后端自己的任务 ID
+    fn submit(&self, job: &BackendJob) -> Result<String, BackendError>;
+
+    fn status(&self, job_id: &str) -> Result<JobState, BackendError>;
+
+    fn cancel(&self, job_id: &str) -> Result<(), BackendError>;
+
+    fn capabilities(&self) -> Result<PrinterCapabilities, BackendError>;
+
+    /// 需要原样写入 Get-Printer-Attributes 响应的打印机属性 (IPP 代理镜像下游打印机)
+    fn printer_attributes(&self) -> Result<Vec<IppAttribute>, BackendError> {
+        Ok(Vec::new())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackendKind {
+    /// CUPS `lp` 命令
+    Lp,
+    /// 直接通过 IPP 与本机或远程 CUPS 通信
+    CupsIpp,
+    /// Windows 打印对话框 (PowerShell)
+    Windows,
+    /// 直接写入 USB 打印机设备节点 (未配置为 CUPS 队列的打印机)
+    UsbRaw,
+    Mock,
+}
+
+impl BackendKind {
+    pub fn platform_default() -> Self {
+        if cfg!(target_os = "windows") {
+            BackendKind::Windows
+        } else {
+            BackendKind::Lp
+        }
+    }
+
+    /// 打印机的默认后端：直接检测到的 USB 打印机没有 CUPS 队列，只能 raw 输出；
+    /// 远程 CUPS 上的队列通过 IPP 转发
+    pub fn default_for(printer: &Printer) -> Self {
+        if printer.id.starts_with(USB_PRINTER_ID_PREFIX) {
+            BackendKind::UsbRaw
+        } else if !printer.details.origin.is_empty() {
+            BackendKind::CupsIpp
+        } else {
+            Self::platform_default()
+        }
+    }
+}
+
+/// 为打印机创建指定类型的后端
+pub fn create(kind: BackendKind, printer: &Printer) -> Result<Arc<dyn PrinterBackend>, BackendError> {
+    match kind {
+        // lp 只能提交到本机队列
+        BackendKind::Lp if !printer.details.origin.is_empty() => Err(BackendError::Unsupported("lp")),
+        BackendKind::Lp => Ok(Arc::new(LpBackend::new(&printer.name))),
+        BackendKind::CupsIpp => Ok(Arc::new(CupsIppBackend::for_printer(printer))),
+        #[cfg(target_os = "windows")]
+        BackendKind::Windows => Ok(Arc::new(WindowsBackend::new(&printer.name))),
+        #[cfg(not(target_os = "windows"))]
+        BackendKind::Windows => Err(BackendError::Unsupported("windows")),
+        BackendKind::UsbRaw => Ok(Arc::new(UsbRawBackend::new(
+            &printer.id,
+            printer.capabilities.clone().unwrap_or_default(),
+        ))),
+        BackendKind::Mock => Ok(Arc::new(MockBackend::new())),
+    }
+}
@@ -0,0 +1,80 @@
+// src-tauri/src/services/backend/usb_raw.rs
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use rust_i18n::t;
+
+use super::{BackendError, BackendJob, JobState, PrinterBackend};
+use crate::models::PrinterCapabilities;
+use crate::services::usb;
+
+/// 不经过 CUPS，把文档原样写入 USB 打印机的设备节点 (/dev/usb/lp*)。
+/// 打印机必须能直接识别文档格式 (PDF、PostScript、PCL 等)
+pub struct UsbRawBackend {
+    printer_id: String,
+    sysfs_root: PathBuf,
+    dev_root: PathBuf,
+    capabilities: PrinterCapabilities,
+    /// 已写入的任务，写入完成即视为完成
+    jobs: Mutex<HashSet<String>>,
+}
+
+impl UsbRawBackend {
+    pub fn new(printer_id: &str, capabilities: PrinterCapabilities) -> Self {
+        Self::with_roots(printer_id, capabilities, usb::SYSFS_ROOT, usb::DEV_ROOT)
+    }
+
+    /// 指定 sysfs 和 /dev 的根目录
+    pub fn with_roots(printer_id: &str, capabilities: PrinterCapabilities, sysfs_root: impl Into<PathBuf>, dev_root: impl Into<PathBuf>) -> Self {
+        Self {
+            printer_id: printer_id.to_string(),
+            sysfs_root: sysfs_root.into(),
+            dev_root: dev_root.into(),
+            capabilities,
+            jobs: Mutex::new(HashSet::new()),
+        }
+    }
+}
+
+impl PrinterBackend for UsbRawBackend {
+    fn name(&self) -> &'static str {
+        "usb-raw"
+    }
+
+    fn submit(&self, job: &BackendJob) -> Result<String, BackendError> {
+        if !self.capabilities.document_formats.contains(&job.document_format) {
+            return Err(BackendError::Rejected(job.document_format.clone()));
+        }
+        // 每次提交时重新查找节点，打印机重新插拔后节点号可能变化
+        let device = usb::find_device(&self.sysfs_root, &self.dev_root, &self.printer_id)
+            .ok_or_else(|| BackendError::Unavailable(t!("errors.usb_device_not_found", id = self.printer_id).to_string()))?;
+
+        let mut output = OpenOptions::new().write(true).open(&device)?;
+        let bytes = io::copy(&mut File::open(&job.document)?, &mut output)?;
+        output.sync_all().ok();
+        println!("{}", t!("logs.usb_job_written", bytes = bytes, device = device.display()));
+
+        let job_id = job.id.to_string();
+        self.jobs.lock().unwrap().insert(job_id.clone());
+        Ok(job_id)
+    }
+
+    fn status(&self, job_id: &str) -> Result<JobState, BackendError> {
+        if self.jobs.lock().unwrap().contains(job_id) {
+            Ok(JobState::Completed)
+        } else {
+            Err(BackendError::JobNotFound(job_id.to_string()))
+        }
+    }
+
+    fn cancel(&self, _job_id: &str) -> Result<(), BackendError> {
+        Err(BackendError::Unsupported("cancel"))
+    }
+
+    fn capabilities(&self) -> Result<PrinterCapabilities, BackendError> {
+        Ok(self.capabilities.clone())
+    }
+}
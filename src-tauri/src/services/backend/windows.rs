@@ -0,0 +1,156 @@
+// src-tauri/src/services/backend/windows.rs
+use std::os::windows::process::CommandExt;
+use std::path::Path;
+use std::process::Command;
+
+use rust_i18n::t;
+
+use super::{BackendError, BackendJob, JobState, PrinterBackend};
+use crate::models::PrinterCapabilities;
+
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Windows: 弹出标准打印对话框 (默认选中共享的打印机)，再用 shell "Print" 动词打印
+pub struct WindowsBackend {
+    printer_name: String,
+}
+
+impl WindowsBackend {
+    pub fn new(printer_name: &str) -> Self {
+        Self {
+            printer_name: printer_name.to_string(),
+        }
+    }
+
+    fn fallback_print(filepath: &Path) -> Result<(), BackendError> {
+        // 最后的降级方案：直接用默认程序打开
+        let path_str = filepath.to_string_lossy();
+        println!("{}", t!("logs.ipp_fallback_open", file = path_str));
+
+        Command::new("cmd")
+            .args(["/C", "start", "", &path_str])
+            .creation_flags(CREATE_NO_WINDOW)
+            .spawn()
+            .map_err(|e| BackendError::Unavailable(t!("errors.ipp_fallback_failed", error = e.to_string()).to_string()))?;
+
+        Ok(())
+    }
+}
+
+impl PrinterBackend for WindowsBackend {
+    fn name(&self) -> &'static str {
+        "windows"
+    }
+
+    fn submit(&self, job: &BackendJob) -> Result<String, BackendError> {
+        let filepath = &job.document;
+        let path_str = filepath.to_string_lossy();
+
+        let ps_script = format!(
+                        r#"
+            Add-Type -AssemblyName System.Windows.Forms
+            Add-Type -AssemblyName System.Drawing
+
+            $form = New-Object System.Windows.Forms.Form
+            $form.WindowState = 'Minimized'  # 隐藏辅助窗口
+            $form.ShowInTaskbar = $false
+
+            $printDialog = New-Object System.Windows.Forms.PrintDialog
+            $printDialog.UseEXDialog = $true  # 使用现代样式的打印对话框
+            $printDialog.AllowSomePages = $true
+            $printDialog.AllowSelection = $true
+
+            # 创建 PrintDocument 来承载设置
+            $printDoc = New-Object System.Drawing.Printing.PrintDocument
+            $printDialog.Document = $printDoc
+
+            # 设置默认值（从 IPP 请求传递过来的）
+            $printDoc.PrinterSettings.PrinterName = "{printer}"
+            $printDoc.PrinterSettings.Copies = {copies}
+
+            # 显示打印对话框
+            $result = $printDialog.ShowDialog($form)
+
+            if ($result -eq [System.Windows.Forms.DialogResult]::OK) {{
+                $printer = $printDoc.PrinterSettings.PrinterName
+                $copies = $printDoc.PrinterSettings.Copies
+
+                Write-Host "Selected printer: $printer"
+                Write-Host "Copies: $copies"
+
+                # 使用选择的打印机打印文件
+                # 方法1: 使用 WMI 设置默认打印机后打印
+                $wsnet = New-Object -ComObject WScript.Network
+                $originalPrinter = $wsnet.EnumPrinterConnections() | Select-Object -Index 1
+
+                try {{
+                    $wsnet.SetDefaultPrinter($printer)
+
+                    # 现在用默认动词打印
+                    $psi = New-Object System.Diagnostics.ProcessStartInfo
+                    $psi.FileName = "{path}"
+                    $psi.Verb = "Print"
+                    $psi.UseShellExecute = $true
+                    $psi.WindowStyle = 'Hidden'
+
+                    $proc = [System.Diagnostics.Process]::Start($psi)
+                    $proc.WaitForExit()
+
+                    Write-Host "PRINT_SUCCESS"
+                }} finally {{
+                    # 恢复原来的默认打印机
+                    if ($originalPrinter) {{
+                        $wsnet.SetDefaultPrinter($originalPrinter)
+                    }}
+                }}
+            }} else {{
+                Write-Host "PRINT_CANCELLED"
+            }}
+
+            $form.Close()
+            "#,
+            path = path_str.replace("\\", "\\\\").replace("\"", "`\""),
+            printer = self.printer_name.replace("\"", "`\""),
+            copies = job.options.copies
+        );
+
+        match Command::new("powershell")
+            .args(["-NoProfile", "-Sta", "-ExecutionPolicy", "Bypass", "-Command", &ps_script])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+        {
+            Ok(out) => {
+                let stdout = String::from_utf8_lossy(&out.stdout);
+                if stdout.contains("PRINT_SUCCESS") {
+                    println!("{}", t!("logs.ipp_print_success"));
+                } else if stdout.contains("PRINT_CANCELLED") {
+                    println!("{}", t!("logs.ipp_print_cancelled"));
+                    return Err(BackendError::Rejected(t!("logs.ipp_print_cancelled").to_string()));
+                } else {
+                    eprintln!("Print dialog error: {}", String::from_utf8_lossy(&out.stderr));
+                    Self::fallback_print(filepath)?;
+                }
+            }
+            Err(e) => {
+                eprintln!("Failed to start print dialog: {}", e);
+                Self::fallback_print(filepath)?;
+            }
+        }
+
+        Ok(format!("win-{}", job.id))
+    }
+
+    fn status(&self, _job_id: &str) -> Result<JobState, BackendError> {
+        // 交给 shell 打印后无法再跟踪
+        Err(BackendError::Unsupported("status"))
+    }
+
+    fn cancel(&self, _job_id: &str) -> Result<(), BackendError> {
+        Err(BackendError::Unsupported("cancel"))
+    }
+
+    fn capabilities(&self) -> Result<PrinterCapabilities, BackendError> {
+        // 无法读取驱动的能力，按保守默认值 (黑白、单面、PDF) 广播
+        Ok(PrinterCapabilities::default())
+    }
+}
@@ -0,0 +1,209 @@
+// src-tauri/src/services/capabilities.rs
+//
+// 由 PrinterCapabilities 生成 mDNS TXT 记录和 IPP 打印机属性。
+// 两处共用同一份推导，保证广播内容与 Get-Printer-Attributes 一致。
+
+use ipp::attribute::IppAttribute;
+use ipp::value::IppValue;
+
+use crate::models::PrinterCapabilities;
+use crate::services::ipp::attributes;
+
+/// 推导能力用到的 IPP 打印机属性
+pub const IPP_ATTRIBUTE_NAMES: &[&str] = &[
+    "color-supported",
+    "sides-supported",
+    "document-format-supported",
+    "media-supported",
+    "printer-resolution-supported",
+];
+
+/// AirPrint PaperMax 取值 (从小到大)
+pub const PAPER_MAX_SMALL: &str = "<legal-A4";
+pub const PAPER_MAX_LEGAL_A4: &str = "legal-A4";
+pub const PAPER_MAX_ISOC_A2: &str = "isoC-A2";
+pub const PAPER_MAX_LARGE: &str = ">isoC-A2";
+
+/// URF (Apple Raster) 能力关键字
+pub fn urf_keywords(caps: &PrinterCapabilities) -> Vec<String> {
+    let mut keywords = vec!["V1.4".to_string(), "CP1".to_string()];
+    if caps.duplex {
+        keywords.push("DM1".to_string());
+    }
+    keywords.push("IS1".to_string());
+    keywords.push("W8".to_string());
+
+    let resolutions = resolutions(caps);
+    keywords.push(format!(
+        "RS{}",
+        resolutions.iter().map(|r| r.to_string()).collect::<Vec<_>>().join("-")
+    ));
+
+    if caps.color {
+        keywords.push("SRGB24".to_string());
+    }
+    keywords
+}
+
+/// 能力相关的 TXT 记录 (协议关键字保持英文)
+pub fn txt_records(caps: &PrinterCapabilities) -> Vec<(String, String)> {
+    let flag = |b: bool| if b { "T" } else { "F" }.to_string();
+    vec![
+        ("pdl".to_string(), caps.document_formats.join(",")),
+        ("Color".to_string(), flag(caps.color)),
+        ("Duplex".to_string(), flag(caps.duplex)),
+        ("PaperMax".to_string(), caps.max_paper.clone()),
+        ("URF".to_string(), urf_keywords(caps).join(",")),
+    ]
+}
+
+/// 能力相关的 IPP 打印机属性
+pub fn ipp_attributes(caps: &PrinterCapabilities) -> Vec<IppAttribute> {
+    let keywords = |values: &[&str]| IppValue::Array(values.iter().map(|v| IppValue::Keyword(v.to_string())).collect());
+
+    let formats: Vec<IppValue> = caps
+        .document_formats
+        .iter()
+        .map(|f| IppValue::MimeMediaType(f.clone()))
+        .collect();
+    let default_format = caps
+        .document_formats
+        .iter()
+        .find(|f| *f == "application/pdf")
+        .or(caps.document_formats.first())
+        .cloned()
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    let sides: &[&str] = if caps.duplex {
+        &["one-sided", "two-sided-long-edge", "two-sided-short-edge"]
+    } else {
+        &["one-sided"]
+    };
+    let color_modes: &[&str] = if caps.color { &["monochrome", "color", "auto"] } else { &["monochrome"] };
+
+    let resolutions: Vec<IppValue> = resolutions(caps)
+        .into_iter()
+        .map(|dpi| IppValue::Resolution { cross_feed: dpi as i32, feed: dpi as i32, units: 3 })
+        .collect();
+    let default_resolution = resolutions[0].clone();
+    let default_media = caps
+        .media
+        .iter()
+        .find(|m| m.starts_with("iso_a4_"))
+        .or(caps.media.first())
+        .cloned();
+
+    let mut attrs = vec![
+        IppAttribute::new("document-format-supported", IppValue::Array(formats)),
+        IppAttribute::new("document-format-default", IppValue::MimeMediaType(default_format)),
+        IppAttribute::new("color-supported", IppValue::Boolean(caps.color)),
+        IppAttribute::new("print-color-mode-supported", keywords(color_modes)),
+        IppAttribute::new("print-color-mode-default", IppValue::Keyword(if caps.color { "auto" } else { "monochrome" }.to_string())),
+        IppAttribute::new("sides-supported", keywords(sides)),
+        IppAttribute::new("sides-default", IppValue::Keyword("one-sided".to_string())),
+        IppAttribute::new("printer-resolution-supported", IppValue::Array(resolutions)),
+        IppAttribute::new("printer-resolution-default", default_resolution),
+        IppAttribute::new("urf-supported", IppValue::Array(
+            urf_keywords(caps).into_iter().map(IppValue::Keyword).collect(),
+        )),
+    ];
+    if let Some(default_media) = default_media {
+        let media = caps.media.iter().map(|m| IppValue::Keyword(m.clone())).collect();
+        attrs.push(IppAttribute::new("media-supported", IppValue::Array(media)));
+        attrs.push(IppAttribute::new("media-default", IppValue::Keyword(default_media)));
+    }
+    attrs
+}
+
+/// 由打印机的 IPP 属性 (Get-Printer-Attributes / CUPS-Get-Printers) 推导能力，
+/// 缺少的属性保持默认值
+pub fn from_ipp_attributes<'a>(find: impl Fn(&str) -> Option<&'a IppValue>) -> PrinterCapabilities {
+    let mut caps = PrinterCapabilities::default();
+    if let Some(IppValue::Boolean(color)) = find("color-supported") {
+        caps.color = *color;
+    }
+    if let Some(sides) = find("sides-supported") {
+        caps.duplex = attributes::as_strings(sides).iter().any(|s| s.starts_with("two-sided"));
+    }
+    if let Some(formats) = find("document-format-supported") {
+        let formats = attributes::as_strings(formats);
+        if !formats.is_empty() {
+            caps.document_formats = formats;
+        }
+    }
+    if let Some(media) = find("media-supported") {
+        let media = attributes::as_strings(media);
+        if let Some(max) = paper_max(&media) {
+            caps.max_paper = max.to_string();
+        }
+        if !media.is_empty() {
+            caps.media = media;
+        }
+    }
+    if let Some(IppValue::Array(values)) = find("printer-resolution-supported") {
+        caps.resolutions = values.iter().filter_map(resolution_dpi).collect();
+    } else if let Some(value) = find("printer-resolution-supported") {
+        caps.resolutions = resolution_dpi(value).into_iter().collect();
+    }
+    caps
+}
+
+/// Resolution 取 dpi (units 3 = dpi，4 = dpcm)
+fn resolution_dpi(value: &IppValue) -> Option<u32> {
+    match value {
+        IppValue::Resolution { cross_feed, feed, units } => {
+            let dots = (*cross_feed).min(*feed).max(0) as f64;
+            Some(if *units == 4 { (dots * 2.54).round() as u32 } else { dots as u32 })
+        }
+        _ => None,
+    }
+}
+
+/// 分辨率 (dpi) 升序去重，未知时按 300 dpi
+fn resolutions(caps: &PrinterCapabilities) -> Vec<u32> {
+    let mut resolutions: Vec<u32> = caps.resolutions.iter().copied().filter(|r| *r > 0).collect();
+    resolutions.sort_unstable();
+    resolutions.dedup();
+    if resolutions.is_empty() {
+        resolutions.push(300);
+    }
+    resolutions
+}
+
+/// 根据 PWG 媒体名 (如 `iso_a3_297x420mm`、`na_legal_8.5x14in`) 推算 PaperMax
+pub fn paper_max(media: &[String]) -> Option<&'static str> {
+    let largest = media
+        .iter()
+        .filter_map(|m| media_size_mm(m))
+        .map(|(w, h)| (w.min(h), w.max(h)))
+        .fold(None, |acc: Option<(f64, f64)>, size| match acc {
+            Some(max) if max.0 * max.1 >= size.0 * size.1 => Some(max),
+            _ => Some(size),
+        })?;
+
+    // legal: 215.9 x 355.6mm，A4: 210 x 297mm，A2: 420 x 594mm (留 1mm 余量)
+    let (short, long) = largest;
+    Some(if short < 209.0 && long < 296.0 {
+        PAPER_MAX_SMALL
+    } else if short <= 217.0 && long <= 357.0 {
+        PAPER_MAX_LEGAL_A4
+    } else if short <= 421.0 && long <= 595.0 {
+        PAPER_MAX_ISOC_A2
+    } else {
+        PAPER_MAX_LARGE
+    })
+}
+
+/// 从 PWG 自描述媒体名读出尺寸 (mm)
+fn media_size_mm(media: &str) -> Option<(f64, f64)> {
+    let dims = media.rsplit('_').next()?;
+    let (dims, scale) = if let Some(d) = dims.strip_suffix("mm") {
+        (d, 1.0)
+    } else if let Some(d) = dims.strip_suffix("in") {
+        (d, 25.4)
+    } else {
+        return None;
+    };
+    let (w, h) = dims.split_once('x')?;
+    Some((w.parse::<f64>().ok()? * scale, h.parse::<f64>().ok()? * scale))
+}
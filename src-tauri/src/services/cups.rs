@@ -0,0 +1,134 @@
+// src-tauri/src/services/cups.rs
+//
+// 通过 IPP 查询 CUPS 服务器 (CUPS-Get-Printers)，不解析 lpstat 的文本输出，
+// 结果与系统语言无关。
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use ipp::attribute::IppAttribute;
+use ipp::model::{DelimiterTag, IppVersion, Operation};
+use ipp::prelude::*;
+
+use crate::models::{Printer, PrinterDetails};
+use crate::services::{capabilities, printer_state};
+use crate::services::ipp::attributes;
+use crate::services::printer_detector::{cups_printer_id, remote_printer_id};
+
+/// CUPS 默认监听的地址
+pub const DEFAULT_SERVER: &str = "localhost:631";
+
+/// 查询超时，远程服务器不可达时不拖住后台检测
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// CUPS-Get-Printers 请求的属性 (另加 printer_state 和 capabilities 的 IPP_ATTRIBUTE_NAMES)
+const REQUESTED_ATTRIBUTES: &[&str] = &[
+    "printer-name",
+    "printer-make-and-model",
+    "printer-location",
+    "printer-info",
+    "device-uri",
+];
+
+/// 本机 CUPS 服务器：`CUPS_SERVER` 为主机名时使用它，为域套接字路径时仍连 localhost:631
+/// (ipp 客户端只支持 HTTP over TCP)
+pub fn local_server() -> String {
+    match std::env::var("CUPS_SERVER") {
+        Ok(server) if !server.is_empty() && !server.starts_with('/') => server,
+        _ => DEFAULT_SERVER.to_string(),
+    }
+}
+
+/// 列出 CUPS 服务器上的全部队列 (含打印机类)
+pub fn get_printers(server: &str) -> Result<Vec<Printer>, String> {
+    let uri = format!("ipp://{}/", server).parse::<Uri>().map_err(|e| e.to_string())?;
+
+    let mut request = IppRequestResponse::new(IppVersion::v1_1(), Operation::CupsGetPrinters, None);
+    let requested = REQUESTED_ATTRIBUTES
+        .iter()
+        .chain(printer_state::IPP_ATTRIBUTE_NAMES)
+        .chain(capabilities::IPP_ATTRIBUTE_NAMES)
+        .map(|name| IppValue::Keyword(name.to_string()))
+        .collect();
+    request.attributes_mut().add(
+        DelimiterTag::OperationAttributes,
+        IppAttribute::new(IppAttribute::REQUESTED_ATTRIBUTES, IppValue::Array(requested)),
+    );
+
+    let client = IppClient::builder(uri).request_timeout(REQUEST_TIMEOUT).build();
+    let response = client.send(request).map_err(|e| e.to_string())?;
+    let status = response.header().status_code();
+    // 没有任何队列时 CUPS 返回 client-error-not-found
+    if status == StatusCode::ClientErrorNotFound {
+        return Ok(Vec::new());
+    }
+    if !status.is_success() {
+        return Err(format!("{:?}", status));
+    }
+
+    Ok(response
+        .attributes()
+        .groups_of(DelimiterTag::PrinterAttributes)
+        .filter_map(|group| printer_from_attributes(group.attributes()))
+        .collect())
+}
+
+/// 远程 CUPS 服务器上的队列，ID 带上服务器地址，与本机的同名队列区分
+pub fn remote_printers(host: &str) -> Result<Vec<Printer>, String> {
+    let mut printers = get_printers(host)?;
+    for printer in &mut printers {
+        printer.id = remote_printer_id(host, &printer.name);
+        printer.details.origin = host.to_string();
+    }
+    Ok(printers)
+}
+
+/// CUPS 服务器上队列的地址
+pub fn printer_uri(server: &str, queue: &str) -> String {
+    format!("ipp://{}/printers/{}", server, queue)
+}
+
+/// 规范化用户输入的服务器地址：允许带 ipp:// 或 http:// 前缀和末尾的 '/'，
+/// 结果为 host 或 host:port
+pub fn parse_host(text: &str) -> Option<String> {
+    let text = text.trim();
+    let host = ["ipp://", "http://"]
+        .iter()
+        .find_map(|scheme| text.strip_prefix(scheme))
+        .unwrap_or(text)
+        .trim_end_matches('/');
+    let valid = !host.is_empty() && !host.contains(['/', '?', '#', '@']) && !host.contains(char::is_whitespace);
+    valid.then(|| host.to_string())
+}
+
+/// 由一个 printer-attributes 组生成打印机
+pub fn printer_from_attributes(attrs: &HashMap<String, IppAttribute>) -> Option<Printer> {
+    let value = |name: &str| attrs.get(name).map(|a| a.value());
+    let text = |name: &str| value(name).and_then(attributes::as_str).unwrap_or("").to_string();
+
+    let name = text("printer-name");
+    if name.is_empty() {
+        return None;
+    }
+    let (status, state) = printer_state::from_ipp_attributes(value);
+    // CUPS 对没有 PPD 的 raw 队列不返回这些属性，此时留给 PPD / lpoptions 补充
+    let capabilities = capabilities::IPP_ATTRIBUTE_NAMES
+        .iter()
+        .any(|name| attrs.contains_key(*name))
+        .then(|| capabilities::from_ipp_attributes(value));
+
+    Some(Printer {
+        id: cups_printer_id(&name),
+        status,
+        details: PrinterDetails {
+            make_and_model: text("printer-make-and-model"),
+            location: text("printer-location"),
+            info: text("printer-info"),
+            device_uri: text("device-uri"),
+            ..PrinterDetails::default()
+        },
+        state,
+        capabilities,
+        name,
+    })
+}
@@ -0,0 +1,307 @@
+// src-tauri/src/services/dns_sd_export.rs
+//
+// 把 mDNS 广播的服务导出为单播 DNS-SD 记录 (RFC 6763)，供 IT 发布到企业 DNS，
+// 跨网段 / VLAN 的客户端即可通过广域 Bonjour 发现打印机。
+// 支持两种格式：区域文件片段和 DNS UPDATE (RFC 2136) 报文。
+
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use mdns_sd::ServiceInfo;
+use rust_i18n::t;
+
+/// 导出记录的 TTL (秒)
+pub const EXPORT_TTL: u32 = 3600;
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_AAAA: u16 = 28;
+const TYPE_SRV: u16 = 33;
+const TYPE_SOA: u16 = 6;
+const TYPE_ANY: u16 = 255;
+const CLASS_IN: u16 = 1;
+const CLASS_ANY: u16 = 255;
+const OPCODE_UPDATE: u16 = 5;
+
+/// 域名，按标签保存 (实例名标签可以包含空格和 UTF-8)
+pub type Name = Vec<String>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RecordData {
+    Ptr(Name),
+    Srv { priority: u16, weight: u16, port: u16, target: Name },
+    Txt(Vec<String>),
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Record {
+    pub name: Name,
+    pub ttl: u32,
+    pub data: RecordData,
+}
+
+/// 由 mDNS 服务生成 `domain` 下的 DNS-SD 记录：浏览域、PTR (含子类型)、SRV、TXT、A/AAAA。
+/// `.local.` 换成 `domain`，链路本地地址不导出
+pub fn records(services: &[ServiceInfo], domain: &str) -> Result<Vec<Record>, String> {
+    let domain = parse_domain(domain)?;
+    let in_domain = |labels: Vec<String>| -> Name { labels.into_iter().chain(domain.iter().cloned()).collect() };
+
+    // RFC 6763 11：让客户端在该域中浏览
+    let mut records: Vec<Record> = ["b", "lb"]
+        .iter()
+        .map(|kind| Record {
+            name: in_domain(vec![kind.to_string(), "_dns-sd".to_string(), "_udp".to_string()]),
+            ttl: EXPORT_TTL,
+            data: RecordData::Ptr(domain.clone()),
+        })
+        .collect();
+    let mut hosts = HashSet::new();
+
+    for info in services {
+        let service_type = local_labels(info.get_type());
+        let instance = instance_label(info);
+        let instance_name = in_domain(std::iter::once(instance).chain(service_type.iter().cloned()).collect());
+
+        records.push(Record {
+            name: in_domain(service_type.clone()),
+            ttl: EXPORT_TTL,
+            data: RecordData::Ptr(instance_name.clone()),
+        });
+        if let Some(subtype) = info.get_subtype() {
+            records.push(Record {
+                name: in_domain(local_labels(subtype)),
+                ttl: EXPORT_TTL,
+                data: RecordData::Ptr(instance_name.clone()),
+            });
+        }
+
+        let host = in_domain(local_labels(info.get_hostname()));
+        records.push(Record {
+            name: instance_name.clone(),
+            ttl: EXPORT_TTL,
+            data: RecordData::Srv { priority: 0, weight: 0, port: info.get_port(), target: host.clone() },
+        });
+        records.push(Record {
+            name: instance_name,
+            ttl: EXPORT_TTL,
+            data: RecordData::Txt(txt_strings(info)),
+        });
+
+        if !hosts.insert(host.clone()) {
+            continue;
+        }
+        let mut addrs: Vec<IpAddr> = info.get_addresses().iter().copied().filter(is_routable).collect();
+        addrs.sort_by_key(|addr| (addr.is_ipv6(), *addr));
+        for addr in addrs {
+            let data = match addr {
+                IpAddr::V4(v4) => RecordData::A(v4),
+                IpAddr::V6(v6) => RecordData::Aaaa(v6),
+            };
+            records.push(Record { name: host.clone(), ttl: EXPORT_TTL, data });
+        }
+    }
+    // 同一实例的每个子类型各是一个 ServiceInfo，重复的 PTR / SRV / TXT 只保留一份
+    let mut seen = HashSet::new();
+    records.retain(|record| seen.insert(record.clone()));
+    Ok(records)
+}
+
+/// RFC 1035 区域文件片段，每行一条记录
+pub fn zone_file(records: &[Record]) -> String {
+    let mut zone = String::new();
+    for record in records {
+        let (rtype, data) = match &record.data {
+            RecordData::Ptr(target) => ("PTR", zone_name(target)),
+            RecordData::Srv { priority, weight, port, target } => {
+                ("SRV", format!("{} {} {} {}", priority, weight, port, zone_name(target)))
+            }
+            RecordData::Txt(strings) => (
+                "TXT",
+                strings.iter().map(|s| format!("\"{}\"", escape(s.as_bytes(), true))).collect::<Vec<_>>().join(" "),
+            ),
+            RecordData::A(addr) => ("A", addr.to_string()),
+            RecordData::Aaaa(addr) => ("AAAA", addr.to_string()),
+        };
+        zone.push_str(&format!("{} {} IN {} {}\n", zone_name(&record.name), record.ttl, rtype, data));
+    }
+    zone
+}
+
+/// DNS UPDATE (RFC 2136) 报文：先删除实例名和主机名上的旧记录，再添加全部记录。
+/// 服务类型的 PTR 记录与其他主机共用，只添加不删除
+pub fn dns_update(records: &[Record], zone: &str) -> Result<Vec<u8>, String> {
+    let zone = parse_domain(zone)?;
+
+    let mut replaced: Vec<&Name> = Vec::new();
+    for record in records {
+        if !matches!(record.data, RecordData::Ptr(_)) && !replaced.contains(&&record.name) {
+            replaced.push(&record.name);
+        }
+    }
+
+    let update_count = replaced.len() + records.len();
+    let mut message = Vec::new();
+    message.extend_from_slice(&rand::random::<u16>().to_be_bytes());
+    message.extend_from_slice(&(OPCODE_UPDATE << 11).to_be_bytes());
+    message.extend_from_slice(&1u16.to_be_bytes()); // ZOCOUNT
+    message.extend_from_slice(&0u16.to_be_bytes()); // PRCOUNT
+    message.extend_from_slice(&u16::try_from(update_count).map_err(|e| e.to_string())?.to_be_bytes());
+    message.extend_from_slice(&0u16.to_be_bytes()); // ADCOUNT
+
+    // Zone 段
+    write_name(&mut message, &zone)?;
+    message.extend_from_slice(&TYPE_SOA.to_be_bytes());
+    message.extend_from_slice(&CLASS_IN.to_be_bytes());
+
+    // 删除名称上的所有 RRset：CLASS=ANY TYPE=ANY TTL=0 RDLENGTH=0
+    for name in replaced {
+        write_name(&mut message, name)?;
+        message.extend_from_slice(&TYPE_ANY.to_be_bytes());
+        message.extend_from_slice(&CLASS_ANY.to_be_bytes());
+        message.extend_from_slice(&0u32.to_be_bytes());
+        message.extend_from_slice(&0u16.to_be_bytes());
+    }
+
+    for record in records {
+        write_name(&mut message, &record.name)?;
+        let mut rdata = Vec::new();
+        let rtype = match &record.data {
+            RecordData::Ptr(target) => {
+                write_name(&mut rdata, target)?;
+                TYPE_PTR
+            }
+            RecordData::Srv { priority, weight, port, target } => {
+                rdata.extend_from_slice(&priority.to_be_bytes());
+                rdata.extend_from_slice(&weight.to_be_bytes());
+                rdata.extend_from_slice(&port.to_be_bytes());
+                write_name(&mut rdata, target)?;
+                TYPE_SRV
+            }
+            RecordData::Txt(strings) => {
+                for s in strings {
+                    rdata.push(s.len() as u8);
+                    rdata.extend_from_slice(s.as_bytes());
+                }
+                TYPE_TXT
+            }
+            RecordData::A(addr) => {
+                rdata.extend_from_slice(&addr.octets());
+                TYPE_A
+            }
+            RecordData::Aaaa(addr) => {
+                rdata.extend_from_slice(&addr.octets());
+                TYPE_AAAA
+            }
+        };
+        message.extend_from_slice(&rtype.to_be_bytes());
+        message.extend_from_slice(&CLASS_IN.to_be_bytes());
+        message.extend_from_slice(&record.ttl.to_be_bytes());
+        message.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        message.extend_from_slice(&rdata);
+    }
+    Ok(message)
+}
+
+/// 校验目标域名 (ASCII)，返回标签
+fn parse_domain(domain: &str) -> Result<Name, String> {
+    let invalid = || t!("errors.dns_sd_invalid_domain", domain = domain).to_string();
+    let trimmed = domain.trim().trim_end_matches('.');
+    if trimmed.is_empty() {
+        return Err(invalid());
+    }
+    let labels: Name = trimmed.split('.').map(|l| l.to_ascii_lowercase()).collect();
+    let valid = labels.iter().all(|l| {
+        !l.is_empty() && l.len() <= 63 && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    });
+    if !valid || trimmed.len() > 253 {
+        return Err(invalid());
+    }
+    Ok(labels)
+}
+
+/// "_universal._sub._ipp._tcp.local." -> ["_universal", "_sub", "_ipp", "_tcp"]
+fn local_labels(name: &str) -> Name {
+    let name = name.trim_end_matches('.');
+    let name = name.strip_suffix(".local").unwrap_or(name);
+    name.split('.').map(|l| l.to_string()).collect()
+}
+
+/// 完整名称去掉服务类型后即实例名，实例名本身不含 '.'
+fn instance_label(info: &ServiceInfo) -> String {
+    let fullname = info.get_fullname();
+    fullname
+        .strip_suffix(info.get_type())
+        .and_then(|name| name.strip_suffix('.'))
+        .unwrap_or(fullname)
+        .to_string()
+}
+
+/// TXT 字符串 "key=value"，txtvers 在前 (RFC 6763 6.7)，其余按键排序
+fn txt_strings(info: &ServiceInfo) -> Vec<String> {
+    let mut properties: Vec<(String, String)> = info
+        .get_properties()
+        .iter()
+        .map(|p| (p.key().to_string(), p.val_str().to_string()))
+        .collect();
+    properties.sort_by(|a, b| (a.0 != "txtvers", &a.0).cmp(&(b.0 != "txtvers", &b.0)));
+    properties
+        .into_iter()
+        .map(|(key, value)| {
+            let entry = format!("{}={}", key, value);
+            let mut end = entry.len().min(255);
+            while !entry.is_char_boundary(end) {
+                end -= 1;
+            }
+            entry[..end].to_string()
+        })
+        .collect()
+}
+
+/// 链路本地地址跨网段不可用
+fn is_routable(addr: &IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(v4) => !v4.is_loopback() && !v4.is_link_local(),
+        IpAddr::V6(v6) => !v6.is_loopback() && (v6.segments()[0] & 0xffc0) != 0xfe80,
+    }
+}
+
+fn zone_name(labels: &Name) -> String {
+    let mut name: String = labels.iter().map(|l| escape(l.as_bytes(), false)).collect::<Vec<_>>().join(".");
+    name.push('.');
+    name
+}
+
+/// 区域文件转义：特殊字符加 '\'，非 ASCII 和控制字符写成 \DDD
+fn escape(bytes: &[u8], quoted: bool) -> String {
+    let mut escaped = String::new();
+    for &b in bytes {
+        match b {
+            b'"' | b'\\' => {
+                escaped.push('\\');
+                escaped.push(b as char);
+            }
+            b'.' | b' ' | b'(' | b')' | b';' | b'@' | b'$' if !quoted => {
+                escaped.push('\\');
+                escaped.push(b as char);
+            }
+            0x21..=0x7e | b' ' => escaped.push(b as char),
+            _ => escaped.push_str(&format!("\\{:03}", b)),
+        }
+    }
+    escaped
+}
+
+fn write_name(buf: &mut Vec<u8>, labels: &Name) -> Result<(), String> {
+    for label in labels {
+        if label.is_empty() || label.len() > 63 {
+            return Err(t!("errors.dns_sd_invalid_domain", domain = label).to_string());
+        }
+        buf.push(label.len() as u8);
+        buf.extend_from_slice(label.as_bytes());
+    }
+    buf.push(0);
+    Ok(())
+}
@@ -0,0 +1,46 @@
+// src-tauri/src/services/ipp/attributes.rs
+//
+// 读取 IPP 响应属性的小工具
+
+use ipp::model::DelimiterTag;
+use ipp::request::IppRequestResponse;
+use ipp::value::IppValue;
+
+/// 在指定属性组中查找属性值
+pub fn find<'a>(resp: &'a IppRequestResponse, tag: DelimiterTag, name: &str) -> Option<&'a IppValue> {
+    resp.attributes()
+        .groups_of(tag)
+        .find_map(|group| group.attributes().get(name))
+        .map(|attr| attr.value())
+}
+
+/// 把字符串类的值转换为 &str
+pub fn as_str(value: &IppValue) -> Option<&str> {
+    match value {
+        IppValue::Keyword(s)
+        | IppValue::NameWithoutLanguage(s)
+        | IppValue::TextWithoutLanguage(s)
+        | IppValue::MimeMediaType(s)
+        | IppValue::Uri(s)
+        | IppValue::Charset(s)
+        | IppValue::NaturalLanguage(s) => Some(s),
+        IppValue::NameWithLanguage { name, .. } => Some(name),
+        IppValue::TextWithLanguage { text, .. } => Some(text),
+        _ => None,
+    }
+}
+
+pub fn as_int(value: &IppValue) -> Option<i32> {
+    match value {
+        IppValue::Integer(v) | IppValue::Enum(v) => Some(*v),
+        _ => None,
+    }
+}
+
+/// 单值或数组 (1setOf) 统一展开为字符串列表
+pub fn as_strings(value: &IppValue) -> Vec<String> {
+    match value {
+        IppValue::Array(values) => values.iter().filter_map(as_str).map(|s| s.to_string()).collect(),
+        other => as_str(other).map(|s| vec![s.to_string()]).unwrap_or_default(),
+    }
+}
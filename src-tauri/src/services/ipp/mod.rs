@@ -1,3 +1,4 @@
 pub mod server;
+pub mod attributes;
 
-pub use server::IppServer;
\ No newline at end of file
+pub use server::IppServer;
@@ -1,10 +1,10 @@
 use tiny_http::{Server, Response, Header};
 use std::thread;
-use std::io::{Read, Cursor, Write};
-use std::fs::{self, File};
-use std::path::{Path};
-use std::process::Command;
-use std::time::Duration;
+use std::io::{Read, Cursor};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener};
+use std::sync::Arc;
+
+use socket2::{Domain, Socket, Type};
 
 // 👇 1. 导入 prelude 以获取 FromPrimitive trait
 use ipp::prelude::*;
@@ -18,68 +18,91 @@ use ipp::reader::IppReader;
 // 引入翻译宏
 use rust_i18n::t;
 
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
-#[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
-
-// 定义一个结构体来存储解析出的打印选项
-#[derive(Debug, Clone)]
-struct PrintOptions {
-    copies: i32,
-    sides: String,
-    color_mode: String,
-    media: String,
-}
-
-impl Default for PrintOptions {
-    fn default() -> Self {
-        Self {
-            copies: 1,
-            sides: "one-sided".to_string(),
-            color_mode: "auto".to_string(),
-            media: "A4".to_string(),
-        }
-    }
-}
+use super::attributes;
+use crate::services::{capabilities, printer_icons, printer_state};
+use crate::services::backend::{BackendError, PrintOptions};
+use crate::services::print_queue::{JobInfo, JobSnapshot, PrintQueue, QueueRegistry};
+
+/// 描述本服务器自身的属性，镜像下游打印机时也以本地值为准
+const LOCAL_ATTRIBUTES: &[&str] = &[
+    "printer-uri-supported",
+    // 与 printer-uri-supported 一一对应
+    "uri-security-supported",
+    "uri-authentication-supported",
+    "printer-uuid",
+    "operations-supported",
+    // 下游打印机的图标地址客户端访问不到
+    "printer-icons",
+];
 
 pub struct IppServer {
-    address: String,
+    address: SocketAddr,
+    queues: QueueRegistry,
 }
 
 impl IppServer {
-    pub fn new(bind_address: &str, port: u16) -> Self {
+    /// `bind_address` 为 `::` 时同时监听 IPv4 和 IPv6
+    pub fn new(bind_address: &str, port: u16, queues: QueueRegistry) -> Self {
+        let ip = bind_address.parse().unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
         Self {
-            address: format!("{}:{}", bind_address, port),
+            address: SocketAddr::new(ip, port),
+            queues,
         }
     }
 
     pub fn start(&self) {
-        let server = match Server::http(&self.address) {
+        let server = match Self::listen(self.address).map_err(|e| e.to_string()).and_then(|listener| {
+            Server::from_listener(listener, None).map_err(|e| e.to_string())
+        }) {
             Ok(s) => s,
             Err(e) => {
                 // 使用 t! 宏翻译错误日志
-                eprintln!("{}", t!("errors.ipp_server_start_failed", error = e.to_string()));
+                eprintln!("{}", t!("errors.ipp_server_start_failed", error = e));
                 return;
             }
         };
 
-        let server_address = self.address.clone();
+        let server_address = server
+            .server_addr()
+            .to_ip()
+            .map(|addr| addr.to_string())
+            .unwrap_or_else(|| self.address.to_string());
+        let queues = self.queues.clone();
         // 翻译启动日志
-        println!("{}", t!("logs.ipp_server_listening", address = self.address));
+        println!("{}", t!("logs.ipp_server_listening", address = server_address));
         println!("{}", t!("logs.ipp_temp_dir_usage"));
 
         thread::spawn(move || {
             for request in server.incoming_requests() {
                 let addr_clone = server_address.clone();
+                let queues = queues.clone();
                 thread::spawn(move || {
-                    Self::handle_request(request, &addr_clone);
+                    Self::handle_request(request, &addr_clone, &queues);
                 });
             }
         });
     }
 
-    fn handle_request(mut request: tiny_http::Request, server_address: &str) {
+    /// 绑定监听。`::` 时关闭 IPV6_V6ONLY 得到双栈套接字，系统不支持 IPv6 时退回 0.0.0.0
+    fn listen(address: SocketAddr) -> std::io::Result<TcpListener> {
+        if address.ip() != IpAddr::V6(Ipv6Addr::UNSPECIFIED) {
+            return TcpListener::bind(address);
+        }
+        let dual_stack = || -> std::io::Result<TcpListener> {
+            let socket = Socket::new(Domain::IPV6, Type::STREAM, None)?;
+            socket.set_only_v6(false)?;
+            socket.set_reuse_address(true)?;
+            socket.bind(&address.into())?;
+            socket.listen(128)?;
+            Ok(socket.into())
+        };
+        dual_stack().or_else(|e| {
+            eprintln!("{}", t!("errors.ipp_dual_stack_failed", error = e.to_string()));
+            TcpListener::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), address.port()))
+        })
+    }
+
+    fn handle_request(mut request: tiny_http::Request, server_address: &str, queues: &QueueRegistry) {
         // Content-Type 检查
         let is_ipp = request.headers().iter().any(|h| {
             let field_lower = h.field.as_str().to_ascii_lowercase();
@@ -88,6 +111,11 @@ impl IppServer {
         });
 
         if !is_ipp {
+            let icon = printer_icons::parse_icon_path(request.url()).map(|(queue, size)| (queue.to_string(), size));
+            if let Some((queue, size)) = icon {
+                Self::serve_icon(request, queues, &queue, size);
+                return;
+            }
             let html = r#"<!DOCTYPE html><html><body><h1>IPP Everywhere Printer</h1></body></html>"#;
             let _ = request.respond(Response::from_string(html)
                 .with_header(Header::from_bytes(&b"Content-Type"[..], &b"text/html; charset=utf-8"[..]).unwrap()));
@@ -109,23 +137,40 @@ impl IppServer {
         let cursor = Cursor::new(body);
         let reader = IppReader::new(cursor);
         let parser = IppParser::new(reader);
-        
+
         match parser.parse() {
             Ok(ipp_request) => {
                 let op_code = ipp_request.header().operation_or_status;
                 let request_id = ipp_request.header().request_id;
-                
+
                 let op_name = Operation::from_u16(op_code)
                     .map(|o| format!("{:?}", o))
                     .unwrap_or_else(|| format!("Unknown({})", op_code));
-                
+
                 // 日志可以使用翻译，但操作名通常保留英文以便调试
                 println!("{}", t!("logs.ipp_request_parsed", op = op_name, id = request_id));
 
+                // 按请求路径找到对应的共享队列
+                let queue = match queues.resolve(request.url()) {
+                    Some(q) => q,
+                    None => {
+                        eprintln!("{}", t!("errors.ipp_queue_not_found", path = request.url()));
+                        let err_resp = Self::create_error_response(request_id, StatusCode::ClientErrorNotFound);
+                        let _ = request.respond(Response::from_data(err_resp)
+                            .with_header(Header::from_bytes(&b"Content-Type"[..], &b"application/ipp"[..]).unwrap()));
+                        return;
+                    }
+                };
+
                 // 👇 【关键步骤 1】在消耗 payload 之前，先提取打印属性
-                let print_options = Self::extract_print_options(&ipp_request);
-                // 这里可以打印选项日志，如果需要的话
-                // println!("{}", t!("logs.ipp_print_options", options = format!("{:?}", print_options)));
+                let mut job_info = Self::extract_job_info(&ipp_request);
+                if let Some(addr) = request.remote_addr() {
+                    job_info.device = addr.ip().to_string();
+                }
+                let target_job_id = Self::extract_job_id(&ipp_request);
+                let document_format = attributes::find(&ipp_request, DelimiterTag::OperationAttributes, "document-format")
+                    .and_then(attributes::as_str)
+                    .map(|s| s.to_string());
 
                 // 👇 【关键步骤 2】提取 Payload
                 let mut payload_reader = ipp_request.into_payload();
@@ -134,19 +179,43 @@ impl IppServer {
                     eprintln!("{}", t!("errors.ipp_read_payload_failed", error = e.to_string()));
                 }
 
+                // 客户端访问用的地址 (Host 头)，IPv6 时形如 [fe80::1]:631
+                let host = request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv("Host"))
+                    .map(|h| h.value.as_str().to_string())
+                    .unwrap_or_else(|| server_address.to_string());
+                let printer_uri = format!("ipp://{}/{}", host, queue.resource_path());
+
                 let response_body = match Operation::from_u16(op_code) {
                     Some(Operation::GetPrinterAttributes) => {
-                        Self::handle_get_printer_attributes(request_id, server_address)
+                        Self::handle_get_printer_attributes(request_id, &printer_uri, &queue)
                     },
                     Some(Operation::PrintJob) => {
-                        Self::handle_print_job(request_id, server_address, document_data, print_options)
+                        Self::handle_print_job(request_id, &printer_uri, queues, &queue, document_data, job_info)
                     },
                     Some(Operation::ValidateJob) => {
                         Self::handle_validate_job(request_id)
                     },
+                    Some(Operation::CreateJob) => {
+                        Self::handle_create_job(request_id, &printer_uri, queues, &queue, job_info)
+                    },
+                    Some(Operation::SendDocument) => {
+                        Self::handle_send_document(request_id, &printer_uri, queues, &queue, target_job_id, document_data, document_format)
+                    },
+                    Some(Operation::GetJobAttributes) => {
+                        Self::handle_get_job_attributes(request_id, &printer_uri, queues, &queue, target_job_id)
+                    },
+                    Some(Operation::GetJobs) => {
+                        Self::handle_get_jobs(request_id, &printer_uri, queues, &queue)
+                    },
+                    Some(Operation::CancelJob) => {
+                        Self::handle_cancel_job(request_id, queues, &queue, target_job_id)
+                    },
                     _ => {
                         eprintln!("{}", t!("errors.ipp_unsupported_operation", op = op_code));
-                        Self::create_error_response(request_id, StatusCode::ClientErrorBadRequest)
+                        Self::create_error_response(request_id, StatusCode::ServerErrorOperationNotSupported)
                     }
                 };
 
@@ -162,11 +231,12 @@ impl IppServer {
         }
     }
 
-    fn extract_print_options(req: &IppRequestResponse) -> PrintOptions {
+    fn extract_job_info(req: &IppRequestResponse) -> JobInfo {
+        let mut info = JobInfo::default();
         let mut options = PrintOptions::default();
-        
+
         for group in req.attributes().groups() {
-            for (_, attr) in group.attributes() {
+            for attr in group.attributes().values() {
                 match attr.name() {
                     "copies" => {
                         if let IppValue::Integer(val) = attr.value() {
@@ -188,262 +258,252 @@ impl IppServer {
                             options.media = val.clone();
                         }
                     },
+                    "job-name" => {
+                        if let Some(val) = attributes::as_str(attr.value()) {
+                            info.name = val.to_string();
+                        }
+                    },
+                    "requesting-user-name" => {
+                        if let Some(val) = attributes::as_str(attr.value()) {
+                            info.user = val.to_string();
+                        }
+                    },
+                    "document-format" => {
+                        if let Some(val) = attributes::as_str(attr.value()) {
+                            info.document_format = val.to_string();
+                        }
+                    },
                     _ => {}
                 }
             }
         }
-        options
+        info.options = options;
+        info
+    }
+
+    /// 从 job-id 或 job-uri 中取出目标任务
+    fn extract_job_id(req: &IppRequestResponse) -> Option<u32> {
+        if let Some(id) = attributes::find(req, DelimiterTag::OperationAttributes, "job-id").and_then(attributes::as_int) {
+            return u32::try_from(id).ok();
+        }
+        attributes::find(req, DelimiterTag::OperationAttributes, "job-uri")
+            .and_then(attributes::as_str)
+            .and_then(|uri| uri.rsplit('/').next())
+            .and_then(|id| id.parse().ok())
     }
 
-    fn handle_get_printer_attributes(request_id: u32, server_address: &str) -> Vec<u8> {
-        let printer_uri_str = format!("ipp://{}/ipp/print", server_address);
+    /// GET /icons/{队列}/{尺寸}.png
+    fn serve_icon(request: tiny_http::Request, queues: &QueueRegistry, queue: &str, size: u32) {
+        let icons = queues.get(queue).map(|q| q.icons());
+        let response = match icons.as_ref().and_then(|icons| icons.get(size)) {
+            Some(png) => Response::from_data(png.to_vec())
+                .with_header(Header::from_bytes(&b"Content-Type"[..], &b"image/png"[..]).unwrap()),
+            None => Response::from_data(Vec::new()).with_status_code(404),
+        };
+        let _ = request.respond(response);
+    }
+
+    fn handle_get_printer_attributes(request_id: u32, printer_uri: &str, queue: &Arc<PrintQueue>) -> Vec<u8> {
         let version = IppVersion::v2_0();
         let mut response = IppRequestResponse::new_response(version, StatusCode::SuccessfulOk, request_id);
         let attrs = response.attributes_mut();
-        
+
+        // IPP 代理：先写入下游打印机的属性，下面的默认值只补充缺失项
+        let mirrored = queue.backend.printer_attributes().unwrap_or_else(|e| {
+            eprintln!("{}", t!("errors.ipp_mirror_attributes_failed", error = e.to_string()));
+            Vec::new()
+        });
+        let mirrored_names: Vec<String> = mirrored.iter().map(|a| a.name().to_string()).collect();
+        for attr in mirrored {
+            attrs.add(DelimiterTag::PrinterAttributes, attr);
+        }
+
         // 协议属性值保持英文
-        attrs.add(DelimiterTag::PrinterAttributes, IppAttribute::new("printer-name", IppValue::NameWithoutLanguage("AirPrinter".to_string())));
-        attrs.add(DelimiterTag::PrinterAttributes, IppAttribute::new("printer-make-and-model", IppValue::TextWithoutLanguage("AirPrinter Model A".to_string())));
-        attrs.add(DelimiterTag::PrinterAttributes, IppAttribute::new("printer-state", IppValue::Enum(3)));
-        attrs.add(DelimiterTag::PrinterAttributes, IppAttribute::new("printer-is-accepting-jobs", IppValue::Boolean(true)));
-        attrs.add(DelimiterTag::PrinterAttributes, IppAttribute::new("printer-state-reasons", IppValue::Keyword("none".to_string())));
-        attrs.add(DelimiterTag::PrinterAttributes, IppAttribute::new("printer-uri-supported", IppValue::Uri(printer_uri_str)));
-        
-        attrs.add(DelimiterTag::PrinterAttributes, IppAttribute::new("operations-supported", IppValue::Array(vec![
-            IppValue::Enum(Operation::PrintJob as i32),
-            IppValue::Enum(Operation::GetPrinterAttributes as i32),
-            IppValue::Enum(Operation::ValidateJob as i32),
-        ])));
-        
-        attrs.add(DelimiterTag::PrinterAttributes, IppAttribute::new("document-format-supported", IppValue::Array(vec![
-            IppValue::MimeMediaType("application/pdf".to_string()),
-            IppValue::MimeMediaType("image/urf".to_string()),
-            IppValue::MimeMediaType("image/jpeg".to_string()),
-        ])));
-        attrs.add(DelimiterTag::PrinterAttributes, IppAttribute::new("document-format-default", IppValue::MimeMediaType("application/pdf".to_string())));
-        
-        attrs.add(DelimiterTag::PrinterAttributes, IppAttribute::new("urf-supported", IppValue::Array(vec![
-            IppValue::Keyword("V1.4".to_string()),
-            IppValue::Keyword("CP1".to_string()),
-            IppValue::Keyword("DM1".to_string()),
-            IppValue::Keyword("IS1".to_string()),
-            IppValue::Keyword("W8".to_string()),
-            IppValue::Keyword("RS300".to_string()),
-            IppValue::Keyword("SRGB24".to_string()),
-        ])));
+        let mut defaults = vec![
+            IppAttribute::new("printer-name", IppValue::NameWithoutLanguage(queue.display_name())),
+            IppAttribute::new("printer-location", IppValue::TextWithoutLanguage(queue.location())),
+            IppAttribute::new("printer-info", IppValue::TextWithoutLanguage(queue.info())),
+            IppAttribute::new("printer-make-and-model", IppValue::TextWithoutLanguage("AirPrinter Model A".to_string())),
+            IppAttribute::new("printer-uri-supported", IppValue::Uri(printer_uri.to_string())),
+            // 本服务器只提供不加密、不认证的 ipp://
+            IppAttribute::new("uri-security-supported", IppValue::Keyword("none".to_string())),
+            IppAttribute::new("uri-authentication-supported", IppValue::Keyword("none".to_string())),
+            IppAttribute::new("printer-uuid", IppValue::Uri(queue.uuid_urn())),
+            IppAttribute::new("printer-icons", IppValue::Array(
+                Self::icon_uris(printer_uri, queue).into_iter().map(IppValue::Uri).collect(),
+            )),
+            IppAttribute::new("operations-supported", IppValue::Array(vec![
+                IppValue::Enum(Operation::PrintJob as i32),
+                IppValue::Enum(Operation::GetPrinterAttributes as i32),
+                IppValue::Enum(Operation::ValidateJob as i32),
+                IppValue::Enum(Operation::CreateJob as i32),
+                IppValue::Enum(Operation::SendDocument as i32),
+                IppValue::Enum(Operation::GetJobAttributes as i32),
+                IppValue::Enum(Operation::GetJobs as i32),
+                IppValue::Enum(Operation::CancelJob as i32),
+            ])),
+        ];
+        // 用户填写的显示信息也覆盖下游打印机的属性
+        let profile = queue.profile();
+        let mut overrides: Vec<&str> = [
+            ("printer-name", &profile.display_name),
+            ("printer-location", &profile.location),
+            ("printer-info", &profile.info),
+        ]
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(name, _)| *name)
+        .collect();
+        if !profile.geo_location.is_empty() {
+            defaults.push(IppAttribute::new("printer-geo-location", IppValue::Uri(profile.geo_location.clone())));
+            overrides.push("printer-geo-location");
+        }
+        if !profile.more_info.is_empty() {
+            defaults.push(IppAttribute::new("printer-more-info", IppValue::Uri(profile.more_info.clone())));
+            overrides.push("printer-more-info");
+        }
+
+        let (status, state) = queue.state();
+        defaults.extend(printer_state::ipp_attributes(&status, &state));
+        // 能力相关属性与 mDNS TXT 共用同一推导
+        defaults.extend(capabilities::ipp_attributes(&queue.capabilities));
+
+        for attr in defaults {
+            let local = LOCAL_ATTRIBUTES.contains(&attr.name()) || overrides.contains(&attr.name());
+            if local || !mirrored_names.iter().any(|n| n == attr.name()) {
+                attrs.add(DelimiterTag::PrinterAttributes, attr);
+            }
+        }
 
         response.to_bytes().to_vec()
     }
 
-    fn handle_print_job(request_id: u32, server_address: &str, document_data: Vec<u8>, options: PrintOptions) -> Vec<u8> {
+    /// 图标地址与 printer-uri 使用同一主机 (客户端请求时的 Host)
+    fn icon_uris(printer_uri: &str, queue: &PrintQueue) -> Vec<String> {
+        let host = printer_uri.trim_start_matches("ipp://").split('/').next().unwrap_or_default();
+        printer_icons::icon_uris(&format!("http://{}/", host), &queue.name())
+    }
+
+    fn handle_print_job(request_id: u32, printer_uri: &str, queues: &QueueRegistry, queue: &Arc<PrintQueue>, document_data: Vec<u8>, job_info: JobInfo) -> Vec<u8> {
         // 翻译日志
-        println!("{}", t!("logs.ipp_job_received", id = request_id, size = document_data.len(), copies = options.copies));
+        println!("{}", t!("logs.ipp_job_received", id = request_id, size = document_data.len(), copies = job_info.options.copies));
 
         if document_data.is_empty() {
             return Self::create_error_response(request_id, StatusCode::ClientErrorBadRequest);
         }
 
-        let temp_dir = std::env::temp_dir();
-        let timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis();
-        let filename = format!("airprint_{}_{}.pdf", timestamp, request_id);
-        let filepath = temp_dir.join(filename);
-
-        let write_result = (|| -> std::io::Result<()> {
-            let mut file = File::create(&filepath)?;
-            file.write_all(&document_data)?;
-            file.sync_all()?; 
-            Ok(())
-        })();
-
-        if let Err(e) = write_result {
-            eprintln!("{}", t!("errors.ipp_write_temp_failed", error = e.to_string(), path = format!("{:?}", filepath)));
-            return Self::create_error_response(request_id, StatusCode::ServerErrorInternalError);
+        let job_id = match queues.submit(queue, document_data, job_info) {
+            Ok(id) => id,
+            Err(_) => return Self::create_error_response(request_id, StatusCode::ServerErrorInternalError),
+        };
+
+        let version = IppVersion::v2_0();
+        let mut response = IppRequestResponse::new_response(version, StatusCode::SuccessfulOk, request_id);
+        if let Some(job) = queues.job(job_id) {
+            Self::add_job_attributes(response.attributes_mut(), printer_uri, &job);
         }
+        response.to_bytes().to_vec()
+    }
 
-        println!("{}", t!("logs.ipp_temp_file_created", path = format!("{:?}", filepath)));
+    fn handle_create_job(request_id: u32, printer_uri: &str, queues: &QueueRegistry, queue: &Arc<PrintQueue>, job_info: JobInfo) -> Vec<u8> {
+        let job_id = queues.create_job(queue, job_info);
 
-        let filepath_clone = filepath.clone();
-        let options_clone = options.clone();
-        
-        thread::spawn(move || {
-            thread::sleep(Duration::from_millis(500));
+        let version = IppVersion::v2_0();
+        let mut response = IppRequestResponse::new_response(version, StatusCode::SuccessfulOk, request_id);
+        if let Some(job) = queues.job(job_id) {
+            Self::add_job_attributes(response.attributes_mut(), printer_uri, &job);
+        }
+        response.to_bytes().to_vec()
+    }
 
-            if !filepath_clone.exists() {
-                eprintln!("{}", t!("errors.ipp_file_missing_before_print", path = format!("{:?}", filepath_clone)));
-                return;
-            }
+    fn handle_send_document(request_id: u32, printer_uri: &str, queues: &QueueRegistry, queue: &Arc<PrintQueue>, job_id: Option<u32>, document_data: Vec<u8>, document_format: Option<String>) -> Vec<u8> {
+        let Some(job_id) = job_id else {
+            return Self::create_error_response(request_id, StatusCode::ClientErrorBadRequest);
+        };
+        if Self::find_job(queues, queue, job_id).is_none() {
+            return Self::create_error_response(request_id, StatusCode::ClientErrorNotFound);
+        }
+        println!("{}", t!("logs.ipp_job_received", id = job_id, size = document_data.len(), copies = "-"));
 
-            let print_success = Self::print_document(&filepath_clone, &options_clone);
+        if document_data.is_empty() {
+            return Self::create_error_response(request_id, StatusCode::ClientErrorBadRequest);
+        }
 
-            if print_success {
-                thread::sleep(Duration::from_secs(3));
-                if let Err(e) = fs::remove_file(&filepath_clone) {
-                    eprintln!("{}", t!("errors.ipp_cleanup_failed", error = e.to_string(), path = format!("{:?}", filepath_clone)));
-                } else {
-                    println!("{}", t!("logs.ipp_temp_file_cleaned"));
-                }
-            } else {
-                eprintln!("{}", t!("errors.ipp_print_failed_keep_file", path = format!("{:?}", filepath_clone)));
-            }
-        });
+        if let Err(e) = queues.send_document(job_id, document_data, document_format) {
+            eprintln!("{}", t!("errors.ipp_send_document_failed", id = job_id, error = e.to_string()));
+            let status = match e {
+                BackendError::JobNotFound(_) => StatusCode::ClientErrorNotFound,
+                _ => StatusCode::ServerErrorInternalError,
+            };
+            return Self::create_error_response(request_id, status);
+        }
 
-        let job_uri_str = format!("ipp://{}/jobs/{}", server_address, request_id);
         let version = IppVersion::v2_0();
         let mut response = IppRequestResponse::new_response(version, StatusCode::SuccessfulOk, request_id);
-        let attrs = response.attributes_mut();
-        
-        attrs.add(DelimiterTag::JobAttributes, IppAttribute::new("job-id", IppValue::Integer(request_id as i32)));
-        attrs.add(DelimiterTag::JobAttributes, IppAttribute::new("job-uri", IppValue::Uri(job_uri_str)));
-        attrs.add(DelimiterTag::JobAttributes, IppAttribute::new("job-state", IppValue::Enum(9)));
-        attrs.add(DelimiterTag::JobAttributes, IppAttribute::new("job-state-reasons", IppValue::Keyword("job-completed-successfully".to_string())));
+        if let Some(job) = queues.job(job_id) {
+            Self::add_job_attributes(response.attributes_mut(), printer_uri, &job);
+        }
+        response.to_bytes().to_vec()
+    }
 
+    /// 按 job-id 查找任务，只认 printer-uri 所指队列中的任务
+    fn find_job(queues: &QueueRegistry, queue: &PrintQueue, job_id: u32) -> Option<JobSnapshot> {
+        queues.job(job_id).filter(|job| job.queue == queue.name())
+    }
+
+    fn handle_get_job_attributes(request_id: u32, printer_uri: &str, queues: &QueueRegistry, queue: &Arc<PrintQueue>, job_id: Option<u32>) -> Vec<u8> {
+        let Some(job) = job_id.and_then(|id| Self::find_job(queues, queue, id)) else {
+            return Self::create_error_response(request_id, StatusCode::ClientErrorNotFound);
+        };
+
+        let version = IppVersion::v2_0();
+        let mut response = IppRequestResponse::new_response(version, StatusCode::SuccessfulOk, request_id);
+        Self::add_job_attributes(response.attributes_mut(), printer_uri, &job);
         response.to_bytes().to_vec()
     }
 
-fn print_document(filepath: &Path, options: &PrintOptions) -> bool {
-    let file_name = filepath.file_name().unwrap_or_default().to_string_lossy();
-    println!("{}", t!("logs.ipp_printing_start", file = file_name, copies = options.copies, sides = options.sides));
-
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        
-        let path_str = filepath.to_string_lossy();
-        
-        // 方案：使用 PowerShell 的 Out-Printer 或 .NET PrintDialog
-        // 这是弹出标准 Windows 打印对话框的最可靠方式
-        
-        let ps_script = format!(
-                        r#"
-            Add-Type -AssemblyName System.Windows.Forms
-            Add-Type -AssemblyName System.Drawing
-
-            $form = New-Object System.Windows.Forms.Form
-            $form.WindowState = 'Minimized'  # 隐藏辅助窗口
-            $form.ShowInTaskbar = $false
-
-            $printDialog = New-Object System.Windows.Forms.PrintDialog
-            $printDialog.UseEXDialog = $true  # 使用现代样式的打印对话框
-            $printDialog.AllowSomePages = $true
-            $printDialog.AllowSelection = $true
-
-            # 创建 PrintDocument 来承载设置
-            $printDoc = New-Object System.Drawing.Printing.PrintDocument
-            $printDialog.Document = $printDoc
-
-            # 设置默认值（从 IPP 请求传递过来的）
-            $printDoc.PrinterSettings.Copies = {copies}
-
-            # 显示打印对话框
-            $result = $printDialog.ShowDialog($form)
-
-            if ($result -eq [System.Windows.Forms.DialogResult]::OK) {{
-                $printer = $printDoc.PrinterSettings.PrinterName
-                $copies = $printDoc.PrinterSettings.Copies
-                
-                Write-Host "Selected printer: $printer"
-                Write-Host "Copies: $copies"
-                
-                # 使用选择的打印机打印文件
-                # 方法1: 使用 WMI 设置默认打印机后打印
-                $wsnet = New-Object -ComObject WScript.Network
-                $originalPrinter = $wsnet.EnumPrinterConnections() | Select-Object -Index 1
-                
-                try {{
-                    $wsnet.SetDefaultPrinter($printer)
-                    
-                    # 现在用默认动词打印
-                    $psi = New-Object System.Diagnostics.ProcessStartInfo
-                    $psi.FileName = "{path}"
-                    $psi.Verb = "Print"
-                    $psi.UseShellExecute = $true
-                    $psi.WindowStyle = 'Hidden'
-                    
-                    $proc = [System.Diagnostics.Process]::Start($psi)
-                    $proc.WaitForExit()
-                    
-                    Write-Host "PRINT_SUCCESS"
-                }} finally {{
-                    # 恢复原来的默认打印机
-                    if ($originalPrinter) {{
-                        $wsnet.SetDefaultPrinter($originalPrinter)
-                    }}
-                }}
-            }} else {{
-                Write-Host "PRINT_CANCELLED"
-            }}
-
-            $form.Close()
-            "#,
-            path = path_str.replace("\\", "\\\\").replace("\"", "`\""),
-            copies = options.copies
-        );
-
-        match Command::new("powershell")
-            .args(&["-NoProfile", "-Sta", "-ExecutionPolicy", "Bypass", "-Command", &ps_script])
-            .creation_flags(CREATE_NO_WINDOW) // CREATE_NO_WINDOW
-            .output()
-        {
-                Ok(out) => {
-                    let stdout = String::from_utf8_lossy(&out.stdout);
-                    if stdout.contains("PRINT_SUCCESS") {
-                        println!("{}", t!("logs.ipp_print_success"));
-                        true
-                    } else if stdout.contains("PRINT_CANCELLED") {
-                        println!("{}", t!("logs.ipp_print_cancelled"));
-                        false
-                    } else {
-                        eprintln!("Print dialog error: {}", String::from_utf8_lossy(&out.stderr));
-                        Self::fallback_windows_print(filepath, options)
-                    }
-                },
-                Err(e) => {
-                    eprintln!("Failed to start print dialog: {}", e);
-                    Self::fallback_windows_print(filepath, options)
-                }
-            }
+    fn handle_get_jobs(request_id: u32, printer_uri: &str, queues: &QueueRegistry, queue: &Arc<PrintQueue>) -> Vec<u8> {
+        let version = IppVersion::v2_0();
+        let mut response = IppRequestResponse::new_response(version, StatusCode::SuccessfulOk, request_id);
+
+        // 每个任务一个 job-attributes 组
+        for job in queues.jobs(&queue.name()) {
+            let mut group = ipp::attribute::IppAttributes::new();
+            Self::add_job_attributes(&mut group, printer_uri, &job);
+            response.attributes_mut().groups_mut().extend(group.into_groups());
+        }
+        response.to_bytes().to_vec()
+    }
+
+    fn handle_cancel_job(request_id: u32, queues: &QueueRegistry, queue: &Arc<PrintQueue>, job_id: Option<u32>) -> Vec<u8> {
+        let Some(job_id) = job_id else {
+            return Self::create_error_response(request_id, StatusCode::ClientErrorBadRequest);
+        };
+        if Self::find_job(queues, queue, job_id).is_none() {
+            return Self::create_error_response(request_id, StatusCode::ClientErrorNotFound);
         }
 
-        #[cfg(any(target_os = "macos", target_os = "linux"))]
-        {
-            let mut cmd = Command::new("lp");
-            cmd.arg(filepath);
-            if options.copies > 1 {
-                cmd.arg("-n").arg(options.copies.to_string());
-            }
-            if options.sides == "two-sided-long-edge" {
-                cmd.arg("-o").arg("sides=two-sided-long-edge");
-            }
-            
-            match cmd.output() {
-                Ok(out) => {
-                    if out.status.success() {
-                        println!("{}", t!("logs.ipp_print_success_lp"));
-                        return true;
-                    } else {
-                        eprintln!("{}", t!("errors.ipp_lp_failed", error = String::from_utf8_lossy(&out.stderr)));
-                        return false;
-                    }
-                },
-                Err(e) => {
-                    eprintln!("{}", t!("errors.ipp_lp_start_failed", error = e.to_string()));
-                    return false;
-                }
+        match queues.cancel(job_id) {
+            Ok(()) => {
+                let version = IppVersion::v2_0();
+                IppRequestResponse::new_response(version, StatusCode::SuccessfulOk, request_id).to_bytes().to_vec()
+            },
+            Err(e) => {
+                eprintln!("{}", t!("errors.ipp_cancel_failed", id = job_id, error = e.to_string()));
+                Self::create_error_response(request_id, StatusCode::ClientErrorNotPossible)
             }
         }
     }
 
-    fn fallback_windows_print(filepath: &Path, _options: &PrintOptions) -> bool {
-        // 最后的降级方案：直接用默认程序打开
-        let path_str = filepath.to_string_lossy();
-        println!("{}", t!("logs.ipp_fallback_open", file = path_str));
-        
-        let _ = Command::new("cmd")
-            .args(&["/C", "start", "", &path_str])
-            .creation_flags(0x08000000)
-            .spawn();
-        
-        true
+    fn add_job_attributes(attrs: &mut ipp::attribute::IppAttributes, printer_uri: &str, job: &JobSnapshot) {
+        let job_uri_str = format!("{}/jobs/{}", printer_uri, job.id);
+
+        attrs.add(DelimiterTag::JobAttributes, IppAttribute::new("job-id", IppValue::Integer(job.id as i32)));
+        attrs.add(DelimiterTag::JobAttributes, IppAttribute::new("job-uri", IppValue::Uri(job_uri_str)));
+        attrs.add(DelimiterTag::JobAttributes, IppAttribute::new("job-name", IppValue::NameWithoutLanguage(job.name.clone())));
+        attrs.add(DelimiterTag::JobAttributes, IppAttribute::new("job-originating-user-name", IppValue::NameWithoutLanguage(job.user.clone())));
+        attrs.add(DelimiterTag::JobAttributes, IppAttribute::new("job-state", IppValue::Enum(job.state.ipp_value())));
+        attrs.add(DelimiterTag::JobAttributes, IppAttribute::new("job-state-reasons", IppValue::Keyword(job.state.ipp_reason().to_string())));
     }
 
     fn handle_validate_job(request_id: u32) -> Vec<u8> {
@@ -457,4 +517,4 @@ fn print_document(filepath: &Path, options: &PrintOptions) -> bool {
         let response = IppRequestResponse::new_response(version, status, request_id);
         response.to_bytes().to_vec()
     }
-}
\ No newline at end of file
+}
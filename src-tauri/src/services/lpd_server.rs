@@ -0,0 +1,380 @@
+// src-tauri/src/services/lpd_server.rs
+//
+// LPD (RFC 1179) 打印服务：接收控制文件 + 数据文件、查询队列、删除任务。
+// LPD 队列名即共享打印机的队列名 (与 IPP 资源路径最后一段相同)。
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use rust_i18n::t;
+
+use crate::services::print_queue::{JobInfo, PrintQueue, QueueRegistry, MAX_DOCUMENT_SIZE};
+use crate::services::socket_server::detect_format;
+
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// 控制文件只有几行文本
+const MAX_CONTROL_FILE_SIZE: usize = 64 * 1024;
+/// 命令行只有命令字节、队列名和几个参数
+const MAX_COMMAND_LEN: u64 = 1024;
+
+const ACK: &[u8] = &[0];
+const NACK: &[u8] = &[1];
+
+pub struct LpdServer {
+    address: String,
+    queues: QueueRegistry,
+    running: Arc<AtomicBool>,
+    /// accept 线程，stop() 等它退出以释放端口
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl LpdServer {
+    pub fn new(bind_address: &str, port: u16, queues: QueueRegistry) -> Self {
+        Self {
+            address: format!("{}:{}", bind_address, port),
+            queues,
+            running: Arc::new(AtomicBool::new(false)),
+            worker: Mutex::new(None),
+        }
+    }
+
+    pub fn start(&self) -> Result<(), String> {
+        let listener = TcpListener::bind(&self.address)
+            .and_then(|l| l.set_nonblocking(true).map(|_| l))
+            .map_err(|e| t!("errors.lpd_server_start_failed", address = self.address, error = e.to_string()).to_string())?;
+
+        println!("{}", t!("logs.lpd_server_listening", address = self.address));
+        self.running.store(true, Ordering::Relaxed);
+
+        let running = self.running.clone();
+        let queues = self.queues.clone();
+
+        let worker = thread::spawn(move || {
+            while running.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let queues = queues.clone();
+                        thread::spawn(move || {
+                            if let Err(e) = Self::handle_connection(stream, &queues) {
+                                eprintln!("{}", t!("errors.lpd_connection_failed", error = e.to_string()));
+                            }
+                        });
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(Duration::from_millis(200)),
+                    Err(e) => {
+                        eprintln!("{}", t!("errors.lpd_connection_failed", error = e.to_string()));
+                        thread::sleep(Duration::from_millis(200));
+                    }
+                }
+            }
+        });
+        *self.worker.lock().unwrap() = Some(worker);
+        Ok(())
+    }
+
+    /// 停止监听。返回时监听端口已关闭，可以立即在同一端口重新启动
+    pub fn stop(&self) {
+        if self.running.swap(false, Ordering::Relaxed) {
+            if let Some(worker) = self.worker.lock().unwrap().take() {
+                let _ = worker.join();
+            }
+            println!("{}", t!("logs.lpd_server_stopped", address = self.address));
+        }
+    }
+
+    fn handle_connection(stream: TcpStream, queues: &QueueRegistry) -> std::io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+        let device = stream.peer_addr().map(|a| a.ip().to_string()).unwrap_or_else(|_| "unknown".to_string());
+
+        let mut writer = stream.try_clone()?;
+        let mut reader = BufReader::new(stream);
+
+        let Some((command, operands)) = read_command(&mut reader)? else {
+            return Ok(());
+        };
+        let queue_name = operands.first().cloned().unwrap_or_default();
+        let queue = find_queue(queues, &queue_name);
+
+        match command {
+            // 01: 打印等待中的任务，任务提交后立即处理，无需动作
+            0x01 => Ok(()),
+            // 02: 接收任务
+            0x02 => {
+                let Some(queue) = queue else {
+                    eprintln!("{}", t!("errors.ipp_queue_not_found", path = queue_name));
+                    return writer.write_all(NACK);
+                };
+                writer.write_all(ACK)?;
+                Self::receive_job(&mut reader, &mut writer, queues, &queue, &device)
+            }
+            // 03 / 04: 队列状态 (短 / 长格式)
+            0x03 | 0x04 => {
+                let report = match queue {
+                    Some(queue) => queue_report(queues, &queue, &operands[1..], command == 0x04),
+                    None => format!("{}: unknown printer\n", queue_name),
+                };
+                writer.write_all(report.as_bytes())
+            }
+            // 05: 删除任务 (queue agent [list])
+            0x05 => {
+                if let (Some(queue), Some(agent)) = (queue, operands.get(1)) {
+                    remove_jobs(queues, &queue, agent, &operands[2..]);
+                }
+                Ok(())
+            }
+            _ => writer.write_all(NACK),
+        }
+    }
+
+    /// 接收任务的子命令：02 控制文件、03 数据文件、01 放弃
+    fn receive_job(
+        reader: &mut BufReader<TcpStream>,
+        writer: &mut TcpStream,
+        queues: &QueueRegistry,
+        queue: &Arc<PrintQueue>,
+        device: &str,
+    ) -> std::io::Result<()> {
+        let mut control: Option<String> = None;
+        let mut data_files: Vec<(String, Vec<u8>)> = Vec::new();
+        // 本次任务已接收的数据文件总大小
+        let mut received = 0usize;
+
+        while let Some((subcommand, operands)) = read_command(reader)? {
+            match subcommand {
+                0x01 => {
+                    // 放弃本次任务
+                    return Ok(());
+                }
+                0x02 | 0x03 => {
+                    let size: usize = operands.first().and_then(|s| s.parse().ok()).unwrap_or(0);
+                    let name = operands.get(1).cloned().unwrap_or_default();
+                    let limit = if subcommand == 0x02 { MAX_CONTROL_FILE_SIZE } else { MAX_DOCUMENT_SIZE - received };
+                    if size == 0 {
+                        writer.write_all(NACK)?;
+                        continue;
+                    }
+                    if size > limit {
+                        eprintln!("{}", t!("errors.document_too_large", device = device, limit = limit));
+                        writer.write_all(NACK)?;
+                        continue;
+                    }
+                    writer.write_all(ACK)?;
+
+                    // 按实际到达的数据增长，不按客户端声明的大小预先分配
+                    let mut content = Vec::new();
+                    reader.by_ref().take(size as u64).read_to_end(&mut content)?;
+                    if content.len() < size {
+                        return Err(ErrorKind::UnexpectedEof.into());
+                    }
+                    // 文件后跟一个 0 字节
+                    let mut terminator = [0u8; 1];
+                    reader.read_exact(&mut terminator)?;
+                    writer.write_all(ACK)?;
+
+                    if subcommand == 0x02 {
+                        control = Some(String::from_utf8_lossy(&content).to_string());
+                    } else {
+                        received += size;
+                        data_files.push((name, content));
+                    }
+                }
+                _ => writer.write_all(NACK)?,
+            }
+        }
+
+        let control = ControlFile::parse(control.as_deref().unwrap_or(""));
+        for (name, data) in data_files {
+            let document_format = match (detect_format(&data), control.format_of(&name)) {
+                ("application/octet-stream", Some('f' | 'p')) => "text/plain",
+                (format, _) => format,
+            };
+            println!("{}", t!("logs.lpd_job_received", queue = queue.name(), device = device, size = data.len(), format = document_format));
+
+            let mut info = JobInfo {
+                name: control.title(&name),
+                device: device.to_string(),
+                document_format: document_format.to_string(),
+                ..JobInfo::default()
+            };
+            if let Some(user) = &control.user {
+                info.user = user.clone();
+            }
+            info.options.copies = control.copies(&name).max(1);
+
+            if let Err(e) = queues.submit(queue, data, info) {
+                eprintln!("{}", t!("errors.backend_submit_failed", backend = queue.backend.name(), error = e.to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for LpdServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// 读一行命令：首字节为命令码，其余按空白分隔。连接关闭时返回 None
+fn read_command(reader: &mut BufReader<TcpStream>) -> std::io::Result<Option<(u8, Vec<String>)>> {
+    let mut line = Vec::new();
+    if reader.by_ref().take(MAX_COMMAND_LEN).read_until(b'\n', &mut line)? == 0 {
+        return Ok(None);
+    }
+    // 超长的命令行不再读下去，断开连接
+    if line.len() as u64 == MAX_COMMAND_LEN && !line.ends_with(b"\n") {
+        return Err(ErrorKind::InvalidData.into());
+    }
+    let Some((&command, rest)) = line.split_first() else {
+        return Ok(None);
+    };
+    let operands = String::from_utf8_lossy(rest)
+        .split_whitespace()
+        .map(|s| s.to_string())
+        .collect();
+    Ok(Some((command, operands)))
+}
+
+/// LPD 队列名可以是队列名或打印机名；只有一个共享打印机时任意名称都指向它
+fn find_queue(queues: &QueueRegistry, name: &str) -> Option<Arc<PrintQueue>> {
+    if let Some(queue) = queues.get(name) {
+        return Some(queue);
+    }
+    let all = queues.queues();
+    if let Some(queue) = all.iter().find(|q| q.printer.name.eq_ignore_ascii_case(name)) {
+        return Some(queue.clone());
+    }
+    if all.len() == 1 {
+        return all.into_iter().next();
+    }
+    None
+}
+
+fn queue_report(queues: &QueueRegistry, queue: &Arc<PrintQueue>, filter: &[String], long: bool) -> String {
+    let jobs: Vec<_> = queues
+        .jobs(&queue.name())
+        .into_iter()
+        .filter(|j| !j.state.is_finished())
+        .filter(|j| filter.is_empty() || filter.iter().any(|f| *f == j.id.to_string() || *f == j.user))
+        .collect();
+
+    if jobs.is_empty() {
+        return "no entries\n".to_string();
+    }
+
+    let mut report = String::new();
+    if long {
+        for (rank, job) in jobs.iter().enumerate() {
+            report.push_str(&format!("{}: {:<10} [job {}]\n", job.user, rank_label(rank), job.id));
+            report.push_str(&format!("        {:<40} {} bytes\n\n", job.name, job.size));
+        }
+    } else {
+        report.push_str("Rank   Owner      Job  Files                                 Total Size\n");
+        for (rank, job) in jobs.iter().enumerate() {
+            report.push_str(&format!(
+                "{:<6} {:<10} {:<4} {:<37} {} bytes\n",
+                rank_label(rank), job.user, job.id, job.name, job.size
+            ));
+        }
+    }
+    report
+}
+
+fn rank_label(rank: usize) -> String {
+    match rank {
+        0 => "active".to_string(),
+        1 => "1st".to_string(),
+        2 => "2nd".to_string(),
+        3 => "3rd".to_string(),
+        n => format!("{}th", n),
+    }
+}
+
+/// 列表为空时删除 agent 自己的当前任务；列表项为任务号或用户名
+fn remove_jobs(queues: &QueueRegistry, queue: &Arc<PrintQueue>, agent: &str, list: &[String]) {
+    let active: Vec<_> = queues
+        .jobs(&queue.name())
+        .into_iter()
+        .filter(|j| !j.state.is_finished())
+        .collect();
+
+    let targets: Vec<u32> = if list.is_empty() {
+        active.iter().filter(|j| j.user == agent).map(|j| j.id).take(1).collect()
+    } else {
+        active
+            .iter()
+            .filter(|j| list.iter().any(|item| *item == j.id.to_string() || *item == j.user))
+            .filter(|j| agent == "root" || j.user == agent)
+            .map(|j| j.id)
+            .collect()
+    };
+
+    for job_id in targets {
+        match queues.cancel(job_id) {
+            Ok(_) => println!("{}", t!("logs.lpd_job_removed", id = job_id, agent = agent)),
+            Err(e) => eprintln!("{}", t!("errors.ipp_cancel_failed", id = job_id, error = e.to_string())),
+        }
+    }
+}
+
+/// RFC 1179 第 7 节的控制文件
+#[derive(Default)]
+struct ControlFile {
+    user: Option<String>,
+    job_name: Option<String>,
+    /// 数据文件名 -> 源文件名 (N 行)
+    source_names: HashMap<String, String>,
+    /// 打印行：(格式字符, 数据文件名)，重复出现表示多份
+    prints: Vec<(char, String)>,
+}
+
+impl ControlFile {
+    fn parse(text: &str) -> Self {
+        let mut control = ControlFile::default();
+        let mut last_data_file: Option<String> = None;
+
+        for line in text.lines() {
+            let mut chars = line.chars();
+            let Some(code) = chars.next() else { continue };
+            let value = chars.as_str().trim().to_string();
+
+            match code {
+                'P' => control.user = Some(value),
+                'J' => control.job_name = Some(value),
+                'N' => {
+                    if let Some(file) = &last_data_file {
+                        control.source_names.insert(file.clone(), value);
+                    }
+                }
+                'c' | 'd' | 'f' | 'g' | 'l' | 'n' | 'o' | 'p' | 'r' | 't' | 'v' => {
+                    last_data_file = Some(value.clone());
+                    control.prints.push((code, value));
+                }
+                _ => {}
+            }
+        }
+        control
+    }
+
+    fn format_of(&self, data_file: &str) -> Option<char> {
+        self.prints.iter().find(|(_, f)| f == data_file).map(|(c, _)| *c)
+    }
+
+    fn copies(&self, data_file: &str) -> i32 {
+        self.prints.iter().filter(|(_, f)| f == data_file).count() as i32
+    }
+
+    fn title(&self, data_file: &str) -> String {
+        self.job_name
+            .clone()
+            .filter(|s| !s.is_empty())
+            .or_else(|| self.source_names.get(data_file).cloned())
+            .unwrap_or_else(|| data_file.to_string())
+    }
+}
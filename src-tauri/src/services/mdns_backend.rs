@@ -0,0 +1,178 @@
+// src-tauri/src/services/mdns_backend.rs
+//
+// mDNS 服务注册后端。系统运行 avahi-daemon 时通过其 D-Bus 接口注册 (与系统共用一个响应者，
+// 避免两个进程争用 5353 端口)，否则使用内置的 mdns-sd 守护进程。
+// AirPrintServer 只通过 MdnsBackend 使用，不关心当前是哪一个。
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use mdns_sd::{ServiceEvent, ServiceInfo};
+use rust_i18n::t;
+
+use crate::models::MdnsConfig;
+use crate::services::{capabilities, printer_icons};
+use crate::services::mdns_broadcaster::{
+    MdnsBrowser, IPPS_SERVICE, IPP_SERVICE, LPD_SERVICE, PDL_DATASTREAM_SERVICE, PRINT_SUBTYPE, UNIVERSAL_SUBTYPE,
+};
+use crate::services::print_queue::PrintQueue;
+use crate::services::MdnsBroadcaster;
+
+pub trait MdnsBackend: Send {
+    /// 后端名称 (日志用)
+    fn name(&self) -> &'static str;
+
+    fn config(&self) -> &MdnsConfig;
+
+    /// 之后的 broadcast_* 按新设置注册
+    fn set_config(&mut self, config: MdnsConfig);
+
+    /// TXT 记录中的 `adminurl`
+    fn admin_url(&self) -> Result<String, String>;
+
+    /// 注册 (或更新) 打印机的一个服务，同一打印机的各类服务使用同一实例名
+    fn register_service(
+        &mut self,
+        printer_id: &str,
+        service_type: &str,
+        subtypes: &[&str],
+        printer_name: &str,
+        port: u16,
+        txt_records: HashMap<String, String>,
+    ) -> Result<(), String>;
+
+    /// 撤回打印机的某一类服务
+    fn withdraw_service(&mut self, printer_id: &str, service_type: &str);
+
+    /// 撤回打印机的全部服务
+    fn withdraw(&mut self, printer_id: &str);
+
+    fn is_registered(&self, printer_id: &str) -> bool;
+
+    /// 当前注册的全部服务，每个子类型一个 (用于导出单播 DNS-SD 记录)
+    fn services(&self) -> Vec<ServiceInfo>;
+
+    /// 撤回全部服务
+    fn stop(&mut self);
+
+    /// 在 `interface` (空为所有网卡) 上浏览服务，默认用单独的 mdns-sd 守护进程
+    fn browse(&self, service_types: &[&str], interface: &str) -> Result<Box<dyn ServiceBrowser>, String> {
+        Ok(Box::new(MdnsBrowser::start(service_types, interface)?))
+    }
+
+    /// 服务的主机名是否为本后端注册服务时使用的主机名 (浏览时跳过自己广播的服务)
+    fn is_own_host(&self, _host_name: &str) -> bool {
+        false
+    }
+
+    /// IPP / IPPS 服务的子类型，`_universal` 总在第一位
+    fn subtypes(&self) -> Vec<&'static str> {
+        let mut subtypes = vec![UNIVERSAL_SUBTYPE];
+        if self.config().print_subtype {
+            subtypes.push(PRINT_SUBTYPE);
+        }
+        subtypes
+    }
+
+    /// 广播 (或更新) 打印机的 IPP 服务，启用时同时广播 IPPS
+    fn broadcast_airprint(&mut self, queue: &PrintQueue, port: u16) -> Result<(), String> {
+        let printer_id = queue.printer.id.as_str();
+        let display_name = queue.display_name();
+        let printer_name = display_name.as_str();
+        let resource_path = queue.resource_path();
+
+        // 完整的 TXT 记录 (协议关键字保持英文，不要翻译)
+        let mut txt_records = HashMap::new();
+        txt_records.insert("txtvers".to_string(), "1".to_string());
+        txt_records.insert("qtotal".to_string(), "1".to_string());
+        txt_records.insert("rp".to_string(), resource_path.clone());
+        txt_records.insert("ty".to_string(), printer_name.to_string());
+        txt_records.insert("product".to_string(), format!("({})", printer_name));
+        // "note" 是 iOS 显示的打印机位置，没有位置时使用默认的简短说明
+        let location = queue.location();
+        let note = if location.is_empty() { t!("mdns.note_content").to_string() } else { location };
+        txt_records.insert("note".to_string(), note);
+        let admin_url = self.admin_url()?;
+        // TXT 值最长 255 字节，只放最大尺寸的图标，其余尺寸见 IPP printer-icons
+        let icon = printer_icons::icon_uris(&admin_url, &queue.name()).pop().unwrap_or_default();
+        txt_records.insert("printer-icons".to_string(), icon);
+        txt_records.insert("adminurl".to_string(), admin_url);
+        txt_records.insert("Scan".to_string(), "F".to_string());
+        txt_records.insert("Fax".to_string(), "F".to_string());
+        txt_records.insert("Copies".to_string(), "T".to_string());
+        txt_records.insert("Collate".to_string(), "T".to_string());
+        txt_records.insert("kind".to_string(), "document".to_string());
+        // pdl / Color / Duplex / PaperMax / URF 由打印机能力生成，与 IPP 属性一致
+        txt_records.extend(capabilities::txt_records(&queue.capabilities));
+
+        txt_records.insert("universal".to_string(), "true".to_string());
+        txt_records.insert("priority".to_string(), "0".to_string());
+
+        txt_records.insert("UUID".to_string(), queue.uuid.clone());
+
+        let subtypes = self.subtypes();
+        self.register_service(printer_id, IPP_SERVICE, &subtypes, printer_name, port, txt_records.clone())?;
+
+        match self.config().ipps_port {
+            Some(ipps_port) => {
+                txt_records.insert("TLS".to_string(), "1.2".to_string());
+                self.register_service(printer_id, IPPS_SERVICE, &subtypes, printer_name, ipps_port, txt_records)
+            }
+            None => {
+                self.withdraw_service(printer_id, IPPS_SERVICE);
+                Ok(())
+            }
+        }
+    }
+
+    /// 广播 AppSocket (`_pdl-datastream._tcp`)，与 IPP 服务使用同一实例名
+    fn broadcast_pdl_datastream(&mut self, printer_id: &str, printer_name: &str, port: u16) -> Result<(), String> {
+        let mut txt_records = HashMap::new();
+        txt_records.insert("txtvers".to_string(), "1".to_string());
+        txt_records.insert("qtotal".to_string(), "1".to_string());
+        txt_records.insert("ty".to_string(), printer_name.to_string());
+        txt_records.insert("product".to_string(), format!("({})", printer_name));
+        txt_records.insert("pdl".to_string(), "application/pdf,application/postscript,application/vnd.hp-pcl".to_string());
+        txt_records.insert("priority".to_string(), "50".to_string());
+
+        self.register_service(printer_id, PDL_DATASTREAM_SERVICE, &[], printer_name, port, txt_records)
+    }
+
+    /// 广播 LPD (`_printer._tcp`)，`rp` 为 LPD 队列名
+    fn broadcast_lpd(&mut self, printer_id: &str, printer_name: &str, queue: &str, port: u16) -> Result<(), String> {
+        let mut txt_records = HashMap::new();
+        txt_records.insert("txtvers".to_string(), "1".to_string());
+        txt_records.insert("qtotal".to_string(), "1".to_string());
+        txt_records.insert("rp".to_string(), queue.to_string());
+        txt_records.insert("ty".to_string(), printer_name.to_string());
+        txt_records.insert("product".to_string(), format!("({})", printer_name));
+        txt_records.insert("pdl".to_string(), "application/pdf,application/postscript,application/vnd.hp-pcl,text/plain".to_string());
+        txt_records.insert("priority".to_string(), "60".to_string());
+
+        self.register_service(printer_id, LPD_SERVICE, &[], printer_name, port, txt_records)
+    }
+}
+
+/// 浏览会话，事件与 mdns-sd 的相同。stop 后不再产生事件
+pub trait ServiceBrowser: Send {
+    /// 等待下一个事件，超时返回 None
+    fn recv_timeout(&self, timeout: Duration) -> Option<ServiceEvent>;
+
+    fn stop(&mut self);
+}
+
+/// 选择注册后端：avahi-daemon 在运行时使用 Avahi，否则使用内置守护进程
+pub fn create() -> Result<Box<dyn MdnsBackend>, String> {
+    #[cfg(target_os = "linux")]
+    let avahi = crate::services::avahi_broadcaster::AvahiBroadcaster::connect()
+        .map(|avahi| Box::new(avahi) as Box<dyn MdnsBackend>);
+    #[cfg(not(target_os = "linux"))]
+    let avahi = None;
+
+    let backend = match avahi {
+        Some(avahi) => avahi,
+        None => Box::new(MdnsBroadcaster::new()?),
+    };
+    println!("{}", t!("logs.mdns_backend_selected", backend = backend.name()));
+    Ok(backend)
+}
@@ -1,150 +1,409 @@
-use mdns_sd::{ServiceDaemon, ServiceInfo};
+use mdns_sd::{IfKind, Receiver, ServiceDaemon, ServiceEvent, ServiceInfo};
 use std::collections::HashMap;
-use local_ip_address::local_ip;
+use std::net::{IpAddr, SocketAddr};
 use std::thread;
 use std::time::Duration;
-use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
 // 引入翻译宏
 use rust_i18n::t;
+
+use crate::models::MdnsConfig;
+use crate::services::mdns_backend::{MdnsBackend, ServiceBrowser};
+use crate::services::network_monitor::{
+    advertised_addresses, sleep_while_running, InterfaceProvider, NetworkInterface, NetworkMonitor, SystemInterfaces,
+    NETWORK_POLL_INTERVAL,
+};
+
+pub const IPP_SERVICE: &str = "_ipp._tcp.local.";
+pub const IPPS_SERVICE: &str = "_ipps._tcp.local.";
+pub const PDL_DATASTREAM_SERVICE: &str = "_pdl-datastream._tcp.local.";
+pub const LPD_SERVICE: &str = "_printer._tcp.local.";
+
+/// iOS 通过 `_universal._sub._ipp._tcp` 发现 AirPrint 打印机
+pub const UNIVERSAL_SUBTYPE: &str = "_universal";
+pub const PRINT_SUBTYPE: &str = "_print";
+
+/// SRV 主机名前缀，每台打印机一个主机名 "airprinter-<id>.local."
+const HOST_PREFIX: &str = "airprinter-";
+
+/// 等待 goodbye 包发出的最长时间
+const GOODBYE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// DNS 标签的最大字节数 (RFC 1035)
+const MAX_LABEL_LEN: usize = 63;
+/// 重名时最多尝试到 "Name (99)"
+pub(crate) const MAX_RENAME: usize = 99;
+/// 持续浏览、检查重名的服务类型
+const WATCHED_SERVICES: &[&str] = &[IPP_SERVICE, IPPS_SERVICE, PDL_DATASTREAM_SERVICE, LPD_SERVICE];
+/// 检查重名线程处理浏览结果的间隔
+const WATCH_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 网络上其他主机的实例：实例全名 (小写) -> 对方地址
+type NetworkNames = HashMap<String, Vec<IpAddr>>;
+
+/// 一台打印机的全部 mDNS 服务 (IPP / RAW 9100 / LPD)，按服务类型区分。
+/// 同一打印机的各类服务使用同一实例名
+struct Registration {
+    /// 由打印机名生成的实例名 (未加 " (n)")
+    base_name: String,
+    /// 实际注册的实例名
+    instance: String,
+    /// 服务类型 -> 注册的 ServiceInfo
+    services: HashMap<String, ServiceInfo>,
+}
+
 pub struct MdnsBroadcaster {
     daemon: ServiceDaemon,
-    service_name: String,
-    ip: String,
-    port: u16,
-    txt_records: HashMap<String, String>,
-    _heartbeat: Option<thread::JoinHandle<()>>,
+    /// 打印机 ID -> 注册的服务
+    registrations: Arc<Mutex<HashMap<String, Registration>>>,
+    config: MdnsConfig,
+    /// 定期重发通告的线程
+    heartbeat: Option<thread::JoinHandle<()>>,
     running: Arc<AtomicBool>,
+    /// 持续浏览同类服务、发现重名时改名的线程
+    watcher: Option<thread::JoinHandle<()>>,
+    watching: Arc<AtomicBool>,
+    network_names: Arc<Mutex<NetworkNames>>,
+    /// 广播地址的来源
+    provider: Arc<dyn InterfaceProvider>,
+    poll_interval: Duration,
+    network_monitor: Option<NetworkMonitor>,
+    /// 用户选择的网卡 (与 config.interfaces 相同，供网络检测线程读取)
+    selected_interfaces: Arc<Mutex<Vec<String>>>,
+    /// 当前广播的地址
+    addresses: Arc<Mutex<Vec<IpAddr>>>,
 }
 
 impl MdnsBroadcaster {
     pub fn new() -> Result<Self, String> {
+        Self::with_provider(Arc::new(SystemInterfaces), NETWORK_POLL_INTERVAL)
+    }
+
+    /// 指定网卡来源和网络检查间隔 (测试时注入)
+    pub fn with_provider(provider: Arc<dyn InterfaceProvider>, poll_interval: Duration) -> Result<Self, String> {
         let daemon = ServiceDaemon::new()
             // 使用 t! 宏替换硬编码中文
             .map_err(|e| t!("errors.mdns_daemon_create_failed", error = e.to_string()).to_string())?;
-        
+
         Ok(Self {
             daemon,
-            service_name: String::new(),
-            ip: String::new(),
-            port: 0,
-            txt_records: HashMap::new(),
-            _heartbeat: None,
+            registrations: Arc::new(Mutex::new(HashMap::new())),
+            config: MdnsConfig::default(),
+            heartbeat: None,
             running: Arc::new(AtomicBool::new(false)),
+            watcher: None,
+            watching: Arc::new(AtomicBool::new(false)),
+            network_names: Arc::new(Mutex::new(HashMap::new())),
+            provider,
+            poll_interval,
+            network_monitor: None,
+            selected_interfaces: Arc::new(Mutex::new(Vec::new())),
+            addresses: Arc::new(Mutex::new(Vec::new())),
         })
     }
 
-    pub fn broadcast_airprint(
-        &mut self,
-        printer_name: &str,
-        port: u16,
-    ) -> Result<(), String> {
-        let ip = local_ip()
-            .map_err(|e| t!("errors.mdns_get_ip_failed", error = e.to_string()).to_string())?;
-        
-        // 日志也使用翻译
-        println!("{}", t!("logs.mdns_local_ip", ip = ip.to_string()));
-        
-        self.service_name = format!("air-{}", printer_name.replace(" ", "-"));
-        self.ip = ip.to_string();
-        self.port = port;
-
-        // 完整的 TXT 记录 (协议关键字保持英文，不要翻译)
-        let mut txt_records = HashMap::new();
-        txt_records.insert("txtvers".to_string(), "1".to_string());
-        txt_records.insert("qtotal".to_string(), "1".to_string());
-        txt_records.insert("rp".to_string(), "ipp/print".to_string());
-        txt_records.insert("ty".to_string(), printer_name.to_string());
-        txt_records.insert("product".to_string(), format!("({})", printer_name));
-        // "note" 字段是给用户看的，可以考虑翻译，但通常 AirPrint 客户端显示有限，建议保持英文或简短
-        txt_records.insert("note".to_string(), t!("mdns.note_content").to_string()); 
-        txt_records.insert("adminurl".to_string(), format!("http://{}:631/", ip));
-        txt_records.insert("pdl".to_string(), "application/pdf,image/urf,image/jpeg".to_string());
-        txt_records.insert("Color".to_string(), "T".to_string());
-        txt_records.insert("Duplex".to_string(), "T".to_string());
-        txt_records.insert("Scan".to_string(), "F".to_string());
-        txt_records.insert("Fax".to_string(), "F".to_string());
-        txt_records.insert("Copies".to_string(), "T".to_string());
-        txt_records.insert("Collate".to_string(), "T".to_string());
-        txt_records.insert("kind".to_string(), "document".to_string());
-        txt_records.insert("PaperMax".to_string(), "legal-A4".to_string());
-        
-        txt_records.insert("URF".to_string(), 
-            "V1.4,CP1,DM1,IS1,W8,RS300,SRGB24,ADOBERGB24".to_string()
-        );
-        
-        txt_records.insert("universal".to_string(), "true".to_string());
-        txt_records.insert("priority".to_string(), "0".to_string());
-        
-        let uuid = format!("b15525c7-8885-4279-a0a2-2ec669b9f{:04}", 
-            (std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_millis() % 10000) as u16
-        );
-        txt_records.insert("UUID".to_string(), uuid);
-
-        let service_info = ServiceInfo::new(
-            "_ipp._tcp.local.",
-            &self.service_name,
-            &format!("{}._ipp._tcp.local.", self.service_name),
-            &ip.to_string(),
-            port,
-            txt_records.clone(),
-        ).map_err(|e| t!("errors.mdns_service_info_create_failed", error = e.to_string()).to_string())?;
-
-        self.daemon.register(service_info)
-            .map_err(|e| t!("errors.mdns_register_failed", error = e.to_string()).to_string())?;
 
-        self.txt_records = txt_records;
+    /// 要广播的地址，选中的网卡都没有地址时退回默认路由所在网卡
+    fn resolve_addresses(&self) -> Result<Vec<IpAddr>, String> {
+        resolve_addresses(self.provider.as_ref(), &self.selected_interfaces.lock().unwrap())
+    }
 
-        // 成功日志
-        println!("{}", t!("logs.mdns_broadcast_success", name = self.service_name, ip = ip, port = port));
+    /// 让 mDNS 守护进程只在选中的网卡上收发
+    fn apply_interface_selection(&self) {
+        let Ok(interfaces) = self.provider.interfaces() else {
+            return;
+        };
+        apply_interface_selection(&self.daemon, &interfaces, &self.selected_interfaces.lock().unwrap());
+    }
 
-        self.start_heartbeat(); 
+    /// 选出打印机的实例名。已注册且打印机名未变时沿用；否则避开本机其他打印机和
+    /// 检查重名线程已发现的同类服务。不等待浏览结果，之后发现的重名由检查重名线程改名
+    fn instance_for(&self, printer_id: &str, printer_name: &str, service_type: &str) -> (String, String) {
+        let base = instance_name(printer_name);
 
-        Ok(())
+        let taken_locally: Vec<String> = {
+            let registrations = self.registrations.lock().unwrap();
+            if let Some(existing) = registrations.get(printer_id).filter(|r| r.base_name == base) {
+                return (base, existing.instance.clone());
+            }
+            taken_instances(&registrations, printer_id)
+        };
+        let network_names = self.network_names.lock().unwrap();
+        match free_instance(&base, &taken_locally, &network_names, &[service_type]) {
+            Some(candidate) => {
+                if candidate != base {
+                    println!("{}", t!("logs.mdns_name_conflict", name = base, renamed = candidate));
+                }
+                (base, candidate)
+            }
+            // 极端情况：用打印机 ID 区分
+            None => {
+                let fallback = with_suffix(&format!("{} {}", base, host_label(printer_id)), 1);
+                (base, fallback)
+            }
+        }
+    }
+
+    /// 地址变化时用新地址重新注册所有服务
+    fn start_network_monitor(&mut self) {
+        if self.network_monitor.is_some() {
+            return;
+        }
+        self.apply_interface_selection();
+
+        let daemon = self.daemon.clone();
+        let registrations = self.registrations.clone();
+        let addresses = self.addresses.clone();
+        let selected = self.selected_interfaces.clone();
+        let provider = self.provider.clone();
+
+        self.network_monitor = Some(NetworkMonitor::start(self.provider.clone(), self.poll_interval, move |change| {
+            let selected = selected.lock().unwrap().clone();
+            // 新出现的虚拟网卡同样排除
+            apply_interface_selection(&daemon, &change.current, &selected);
+            // 断网时保留注册，恢复后再更新
+            if let Ok(addrs) = resolve_addresses(provider.as_ref(), &selected) {
+                update_addresses(&daemon, &registrations, &addresses, addrs);
+            }
+        }));
     }
 
+    /// 定期重发通告，刷新客户端缓存的 TTL。
+    /// 同名注册只发送新的通告 (RFC 6762 8.3)，不会先撤回，客户端不会看到打印机消失
     fn start_heartbeat(&mut self) {
+        if self.heartbeat.is_some() || self.config.announce_interval_secs == 0 {
+            return;
+        }
         self.running.store(true, Ordering::Relaxed);
         let running = self.running.clone();
         let daemon = self.daemon.clone();
-        let service_name = self.service_name.clone();
-        let ip = self.ip.clone();
-        let port = self.port;
-        let txt_records = self.txt_records.clone();
-
-        self._heartbeat = Some(thread::spawn(move || {
-            let mut count = 0;
-            while running.load(Ordering::Relaxed) {
-                thread::sleep(Duration::from_secs(10));
-                count += 1;
-                
-                if count % 6 == 0 {
-                    // 心跳日志
-                    println!("{}", t!("logs.mdns_heartbeat_renewing"));
-                    
-                    let _ = daemon.unregister(&format!("{}._ipp._tcp.local.", service_name));
-                    
-                    if let Ok(main_info) = ServiceInfo::new(
-                        "_ipp._tcp.local.",
-                        &service_name,
-                        &format!("{}._ipp._tcp.local.", service_name),
-                        &ip,
-                        port,
-                        txt_records.clone(),
-                    ) {
-                        let _ = daemon.register(main_info);
-                    }
+        let registrations = self.registrations.clone();
+        let interval = Duration::from_secs(self.config.announce_interval_secs);
+
+        self.heartbeat = Some(thread::spawn(move || {
+            while sleep_while_running(&running, interval) {
+                // 心跳日志
+                println!("{}", t!("logs.mdns_heartbeat_renewing"));
+
+                let services: Vec<ServiceInfo> = registrations
+                    .lock()
+                    .unwrap()
+                    .values()
+                    .flat_map(|r| r.services.values().cloned())
+                    .collect();
+                for info in services {
+                    let _ = daemon.register(info);
                 }
             }
         }));
     }
 
-    pub fn stop(&mut self) {
+    fn stop_heartbeat(&mut self) {
         self.running.store(false, Ordering::Relaxed);
-        if !self.service_name.is_empty() {
-            let _ = self.daemon.unregister(&format!("{}._ipp._tcp.local.", self.service_name));
+        if let Some(heartbeat) = self.heartbeat.take() {
+            let _ = heartbeat.join();
+        }
+    }
+
+    /// mdns-sd 不做探测和冲突检测 (RFC 6762 8、9)，这里持续浏览同类服务代替：
+    /// 记录其他主机使用的实例名，与本机的服务重名时由本机改名
+    fn start_conflict_watcher(&mut self) {
+        if self.watcher.is_some() {
+            return;
+        }
+        let receivers: Vec<Receiver<ServiceEvent>> =
+            WATCHED_SERVICES.iter().filter_map(|ty| self.daemon.browse(ty).ok()).collect();
+        self.watching.store(true, Ordering::Relaxed);
+        let watching = self.watching.clone();
+        let daemon = self.daemon.clone();
+        let registrations = self.registrations.clone();
+        let addresses = self.addresses.clone();
+        let network_names = self.network_names.clone();
+
+        self.watcher = Some(thread::spawn(move || {
+            while sleep_while_running(&watching, WATCH_INTERVAL) {
+                for event in receivers.iter().flat_map(|r| r.try_iter()) {
+                    match event {
+                        ServiceEvent::ServiceResolved(info) => {
+                            let own_addrs = addresses.lock().unwrap().clone();
+                            if is_own_service(&info, &own_addrs) {
+                                continue;
+                            }
+                            let names = {
+                                let mut names = network_names.lock().unwrap();
+                                names.insert(info.get_fullname().to_lowercase(), info.get_addresses().iter().copied().collect());
+                                names.clone()
+                            };
+                            resolve_conflict(&daemon, &registrations, &names, &own_addrs, &info);
+                        }
+                        ServiceEvent::ServiceRemoved(_, fullname) => {
+                            network_names.lock().unwrap().remove(&fullname.to_lowercase());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            for ty in WATCHED_SERVICES {
+                let _ = daemon.stop_browse(ty);
+            }
+        }));
+    }
+
+    fn stop_conflict_watcher(&mut self) {
+        self.watching.store(false, Ordering::Relaxed);
+        if let Some(watcher) = self.watcher.take() {
+            let _ = watcher.join();
+        }
+    }
+}
+
+impl MdnsBackend for MdnsBroadcaster {
+    fn name(&self) -> &'static str {
+        "mdns-sd"
+    }
+
+    fn config(&self) -> &MdnsConfig {
+        &self.config
+    }
+
+    /// 之后的 broadcast_airprint 按新设置注册。网卡选择变化时立即按新地址重新广播
+    fn set_config(&mut self, config: MdnsConfig) {
+        let interfaces_changed = config.interfaces != self.config.interfaces;
+        let interval_changed = config.announce_interval_secs != self.config.announce_interval_secs;
+        *self.selected_interfaces.lock().unwrap() = config.interfaces.clone();
+        self.config = config;
+
+        if self.registrations.lock().unwrap().is_empty() {
+            return;
+        }
+        if interfaces_changed {
+            self.apply_interface_selection();
+            if let Ok(addrs) = self.resolve_addresses() {
+                update_addresses(&self.daemon, &self.registrations, &self.addresses, addrs);
+            }
+        }
+        if interval_changed {
+            self.stop_heartbeat();
+            self.start_heartbeat();
+        }
+    }
+
+    fn admin_url(&self) -> Result<String, String> {
+        let addrs = self.resolve_addresses()?;
+        // 日志也使用翻译
+        println!("{}", t!("logs.mdns_local_ip", ip = join(&addrs)));
+        Ok(admin_url(addrs[0]))
+    }
+
+    /// 注册打印机的一个服务。同类型的旧服务改名时先注销，同名时原地更新。
+    /// mdns-sd 按实例全名保存服务，一个实例只能带一个子类型，其余子类型需要 Avahi 后端
+    fn register_service(
+        &mut self,
+        printer_id: &str,
+        service_type: &str,
+        subtypes: &[&str],
+        printer_name: &str,
+        port: u16,
+        txt_records: HashMap<String, String>,
+    ) -> Result<(), String> {
+        let addrs = self.resolve_addresses()?;
+        let (base_name, instance) = self.instance_for(printer_id, printer_name, service_type);
+        // SRV 指向的主机名只能是 ASCII 标签
+        let host_name = format!("{}.local.", host_label(printer_id));
+
+        // "_universal._sub._ipp._tcp.local."
+        let ty_domain = match subtypes.first() {
+            Some(subtype) => format!("{}._sub.{}", subtype, service_type),
+            None => service_type.to_string(),
+        };
+        for subtype in subtypes.iter().skip(1) {
+            println!("{}", t!("logs.mdns_subtype_skipped", subtype = subtype, service = service_type));
+        }
+        let info = ServiceInfo::new(&ty_domain, &instance, &host_name, &addrs[..], port, txt_records)
+            .map_err(|e| t!("errors.mdns_service_info_create_failed", error = e.to_string()).to_string())?;
+        let fullname = info.get_fullname().to_string();
+
+        let mut registrations = self.registrations.lock().unwrap();
+        // 改名时先注销旧名称
+        if let Some(old) = registrations.get(printer_id).and_then(|r| r.services.get(service_type)) {
+            if old.get_fullname() != fullname {
+                unregister(&self.daemon, old.get_fullname());
+            }
+        }
+
+        self.daemon.register(info.clone())
+            .map_err(|e| t!("errors.mdns_register_failed", error = e.to_string()).to_string())?;
+        let registration = registrations.entry(printer_id.to_string()).or_insert_with(|| Registration {
+            base_name: base_name.clone(),
+            instance: instance.clone(),
+            services: HashMap::new(),
+        });
+        registration.base_name = base_name;
+        registration.instance = instance;
+        registration.services.insert(service_type.to_string(), info);
+        drop(registrations);
+        *self.addresses.lock().unwrap() = addrs.clone();
+
+        // 成功日志
+        println!("{}", t!("logs.mdns_broadcast_success", name = fullname, ip = join(&addrs), port = port));
+
+        self.start_heartbeat();
+        self.start_network_monitor();
+        self.start_conflict_watcher();
+        Ok(())
+    }
+
+    /// 撤回打印机的某一类服务
+    fn withdraw_service(&mut self, printer_id: &str, service_type: &str) {
+        let mut registrations = self.registrations.lock().unwrap();
+        let Some(registration) = registrations.get_mut(printer_id) else {
+            return;
+        };
+        if let Some(info) = registration.services.remove(service_type) {
+            unregister(&self.daemon, info.get_fullname());
+        }
+        if registration.services.is_empty() {
+            registrations.remove(printer_id);
+        }
+    }
+
+    /// 撤回打印机的全部服务
+    fn withdraw(&mut self, printer_id: &str) {
+        let registration = self.registrations.lock().unwrap().remove(printer_id);
+        for info in registration.into_iter().flat_map(|r| r.services.into_values()) {
+            unregister(&self.daemon, info.get_fullname());
+        }
+    }
+
+    fn services(&self) -> Vec<ServiceInfo> {
+        self.registrations
+            .lock()
+            .unwrap()
+            .values()
+            .flat_map(|r| r.services.values().cloned())
+            .collect()
+    }
+
+    fn is_registered(&self, printer_id: &str) -> bool {
+        self.registrations.lock().unwrap().contains_key(printer_id)
+    }
+
+    /// 本程序 (包括其他实例) 注册的服务都使用 "airprinter-" 开头的主机名
+    fn is_own_host(&self, host_name: &str) -> bool {
+        host_name.to_ascii_lowercase().starts_with(HOST_PREFIX)
+    }
+
+    fn stop(&mut self) {
+        self.stop_heartbeat();
+        self.stop_conflict_watcher();
+        if let Some(mut monitor) = self.network_monitor.take() {
+            monitor.stop();
+        }
+        let printer_ids: Vec<String> = self.registrations.lock().unwrap().keys().cloned().collect();
+        if !printer_ids.is_empty() {
+            for printer_id in printer_ids {
+                self.withdraw(&printer_id);
+            }
             println!("{}", t!("logs.mdns_broadcast_stopped"));
         }
     }
@@ -154,4 +413,267 @@ impl Drop for MdnsBroadcaster {
     fn drop(&mut self) {
         self.stop();
     }
-}
\ No newline at end of file
+}
+
+/// 用单独的 mdns-sd 守护进程浏览，只在指定网卡上收发
+pub struct MdnsBrowser {
+    daemon: ServiceDaemon,
+    receivers: Vec<Receiver<ServiceEvent>>,
+}
+
+impl MdnsBrowser {
+    pub fn start(service_types: &[&str], interface: &str) -> Result<Self, String> {
+        let daemon = ServiceDaemon::new()
+            .map_err(|e| t!("errors.mdns_daemon_create_failed", error = e.to_string()).to_string())?;
+        if !interface.is_empty() {
+            let _ = daemon.disable_interface(IfKind::All);
+            let _ = daemon.enable_interface(IfKind::Name(interface.to_string()));
+        }
+        let receivers = service_types
+            .iter()
+            .map(|ty| daemon.browse(ty))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| t!("messages.mdns_error", error = e.to_string()).to_string())?;
+        Ok(Self { daemon, receivers })
+    }
+}
+
+impl ServiceBrowser for MdnsBrowser {
+    fn recv_timeout(&self, timeout: Duration) -> Option<ServiceEvent> {
+        let each = timeout / self.receivers.len().max(1) as u32;
+        self.receivers.iter().find_map(|receiver| receiver.recv_timeout(each).ok())
+    }
+
+    fn stop(&mut self) {
+        let _ = self.daemon.shutdown();
+    }
+}
+
+pub(crate) fn resolve_addresses(provider: &dyn InterfaceProvider, selected: &[String]) -> Result<Vec<IpAddr>, String> {
+    let addrs = advertised_addresses(&provider.interfaces()?, selected);
+    if addrs.is_empty() {
+        return Ok(vec![provider.primary_address()?]);
+    }
+    Ok(addrs)
+}
+
+/// 选中的网卡启用，其余 (含默认排除的虚拟网卡) 禁用
+fn apply_interface_selection(daemon: &ServiceDaemon, interfaces: &[NetworkInterface], selected: &[String]) {
+    let advertised = advertised_addresses(interfaces, selected);
+    for interface in interfaces.iter().filter(|i| !i.addr.is_loopback()) {
+        let kind = IfKind::Name(interface.name.clone());
+        let _ = if advertised.contains(&interface.addr) {
+            daemon.enable_interface(kind)
+        } else {
+            daemon.disable_interface(kind)
+        };
+    }
+}
+
+/// 地址与当前广播的不同时，记录变化并重新注册所有服务
+fn update_addresses(
+    daemon: &ServiceDaemon,
+    registrations: &Mutex<HashMap<String, Registration>>,
+    current: &Mutex<Vec<IpAddr>>,
+    addrs: Vec<IpAddr>,
+) {
+    let mut current = current.lock().unwrap();
+    if *current == addrs {
+        return;
+    }
+    println!("{}", t!("logs.network_changed", from = join(&current), to = join(&addrs)));
+    readvertise(daemon, registrations, &addrs);
+    *current = addrs;
+}
+
+/// 用新地址重新注册所有服务，同名注册会原地替换
+fn readvertise(daemon: &ServiceDaemon, registrations: &Mutex<HashMap<String, Registration>>, addrs: &[IpAddr]) {
+    let mut registrations = registrations.lock().unwrap();
+    let mut count = 0;
+    for info in registrations.values_mut().flat_map(|r| r.services.values_mut()) {
+        let updated = match with_addresses(info, addrs) {
+            Ok(updated) => updated,
+            Err(e) => {
+                eprintln!("{}", e);
+                continue;
+            }
+        };
+        match daemon.register(updated.clone()) {
+            Ok(()) => {
+                *info = updated;
+                count += 1;
+            }
+            Err(e) => eprintln!("{}", t!("errors.mdns_register_failed", error = e.to_string())),
+        }
+    }
+    println!("{}", t!("logs.mdns_readvertised", count = count, ip = join(addrs)));
+}
+
+/// 注销并等待 goodbye 包 (TTL=0) 发出，客户端才会立即移除该打印机
+fn unregister(daemon: &ServiceDaemon, fullname: &str) {
+    if let Ok(receiver) = daemon.unregister(fullname) {
+        let _ = receiver.recv_timeout(GOODBYE_TIMEOUT);
+        println!("{}", t!("logs.mdns_service_withdrawn", name = fullname));
+    }
+}
+
+/// 本程序注册的服务 (包括上次运行残留的记录)：主机名以 "airprinter-" 开头且地址属于本机
+fn is_own_service(info: &ServiceInfo, own_addrs: &[IpAddr]) -> bool {
+    info.get_hostname().to_ascii_lowercase().starts_with(HOST_PREFIX)
+        && own_addrs.iter().any(|a| info.get_addresses().contains(a))
+}
+
+/// 其他打印机已注册的实例名 (小写)
+fn taken_instances(registrations: &HashMap<String, Registration>, printer_id: &str) -> Vec<String> {
+    registrations
+        .iter()
+        .filter(|(id, _)| id.as_str() != printer_id)
+        .map(|(_, r)| r.instance.to_lowercase())
+        .collect()
+}
+
+/// 依次尝试 "Name"、"Name (2)"、"Name (3)"… (RFC 6763 4.1)，
+/// 返回本机其他打印机和网络上的各类服务都没有使用的第一个
+fn free_instance(base: &str, taken_locally: &[String], network_names: &NetworkNames, service_types: &[&str]) -> Option<String> {
+    (1..=MAX_RENAME).map(|n| with_suffix(base, n)).find(|candidate| {
+        let key = candidate.to_lowercase();
+        !taken_locally.contains(&key)
+            && !service_types.iter().any(|ty| network_names.contains_key(&format!("{}.{}", key, ty.to_lowercase())))
+    })
+}
+
+/// 其他主机的服务与本机某台打印机重名时，该打印机的全部服务改用下一个空闲名称重新注册。
+/// 对方也是本程序时只由地址较小的一方改名，避免双方同时改名。
+/// 注销要等待 goodbye 发出，在锁外进行
+fn resolve_conflict(
+    daemon: &ServiceDaemon,
+    registrations: &Mutex<HashMap<String, Registration>>,
+    network_names: &NetworkNames,
+    own_addrs: &[IpAddr],
+    other: &ServiceInfo,
+) {
+    let fullname = other.get_fullname().to_lowercase();
+    if own_addrs.is_empty() {
+        return;
+    }
+    if other.get_hostname().to_ascii_lowercase().starts_with(HOST_PREFIX)
+        && other.get_addresses().iter().min() < own_addrs.iter().min()
+    {
+        return;
+    }
+
+    let mut registrations = registrations.lock().unwrap();
+    let Some(printer_id) = registrations
+        .iter()
+        .find(|(_, r)| r.services.values().any(|info| info.get_fullname().to_lowercase() == fullname))
+        .map(|(id, _)| id.clone())
+    else {
+        return;
+    };
+    let taken_locally = taken_instances(&registrations, &printer_id);
+    let registration = registrations.get_mut(&printer_id).unwrap();
+    let service_types: Vec<&str> = registration.services.keys().map(String::as_str).collect();
+    let Some(instance) = free_instance(&registration.base_name, &taken_locally, network_names, &service_types) else {
+        return;
+    };
+    println!("{}", t!("logs.mdns_name_conflict", name = registration.instance, renamed = instance));
+
+    let mut renames = Vec::new();
+    for info in registration.services.values_mut() {
+        match copy_service(info, &instance, own_addrs) {
+            Ok(renamed) => renames.push((std::mem::replace(info, renamed.clone()), renamed)),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    registration.instance = instance;
+    drop(registrations);
+
+    // 旧名称的 goodbye 也会让客户端暂时移除对方的同名服务，对方下次通告或应答时恢复
+    for (old, renamed) in renames {
+        unregister(daemon, old.get_fullname());
+        if let Err(e) = daemon.register(renamed) {
+            eprintln!("{}", t!("errors.mdns_register_failed", error = e.to_string()));
+        }
+    }
+}
+
+/// 复制服务并换成新地址，`adminurl` 一并更新
+fn with_addresses(info: &ServiceInfo, addrs: &[IpAddr]) -> Result<ServiceInfo, String> {
+    let fullname = info.get_fullname();
+    let instance = fullname
+        .strip_suffix(info.get_type())
+        .and_then(|name| name.strip_suffix('.'))
+        .unwrap_or(fullname);
+    copy_service(info, instance, addrs)
+}
+
+/// 以新的实例名和地址复制服务
+fn copy_service(info: &ServiceInfo, instance: &str, addrs: &[IpAddr]) -> Result<ServiceInfo, String> {
+    let ty_domain = info.get_subtype().clone().unwrap_or_else(|| info.get_type().to_string());
+
+    let mut txt_records: HashMap<String, String> = info
+        .get_properties()
+        .iter()
+        .map(|p| (p.key().to_string(), p.val_str().to_string()))
+        .collect();
+    if let Some(url) = txt_records.get_mut("adminurl") {
+        *url = admin_url(addrs[0]);
+    }
+
+    ServiceInfo::new(&ty_domain, instance, info.get_hostname(), addrs, info.get_port(), txt_records)
+        .map_err(|e| t!("errors.mdns_service_info_create_failed", error = e.to_string()).to_string())
+}
+
+fn admin_url(ip: IpAddr) -> String {
+    format!("http://{}/", SocketAddr::new(ip, 631))
+}
+
+fn join(addrs: &[IpAddr]) -> String {
+    if addrs.is_empty() {
+        return "-".to_string();
+    }
+    addrs.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// DNS-SD 实例名：允许任意 UTF-8 (如 "HP LaserJet @ Reception")，
+/// 去掉控制字符，按字符边界截断到 63 字节。mdns-sd 不转义 '.'，替换为 '-'
+pub fn instance_name(printer_name: &str) -> String {
+    let name: String = printer_name
+        .chars()
+        .filter(|c| !c.is_control())
+        .map(|c| if c == '.' { '-' } else { c })
+        .collect();
+    let name = name.trim();
+    let name = if name.is_empty() { "AirPrinter" } else { name };
+    truncate_utf8(name, MAX_LABEL_LEN).trim_end().to_string()
+}
+
+/// 第 n 个候选名：1 为原名，之后为 "Name (n)"，截断原名以保证总长不超过 63 字节
+pub fn with_suffix(base: &str, n: usize) -> String {
+    if n <= 1 {
+        return base.to_string();
+    }
+    let suffix = format!(" ({})", n);
+    format!("{}{}", truncate_utf8(base, MAX_LABEL_LEN - suffix.len()).trim_end(), suffix)
+}
+
+/// 按字符边界截断到不超过 `max_bytes` 字节
+pub fn truncate_utf8(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+    let mut end = max_bytes;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// 主机名标签：小写 ASCII 字母数字和 '-'
+fn host_label(printer_id: &str) -> String {
+    let label: String = format!("{}{}", HOST_PREFIX, printer_id)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    truncate_utf8(label.trim_end_matches('-'), MAX_LABEL_LEN).to_string()
+}
@@ -0,0 +1,256 @@
+// src-tauri/src/services/mdns_reflector.rs
+//
+// mDNS 反射：在源网卡 (如有线 VLAN) 上浏览 `_ipp._tcp` / `_ipps._tcp`，
+// 把符合选择规则的打印机加入 IPP 代理队列，并在目标网卡 (如 Wi-Fi) 上重新广播。
+// 客户端的任务发到本机 631 端口，再由 IppProxyBackend 转发给原打印机。
+// 浏览和广播使用同一个 MdnsBackend (avahi-daemon 运行时都经过 Avahi)。
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use mdns_sd::{ServiceEvent, ServiceInfo};
+use rust_i18n::t;
+
+use crate::models::{DiscoveredPrinter, MdnsConfig, Printer, PrinterDetails, PrinterState, PrinterStatus, ReflectorConfig};
+use crate::services::backend::IppProxyBackend;
+use crate::services::mdns_broadcaster::{IPPS_SERVICE, IPP_SERVICE};
+use crate::services::print_queue::QueueRegistry;
+use crate::services::mdns_backend::{self, ServiceBrowser};
+use crate::services::MdnsBackend;
+
+/// 反射打印机的 ID 前缀
+pub const REFLECTED_ID_PREFIX: &str = "reflect-";
+
+const BROWSE_TYPES: &[&str] = &[IPP_SERVICE, IPPS_SERVICE];
+
+pub struct MdnsReflector {
+    /// 完整服务名 -> 发现的打印机
+    discovered: Arc<Mutex<HashMap<String, DiscoveredPrinter>>>,
+    running: Arc<AtomicBool>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl MdnsReflector {
+    /// 开始在源网卡上浏览。选中的打印机加入 `queues` (由 IPP 服务器提供服务)，
+    /// 并按 `mdns` 设置在目标网卡上广播
+    pub fn start(config: ReflectorConfig, mdns: MdnsConfig, queues: QueueRegistry) -> Result<Self, String> {
+        Self::with_backend(config, mdns, queues, mdns_backend::create()?)
+    }
+
+    /// 指定浏览和广播使用的后端 (测试时注入)
+    pub fn with_backend(
+        config: ReflectorConfig,
+        mdns: MdnsConfig,
+        queues: QueueRegistry,
+        mut broadcaster: Box<dyn MdnsBackend>,
+    ) -> Result<Self, String> {
+        let mut browser = broadcaster.browse(BROWSE_TYPES, &config.source_interface)?;
+        let mut target = mdns;
+        if !config.target_interface.is_empty() {
+            target.interfaces = vec![config.target_interface.clone()];
+        }
+        broadcaster.set_config(target);
+
+        println!(
+            "{}",
+            t!("logs.reflector_started", source = display(&config.source_interface), target = display(&config.target_interface))
+        );
+
+        let running = Arc::new(AtomicBool::new(true));
+        let discovered = Arc::new(Mutex::new(HashMap::new()));
+        let mut worker = Worker {
+            config,
+            broadcaster,
+            queues,
+            discovered: discovered.clone(),
+            reflected: HashMap::new(),
+        };
+        let flag = running.clone();
+        let handle = thread::spawn(move || {
+            worker.run(browser.as_ref(), &flag);
+            browser.stop();
+            worker.withdraw_all();
+        });
+
+        Ok(Self {
+            discovered,
+            running,
+            worker: Some(handle),
+        })
+    }
+
+    /// 发现的打印机，按名称排序
+    pub fn discovered(&self) -> Vec<DiscoveredPrinter> {
+        let mut printers: Vec<DiscoveredPrinter> = self.discovered.lock().unwrap().values().cloned().collect();
+        printers.sort_by(|a, b| a.name.cmp(&b.name).then(a.service_type.cmp(&b.service_type)));
+        printers
+    }
+
+    /// 停止浏览并撤回所有反射的打印机
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+            println!("{}", t!("logs.reflector_stopped"));
+        }
+    }
+}
+
+impl Drop for MdnsReflector {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// 浏览线程的状态
+struct Worker {
+    config: ReflectorConfig,
+    broadcaster: Box<dyn MdnsBackend>,
+    queues: QueueRegistry,
+    discovered: Arc<Mutex<HashMap<String, DiscoveredPrinter>>>,
+    /// 打印机 ID -> 反射的完整服务名
+    reflected: HashMap<String, String>,
+}
+
+impl Worker {
+    fn run(&mut self, browser: &dyn ServiceBrowser, running: &AtomicBool) {
+        while running.load(Ordering::Relaxed) {
+            if let Some(event) = browser.recv_timeout(Duration::from_millis(200)) {
+                self.handle(event);
+            }
+        }
+    }
+
+    fn handle(&mut self, event: ServiceEvent) {
+        match event {
+            ServiceEvent::ServiceResolved(info) => {
+                // 不反射自己广播的服务，否则会在两个网卡之间循环
+                if self.broadcaster.is_own_host(info.get_hostname()) {
+                    return;
+                }
+                let mut printer = discovered_printer(&info);
+                printer.selected = self.config.selects(&printer.name);
+                let fullname = info.get_fullname().to_string();
+                let id = reflected_id(&printer);
+
+                if !self.discovered.lock().unwrap().contains_key(&fullname) {
+                    println!("{}", t!("logs.reflector_discovered", name = printer.name, uri = printer.uri));
+                }
+                // 同一打印机的 _ipp 和 _ipps 只反射一个
+                printer.reflected = match self.reflected.get(&id) {
+                    Some(reflected) => *reflected == fullname,
+                    None => printer.selected && self.reflect(&id, &printer, &info, &fullname),
+                };
+                self.discovered.lock().unwrap().insert(fullname, printer);
+            }
+            ServiceEvent::ServiceRemoved(_, fullname) => {
+                let Some(printer) = self.discovered.lock().unwrap().remove(&fullname) else {
+                    return;
+                };
+                let id = reflected_id(&printer);
+                if self.reflected.get(&id) == Some(&fullname) {
+                    self.unreflect(&id);
+                    println!("{}", t!("logs.reflector_removed", name = printer.name));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 建立代理队列并在目标网卡上广播
+    fn reflect(&mut self, id: &str, printer: &DiscoveredPrinter, info: &ServiceInfo, fullname: &str) -> bool {
+        let proxy = Printer {
+            name: printer.name.clone(),
+            id: id.to_string(),
+            status: PrinterStatus::Online,
+            details: PrinterDetails::default(),
+            state: PrinterState::default(),
+            capabilities: None,
+        };
+        // 沿用原打印机的 UUID，目标网段上只能看到反射后的打印机
+        let uuid = info
+            .get_property_val_str("UUID")
+            .map(|u| u.to_string())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let queue = self.queues.add(proxy, Arc::new(IppProxyBackend::new(&printer.uri)), &uuid);
+
+        match self.broadcaster.broadcast_airprint(&queue, 631) {
+            Ok(()) => {
+                self.reflected.insert(id.to_string(), fullname.to_string());
+                println!("{}", t!("logs.reflector_reflected", name = printer.name));
+                true
+            }
+            Err(e) => {
+                self.queues.remove(id);
+                eprintln!("{}", e);
+                false
+            }
+        }
+    }
+
+    fn unreflect(&mut self, id: &str) {
+        self.reflected.remove(id);
+        self.broadcaster.withdraw(id);
+        self.queues.remove(id);
+    }
+
+    fn withdraw_all(&mut self) {
+        let ids: Vec<String> = self.reflected.keys().cloned().collect();
+        for id in ids {
+            self.unreflect(&id);
+        }
+        self.broadcaster.stop();
+    }
+}
+
+fn discovered_printer(info: &ServiceInfo) -> DiscoveredPrinter {
+    let service_type = info.get_type().trim_end_matches('.').trim_end_matches(".local").to_string();
+    let name = info
+        .get_fullname()
+        .strip_suffix(info.get_type())
+        .and_then(|name| name.strip_suffix('.'))
+        .unwrap_or(info.get_fullname())
+        .to_string();
+    let host = info.get_hostname().trim_end_matches('.').to_string();
+
+    let mut addrs: Vec<IpAddr> = info.get_addresses().iter().copied().collect();
+    addrs.sort_by_key(|addr| (addr.is_ipv6(), *addr));
+
+    // 优先用 IPv4 地址，没有地址时用主机名
+    let authority = match addrs.first() {
+        Some(IpAddr::V6(v6)) => format!("[{}]", v6),
+        Some(addr) => addr.to_string(),
+        None => host.clone(),
+    };
+    let scheme = if info.get_type() == IPPS_SERVICE { "ipps" } else { "ipp" };
+    let rp = info.get_property_val_str("rp").unwrap_or("").trim_start_matches('/');
+
+    DiscoveredPrinter {
+        name,
+        service_type,
+        host,
+        addresses: addrs.iter().map(|a| a.to_string()).collect(),
+        port: info.get_port(),
+        uri: format!("{}://{}:{}/{}", scheme, authority, info.get_port(), rp),
+        model: info.get_property_val_str("ty").unwrap_or("").to_string(),
+        selected: false,
+        reflected: false,
+    }
+}
+
+/// 由主机名和实例名生成的打印机 ID，_ipp 与 _ipps 相同
+fn reflected_id(printer: &DiscoveredPrinter) -> String {
+    let id: String = format!("{}-{}", printer.host, printer.name)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    format!("{}{}", REFLECTED_ID_PREFIX, id)
+}
+
+fn display(interface: &str) -> &str {
+    if interface.is_empty() { "*" } else { interface }
+}
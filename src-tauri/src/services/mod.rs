@@ -1,12 +1,57 @@
 pub mod printer_detector;
 pub use printer_detector::PrinterDetector;
 
+pub mod printer_monitor;
+pub use printer_monitor::PrinterMonitor;
+
+pub mod cups;
+
+pub mod usb;
+
 pub mod airprint_server;
 pub use airprint_server::AirPrintServer;
 
 pub mod mdns_broadcaster;
 pub use mdns_broadcaster::MdnsBroadcaster;
 
+pub mod mdns_backend;
+pub use mdns_backend::MdnsBackend;
+
+#[cfg(target_os = "linux")]
+pub mod avahi_broadcaster;
+
+pub mod dns_sd_export;
+
+pub mod mdns_reflector;
+pub use mdns_reflector::MdnsReflector;
+
+pub mod network_monitor;
+pub use network_monitor::{InterfaceProvider, NetworkMonitor};
+
 pub mod ipp;
 pub use ipp::IppServer;
 
+pub mod socket_server;
+pub use socket_server::AppSocketServer;
+
+pub mod lpd_server;
+pub use lpd_server::LpdServer;
+
+pub mod backend;
+pub use backend::{PrinterBackend, BackendKind, BackendError};
+
+pub mod print_queue;
+pub use print_queue::QueueRegistry;
+
+pub mod settings_store;
+pub use settings_store::SettingsStore;
+
+pub mod pdf;
+
+pub mod capabilities;
+
+pub mod printer_state;
+
+pub mod ppd;
+
+pub mod printer_icons;
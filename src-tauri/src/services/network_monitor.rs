@@ -0,0 +1,162 @@
+// src-tauri/src/services/network_monitor.rs
+//
+// 网络变化检测：定时读取网卡地址，DHCP 续租、切换 Wi-Fi、休眠唤醒后
+// 地址变化时通知调用方重新广播。网卡来源可替换，便于测试。
+// 同时负责挑选要广播的网卡：默认排除 VPN / 虚拟网卡。
+
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use local_ip_address::{list_afinet_netifas, local_ip};
+use rust_i18n::t;
+use serde::Serialize;
+
+/// 默认检查间隔
+pub const NETWORK_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 默认不广播的 VPN / 容器 / 虚拟机网卡 (名称前缀，不区分大小写)
+const VIRTUAL_PREFIXES: &[&str] = &[
+    "docker", "br-", "veth", "virbr", "vboxnet", "vmnet", "lxcbr", "cni", "flannel",
+    "tun", "tap", "utun", "wg", "tailscale", "zt", "ppp", "ipsec", "awdl", "llw",
+    "vethernet", "virtualbox", "vmware",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct NetworkInterface {
+    pub name: String,
+    pub addr: IpAddr,
+}
+
+impl NetworkInterface {
+    pub fn is_virtual(&self) -> bool {
+        let name = self.name.to_ascii_lowercase();
+        VIRTUAL_PREFIXES.iter().any(|prefix| name.starts_with(prefix)) || name.contains("vpn")
+    }
+}
+
+/// 要广播的地址：`selected` 为空时用全部非虚拟网卡，否则只用选中的网卡。
+/// 不含回环地址，IPv4 在前
+pub fn advertised_addresses(interfaces: &[NetworkInterface], selected: &[String]) -> Vec<IpAddr> {
+    let mut addrs: Vec<IpAddr> = interfaces
+        .iter()
+        .filter(|i| !i.addr.is_loopback())
+        .filter(|i| if selected.is_empty() { !i.is_virtual() } else { selected.contains(&i.name) })
+        .map(|i| i.addr)
+        .collect();
+    addrs.sort_by_key(|addr| (addr.is_ipv6(), *addr));
+    addrs.dedup();
+    addrs
+}
+
+/// 网卡地址来源
+pub trait InterfaceProvider: Send + Sync {
+    fn interfaces(&self) -> Result<Vec<NetworkInterface>, String>;
+
+    /// 广播使用的地址，默认取第一个非回环 IPv4 地址
+    fn primary_address(&self) -> Result<IpAddr, String> {
+        self.interfaces()?
+            .into_iter()
+            .map(|i| i.addr)
+            .find(|addr| addr.is_ipv4() && !addr.is_loopback())
+            .ok_or_else(|| t!("errors.network_no_address").to_string())
+    }
+}
+
+/// 系统网卡
+pub struct SystemInterfaces;
+
+impl InterfaceProvider for SystemInterfaces {
+    fn interfaces(&self) -> Result<Vec<NetworkInterface>, String> {
+        let interfaces = list_afinet_netifas()
+            .map_err(|e| t!("errors.mdns_get_ip_failed", error = e.to_string()).to_string())?;
+        Ok(interfaces
+            .into_iter()
+            .map(|(name, addr)| NetworkInterface { name, addr })
+            .collect())
+    }
+
+    /// 默认路由所在网卡的地址
+    fn primary_address(&self) -> Result<IpAddr, String> {
+        local_ip().map_err(|e| t!("errors.mdns_get_ip_failed", error = e.to_string()).to_string())
+    }
+}
+
+/// 一次检测到的变化：变化前后的网卡列表，断网时为空
+#[derive(Debug, Clone)]
+pub struct NetworkChange {
+    pub previous: Vec<NetworkInterface>,
+    pub current: Vec<NetworkInterface>,
+}
+
+/// 排序后的网卡列表，便于比较
+fn snapshot(provider: &dyn InterfaceProvider) -> Vec<NetworkInterface> {
+    let mut interfaces = provider.interfaces().unwrap_or_default();
+    interfaces.sort();
+    interfaces
+}
+
+pub struct NetworkMonitor {
+    running: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl NetworkMonitor {
+    /// 在后台线程中每隔 `interval` 检查一次，网卡或地址变化时调用 `on_change`
+    pub fn start<F>(provider: Arc<dyn InterfaceProvider>, interval: Duration, on_change: F) -> Self
+    where
+        F: Fn(&NetworkChange) + Send + 'static,
+    {
+        let running = Arc::new(AtomicBool::new(true));
+        let flag = running.clone();
+        // 启动时的状态作为基准
+        let mut last = snapshot(provider.as_ref());
+
+        let handle = thread::spawn(move || {
+            while sleep_while_running(&flag, interval) {
+                let current = snapshot(provider.as_ref());
+                if current == last {
+                    continue;
+                }
+
+                let change = NetworkChange {
+                    previous: std::mem::replace(&mut last, current.clone()),
+                    current,
+                };
+                on_change(&change);
+            }
+        });
+
+        Self {
+            running,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for NetworkMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// 分段睡眠以便及时退出，返回是否仍在运行
+pub(crate) fn sleep_while_running(running: &AtomicBool, duration: Duration) -> bool {
+    let deadline = Instant::now() + duration;
+    while running.load(Ordering::Relaxed) {
+        let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+            return true;
+        };
+        thread::sleep(remaining.min(Duration::from_millis(100)));
+    }
+    false
+}
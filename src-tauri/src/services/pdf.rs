@@ -0,0 +1,253 @@
+// src-tauri/src/services/pdf.rs
+//
+// 把 AirPrint 客户端发来的文档转换成 PDF：
+// PDF 原样保存，JPEG 直接嵌入 (DCTDecode)，URF (Apple Raster) 解码后以原始位图嵌入。
+
+use std::io::Write;
+
+/// A4 尺寸 (pt)
+const A4: (f64, f64) = (595.0, 842.0);
+
+/// URF 页头中允许的最高分辨率
+const MAX_URF_DPI: u32 = 1200;
+
+/// URF 页面的最大尺寸 (英寸)，A3+ 再留一点余量
+const MAX_URF_PAGE_INCHES: (u32, u32) = (14, 20);
+
+/// 单个 URF 文档解码后的像素总量上限，防止恶意页头耗尽内存
+const MAX_URF_DECODED_BYTES: usize = 512 * 1024 * 1024;
+
+pub fn to_pdf(document_format: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    match document_format {
+        "application/pdf" => Ok(data.to_vec()),
+        "image/jpeg" => jpeg_to_pdf(data),
+        "image/urf" => urf_to_pdf(data),
+        // 部分客户端只发 application/octet-stream，按文件头判断
+        _ if data.starts_with(b"%PDF") => Ok(data.to_vec()),
+        _ if data.starts_with(&[0xFF, 0xD8]) => jpeg_to_pdf(data),
+        _ if data.starts_with(b"UNIRAST\0") => urf_to_pdf(data),
+        other => Err(format!("unsupported document format: {}", other)),
+    }
+}
+
+/// 一页内容：一张铺满页面 (或按比例居中) 的位图
+struct PdfImage<'a> {
+    width: u32,
+    height: u32,
+    /// /DeviceGray 或 /DeviceRGB
+    color_space: &'static str,
+    /// 例如 "/Filter /DCTDecode"，原始数据为空
+    filter: &'static str,
+    data: &'a [u8],
+    /// 页面尺寸 (pt)
+    page: (f64, f64),
+}
+
+/// 极简 PDF 写入器：每页一个图片 XObject
+fn write_pdf(pages: &[PdfImage]) -> Vec<u8> {
+    let mut out: Vec<u8> = Vec::new();
+    let mut offsets: Vec<usize> = Vec::new();
+    out.extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+
+    // 对象编号：1 catalog, 2 pages, 之后每页 3 个对象 (page, contents, image)
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 3 + i * 3).collect();
+
+    let mut begin = |out: &mut Vec<u8>, id: usize| {
+        if offsets.len() < id {
+            offsets.resize(id, 0);
+        }
+        offsets[id - 1] = out.len();
+        let _ = writeln!(out, "{} 0 obj", id);
+    };
+
+    begin(&mut out, 1);
+    out.extend_from_slice(b"<< /Type /Catalog /Pages 2 0 R >>\nendobj\n");
+
+    begin(&mut out, 2);
+    let kids: Vec<String> = page_ids.iter().map(|id| format!("{} 0 R", id)).collect();
+    let _ = write!(out, "<< /Type /Pages /Kids [{}] /Count {} >>\nendobj\n", kids.join(" "), pages.len());
+
+    for (page, &id) in pages.iter().zip(&page_ids) {
+        let (pw, ph) = page.page;
+        // 保持比例缩放并居中
+        let scale = (pw / page.width as f64).min(ph / page.height as f64);
+        let (w, h) = (page.width as f64 * scale, page.height as f64 * scale);
+        let (x, y) = ((pw - w) / 2.0, (ph - h) / 2.0);
+        let content = format!("q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Im0 Do Q", w, h, x, y);
+
+        begin(&mut out, id);
+        let _ = write!(
+            out,
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /XObject << /Im0 {} 0 R >> >> /Contents {} 0 R >>\nendobj\n",
+            pw, ph, id + 2, id + 1
+        );
+
+        begin(&mut out, id + 1);
+        let _ = write!(out, "<< /Length {} >>\nstream\n{}\nendstream\nendobj\n", content.len(), content);
+
+        begin(&mut out, id + 2);
+        let _ = write!(
+            out,
+            "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace {} /BitsPerComponent 8 {} /Length {} >>\nstream\n",
+            page.width, page.height, page.color_space, page.filter, page.data.len()
+        );
+        out.extend_from_slice(page.data);
+        out.extend_from_slice(b"\nendstream\nendobj\n");
+    }
+
+    let xref = out.len();
+    let _ = write!(out, "xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+    for offset in &offsets {
+        let _ = writeln!(out, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(out, "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", offsets.len() + 1, xref);
+    out
+}
+
+/// 从 SOF 段读取 JPEG 尺寸和通道数
+fn jpeg_info(data: &[u8]) -> Option<(u32, u32, u8)> {
+    let mut i = 2;
+    while i + 9 < data.len() {
+        if data[i] != 0xFF {
+            return None;
+        }
+        let marker = data[i + 1];
+        let len = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+        // SOF0..SOF15，排除 DHT(C4) / JPG(C8) / DAC(CC)
+        if (0xC0..=0xCF).contains(&marker) && ![0xC4, 0xC8, 0xCC].contains(&marker) {
+            let height = u16::from_be_bytes([data[i + 5], data[i + 6]]) as u32;
+            let width = u16::from_be_bytes([data[i + 7], data[i + 8]]) as u32;
+            return Some((width, height, data[i + 9]));
+        }
+        i += 2 + len;
+    }
+    None
+}
+
+fn jpeg_to_pdf(data: &[u8]) -> Result<Vec<u8>, String> {
+    let (width, height, components) = jpeg_info(data).ok_or("invalid JPEG")?;
+    if width == 0 || height == 0 {
+        return Err("invalid JPEG dimensions".to_string());
+    }
+    let color_space = match components {
+        1 => "/DeviceGray",
+        4 => "/DeviceCMYK",
+        _ => "/DeviceRGB",
+    };
+    Ok(write_pdf(&[PdfImage {
+        width,
+        height,
+        color_space,
+        filter: "/Filter /DCTDecode",
+        data,
+        page: A4,
+    }]))
+}
+
+struct UrfPage {
+    width: u32,
+    height: u32,
+    dpi: u32,
+    /// 每像素字节数 (1 灰度 / 3 RGB)
+    bytes_per_pixel: usize,
+    pixels: Vec<u8>,
+}
+
+fn read_u32(data: &[u8], at: usize) -> Option<u32> {
+    data.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// 解码 URF：文件头 "UNIRAST\0" + 页数，每页 32 字节页头 + 行程编码的像素
+fn decode_urf(data: &[u8]) -> Result<Vec<UrfPage>, String> {
+    if !data.starts_with(b"UNIRAST\0") {
+        return Err("invalid URF header".to_string());
+    }
+    let page_count = read_u32(data, 8).ok_or("truncated URF")?;
+    let mut pos = 12;
+    let mut pages = Vec::new();
+    let mut decoded = 0usize;
+
+    for _ in 0..page_count {
+        let header = data.get(pos..pos + 32).ok_or("truncated URF page header")?;
+        let bpp = header[0] as usize;
+        let width = read_u32(header, 12).unwrap_or(0);
+        let height = read_u32(header, 16).unwrap_or(0);
+        let dpi = read_u32(header, 20).unwrap_or(0);
+        pos += 32;
+
+        let bytes_per_pixel = match bpp {
+            8 => 1,
+            24 => 3,
+            other => return Err(format!("unsupported URF bpp: {}", other)),
+        };
+        // 页头来自网络，先校验尺寸再分配
+        if width == 0 || height == 0 || dpi == 0 || dpi > MAX_URF_DPI {
+            return Err(format!("invalid URF page: {}x{} @ {} dpi", width, height, dpi));
+        }
+        if width > dpi * MAX_URF_PAGE_INCHES.0 || height > dpi * MAX_URF_PAGE_INCHES.1 {
+            return Err(format!("URF page too large: {}x{} @ {} dpi", width, height, dpi));
+        }
+        let line_len = (width as usize).checked_mul(bytes_per_pixel).ok_or("URF page too large")?;
+        let page_len = line_len.checked_mul(height as usize).ok_or("URF page too large")?;
+        decoded += page_len;
+        if decoded > MAX_URF_DECODED_BYTES {
+            return Err("URF document too large".to_string());
+        }
+        let mut pixels = Vec::with_capacity(page_len);
+        let mut y = 0;
+
+        while y < height {
+            let repeat = *data.get(pos).ok_or("truncated URF")? as u32 + 1;
+            pos += 1;
+
+            let mut line = Vec::with_capacity(line_len);
+            while line.len() < line_len {
+                let code = *data.get(pos).ok_or("truncated URF")?;
+                pos += 1;
+                if code == 0x80 {
+                    // 剩余部分填白
+                    line.resize(line_len, 0xFF);
+                } else if code < 0x80 {
+                    let pixel = data.get(pos..pos + bytes_per_pixel).ok_or("truncated URF")?;
+                    pos += bytes_per_pixel;
+                    for _ in 0..=code {
+                        line.extend_from_slice(pixel);
+                    }
+                } else {
+                    let count = (257 - code as usize) * bytes_per_pixel;
+                    line.extend_from_slice(data.get(pos..pos + count).ok_or("truncated URF")?);
+                    pos += count;
+                }
+            }
+            line.truncate(line_len);
+
+            for _ in 0..repeat.min(height - y) {
+                pixels.extend_from_slice(&line);
+            }
+            y += repeat;
+        }
+
+        pages.push(UrfPage { width, height, dpi, bytes_per_pixel, pixels });
+    }
+    Ok(pages)
+}
+
+fn urf_to_pdf(data: &[u8]) -> Result<Vec<u8>, String> {
+    let pages = decode_urf(data)?;
+    if pages.is_empty() {
+        return Err("URF contains no pages".to_string());
+    }
+
+    let images: Vec<PdfImage> = pages
+        .iter()
+        .map(|p| PdfImage {
+            width: p.width,
+            height: p.height,
+            color_space: if p.bytes_per_pixel == 1 { "/DeviceGray" } else { "/DeviceRGB" },
+            filter: "",
+            data: &p.pixels,
+            page: (p.width as f64 * 72.0 / p.dpi as f64, p.height as f64 * 72.0 / p.dpi as f64),
+        })
+        .collect();
+    Ok(write_pdf(&images))
+}
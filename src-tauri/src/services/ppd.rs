@@ -0,0 +1,182 @@
+// src-tauri/src/services/ppd.rs
+//
+// 从 CUPS 的 PPD 文件或 `lpoptions -l` 输出推导打印能力，
+// 用于 CUPS 没有通过 IPP 报告能力的队列 (旧驱动、raw 队列)。
+
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+use std::sync::Mutex;
+
+use crate::models::{Printer, PrinterCapabilities};
+use crate::services::capabilities;
+
+/// 常见 PPD 纸张名对应的 PWG 媒体名
+const PAGE_SIZES: &[(&str, &str)] = &[
+    ("A3", "iso_a3_297x420mm"),
+    ("A4", "iso_a4_210x297mm"),
+    ("A5", "iso_a5_148x210mm"),
+    ("B5", "iso_b5_176x250mm"),
+    ("Letter", "na_letter_8.5x11in"),
+    ("Legal", "na_legal_8.5x14in"),
+    ("Tabloid", "na_ledger_11x17in"),
+];
+
+/// CUPS 没有通过 IPP 报告能力时，依次读取队列的 PPD 文件和 `lpoptions -l`
+pub fn queue_capabilities(queue: &str) -> Option<PrinterCapabilities> {
+    #[cfg(target_os = "macos")]
+    const PPD_DIR: &str = "/private/etc/cups/ppd";
+    #[cfg(not(target_os = "macos"))]
+    const PPD_DIR: &str = "/etc/cups/ppd";
+
+    if let Ok(text) = std::fs::read_to_string(format!("{}/{}.ppd", PPD_DIR, queue)) {
+        return Some(parse_ppd(&text));
+    }
+
+    let output = Command::new("lpoptions")
+        .args(["-p", queue, "-l"])
+        .env("LC_ALL", "C")
+        .env("LANG", "C")
+        .output()
+        .ok()
+        .filter(|output| output.status.success() && !output.stdout.is_empty())?;
+    Some(parse_lpoptions(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// 按队列缓存推导出的能力，定时检测时不必每次读取 PPD、运行 `lpoptions`。
+/// 队列的型号或设备地址变化 (重新配置了驱动或设备) 时重新查询
+#[derive(Default)]
+pub struct CapabilityCache {
+    /// 队列名 → (型号 + 设备地址, 能力)
+    queues: Mutex<HashMap<String, (String, Option<PrinterCapabilities>)>>,
+}
+
+impl CapabilityCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 为没有 IPP 能力的打印机填入 `lookup(队列名)` 的结果，已经不存在的队列从缓存中移除
+    pub fn fill<F>(&self, printers: &mut [Printer], lookup: F)
+    where
+        F: Fn(&str) -> Option<PrinterCapabilities>,
+    {
+        let mut queues = self.queues.lock().unwrap();
+        queues.retain(|queue, _| printers.iter().any(|p| &p.name == queue));
+        for printer in printers.iter_mut().filter(|p| p.capabilities.is_none()) {
+            let key = format!("{}\n{}", printer.details.make_and_model, printer.details.device_uri);
+            let cached = queues.get(&printer.name).filter(|(cached_key, _)| *cached_key == key);
+            printer.capabilities = match cached {
+                Some((_, caps)) => caps.clone(),
+                None => {
+                    let caps = lookup(&printer.name);
+                    queues.insert(printer.name.clone(), (key, caps.clone()));
+                    caps
+                }
+            };
+        }
+    }
+}
+
+/// 解析 PPD 文件中的 `*ColorDevice`、`*Duplex`、`*PageSize`、`*Resolution`
+pub fn parse_ppd(text: &str) -> PrinterCapabilities {
+    let mut caps = PrinterCapabilities::default();
+    let mut page_sizes = Vec::new();
+    let mut dimensions = HashMap::new();
+    let mut resolutions = Vec::new();
+
+    for line in text.lines() {
+        let Some((key, value)) = line.strip_prefix('*').and_then(|l| l.split_once(':')) else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"');
+        // `*PageSize A4/A4 210 x 297 mm` → ("PageSize", "A4")
+        let (keyword, option) = match key.split_once(' ') {
+            Some((keyword, option)) => (keyword, option.split('/').next().unwrap_or("").trim()),
+            None => (key, ""),
+        };
+
+        match keyword {
+            "ColorDevice" => caps.color = value.eq_ignore_ascii_case("true"),
+            "Duplex" if option.starts_with("Duplex") => caps.duplex = true,
+            "PageSize" if !option.is_empty() => page_sizes.push(option.to_string()),
+            "PaperDimension" if !option.is_empty() => {
+                dimensions.insert(option.to_string(), value.to_string());
+            }
+            "Resolution" | "DefaultResolution" => resolutions.extend(parse_dpi(option).or(parse_dpi(value))),
+            _ => {}
+        }
+    }
+
+    let media: Vec<String> = page_sizes
+        .iter()
+        .filter_map(|name| pwg_media_name(name, dimensions.get(name).map(String::as_str)))
+        .collect();
+    apply_media_and_resolutions(&mut caps, media, resolutions);
+    caps
+}
+
+/// 解析 `lpoptions -p <队列> -l` 的输出，每行形如 `PageSize/Media Size: *A4 A3 Letter`
+pub fn parse_lpoptions(text: &str) -> PrinterCapabilities {
+    let mut caps = PrinterCapabilities::default();
+    let mut media = Vec::new();
+    let mut resolutions = Vec::new();
+
+    for line in text.lines() {
+        let Some((key, choices)) = line.split_once(':') else {
+            continue;
+        };
+        let keyword = key.split('/').next().unwrap_or("").trim();
+        let mut choices = choices.split_whitespace().map(|c| c.trim_start_matches('*'));
+
+        match keyword {
+            "ColorModel" | "ColorMode" => {
+                // Gray、KGray、Grayscale、Black 等均为黑白
+                caps.color = choices.map(str::to_lowercase).any(|c| !c.contains("gray") && c != "black");
+            }
+            "Duplex" => caps.duplex = choices.any(|c| c.starts_with("Duplex")),
+            "PageSize" => media.extend(choices.filter_map(|c| pwg_media_name(c, None))),
+            "Resolution" => resolutions.extend(choices.filter_map(parse_dpi)),
+            _ => {}
+        }
+    }
+
+    apply_media_and_resolutions(&mut caps, media, resolutions);
+    caps
+}
+
+fn apply_media_and_resolutions(caps: &mut PrinterCapabilities, mut media: Vec<String>, mut resolutions: Vec<u32>) {
+    // A4、A4.Fullbleed、A4Rotated 对应同一 PWG 名称，保留首次出现的顺序
+    let mut seen = HashSet::new();
+    media.retain(|m| seen.insert(m.clone()));
+    if let Some(max) = capabilities::paper_max(&media) {
+        caps.max_paper = max.to_string();
+    }
+    if !media.is_empty() {
+        caps.media = media;
+    }
+    resolutions.sort_unstable();
+    resolutions.dedup();
+    if !resolutions.is_empty() {
+        caps.resolutions = resolutions;
+    }
+}
+
+/// PPD 纸张名转 PWG 媒体名；不在表中的按 `*PaperDimension` (单位为点) 生成 `om_` 自定义名
+fn pwg_media_name(name: &str, dimension: Option<&str>) -> Option<String> {
+    // A4.Fullbleed、A4Rotated 等变体与基础纸张相同
+    let base = name.split('.').next().unwrap_or(name).trim_end_matches("Rotated");
+    if let Some((_, pwg)) = PAGE_SIZES.iter().find(|(ppd, _)| ppd.eq_ignore_ascii_case(base)) {
+        return Some(pwg.to_string());
+    }
+
+    let mut points = dimension?.split_whitespace().map(|n| n.parse::<f64>());
+    let (w, h) = (points.next()?.ok()?, points.next()?.ok()?);
+    let mm = |pt: f64| (pt * 25.4 / 72.0).round() as u32;
+    Some(format!("om_{}_{}x{}mm", base.to_lowercase(), mm(w), mm(h)))
+}
+
+/// "600dpi" / "600x1200dpi" 取较小的值
+fn parse_dpi(value: &str) -> Option<u32> {
+    let dots = value.trim().strip_suffix("dpi")?;
+    dots.split('x').map(|d| d.parse::<u32>().ok()).collect::<Option<Vec<_>>>()?.into_iter().min()
+}
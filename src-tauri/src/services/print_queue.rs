@@ -0,0 +1,476 @@
+// src-tauri/src/services/print_queue.rs
+//
+// 共享打印机的队列表和任务表。IPP 服务器按请求路径找到队列，
+// 把文档交给队列绑定的 PrinterBackend。
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::Write;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use rust_i18n::t;
+
+use crate::models::{Printer, PrinterCapabilities, PrinterProfile, PrinterState, PrinterStatus};
+use crate::services::backend::{BackendError, BackendJob, JobState, PrintOptions, PrinterBackend};
+use crate::services::printer_icons::PrinterIcons;
+
+/// AppSocket / LPD 单个文档的上限，超过时拒绝任务
+pub const MAX_DOCUMENT_SIZE: usize = 128 * 1024 * 1024;
+
+/// 任务表最多保留的任务数，超出时丢弃最早的任务
+pub const MAX_JOB_HISTORY: usize = 1000;
+
+/// 已结束的任务保留多久 (秒)，供客户端查询结果
+const FINISHED_JOB_RETENTION: u64 = 60 * 60;
+
+/// Create-Job 之后等待 Send-Document 的时间 (秒)，超时的任务中止
+const HELD_JOB_TIMEOUT: u64 = 5 * 60;
+
+pub struct PrintQueue {
+    /// URL 中使用的队列名，由打印机 ID 转换而来，重名时追加 "-2"、"-3"…
+    name: String,
+    pub printer: Printer,
+    pub backend: Arc<dyn PrinterBackend>,
+    /// 共享时从后端读取，mDNS TXT 和 IPP 属性都由它生成
+    pub capabilities: PrinterCapabilities,
+    /// RFC 4122 UUID，用于 TXT `UUID` 和 `printer-uuid`
+    pub uuid: String,
+    /// 最新的状态，共享后由打印机监视更新 (`printer` 中的是共享时的快照)
+    state: RwLock<(PrinterStatus, PrinterState)>,
+    /// 对外显示的信息，共享后可修改
+    profile: RwLock<PrinterProfile>,
+    /// 随 profile 中的图标图片更新
+    icons: RwLock<Arc<PrinterIcons>>,
+}
+
+impl PrintQueue {
+    /// URL 中使用的队列名
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// `urn:uuid:` 形式的 printer-uuid
+    pub fn uuid_urn(&self) -> String {
+        format!("urn:uuid:{}", self.uuid)
+    }
+
+    /// 当前状态和状态细节，用于 printer-state 等 IPP 属性
+    pub fn state(&self) -> (PrinterStatus, PrinterState) {
+        self.state.read().unwrap().clone()
+    }
+
+    pub fn set_state(&self, status: PrinterStatus, state: PrinterState) {
+        *self.state.write().unwrap() = (status, state);
+    }
+
+    pub fn profile(&self) -> PrinterProfile {
+        self.profile.read().unwrap().clone()
+    }
+
+    /// 更新显示信息，图标图片无法读取时记录错误并使用默认图标
+    pub fn set_profile(&self, profile: PrinterProfile) {
+        let icons = match profile.icon.as_str() {
+            "" => PrinterIcons::default_icons(),
+            path => PrinterIcons::load(path.as_ref()).map(Arc::new).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                PrinterIcons::default_icons()
+            }),
+        };
+        *self.icons.write().unwrap() = icons;
+        *self.profile.write().unwrap() = profile;
+    }
+
+    pub fn icons(&self) -> Arc<PrinterIcons> {
+        self.icons.read().unwrap().clone()
+    }
+
+    /// 对外显示的名称：自定义名称优先，否则为打印机名
+    pub fn display_name(&self) -> String {
+        or_else(&self.profile.read().unwrap().display_name, &self.printer.name)
+    }
+
+    /// printer-location：自定义位置优先，否则为系统报告的位置
+    pub fn location(&self) -> String {
+        or_else(&self.profile.read().unwrap().location, &self.printer.details.location)
+    }
+
+    /// printer-info：自定义描述优先，其次为系统报告的描述，都没有时为显示名称
+    pub fn info(&self) -> String {
+        let info = or_else(&self.profile.read().unwrap().info, &self.printer.details.info);
+        if info.is_empty() { self.display_name() } else { info }
+    }
+
+    /// IPP 资源路径，同时作为 mDNS TXT 中的 `rp`
+    pub fn resource_path(&self) -> String {
+        format!("ipp/print/{}", self.name())
+    }
+}
+
+fn or_else(custom: &str, fallback: &str) -> String {
+    if custom.is_empty() { fallback } else { custom }.to_string()
+}
+
+pub fn queue_name(printer_id: &str) -> String {
+    printer_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// 提交任务时附带的信息
+#[derive(Debug, Clone)]
+pub struct JobInfo {
+    pub name: String,
+    pub user: String,
+    pub device: String,
+    pub document_format: String,
+    pub options: PrintOptions,
+}
+
+impl Default for JobInfo {
+    fn default() -> Self {
+        Self {
+            name: "Untitled".to_string(),
+            user: "anonymous".to_string(),
+            device: "unknown".to_string(),
+            document_format: "application/pdf".to_string(),
+            options: PrintOptions::default(),
+        }
+    }
+}
+
+/// 任务快照，用于 IPP 响应和前端展示
+#[derive(Serialize, Debug, Clone)]
+pub struct JobSnapshot {
+    pub id: u32,
+    pub queue: String,
+    pub name: String,
+    pub user: String,
+    pub state: JobState,
+    pub size: usize,
+    /// 创建时间 (秒级 Unix 时间戳)
+    pub created_at: u64,
+    pub backend_job_id: Option<String>,
+}
+
+/// 已 Create-Job 但还没收到文档的任务
+type HeldJobs = HashMap<u32, (Arc<PrintQueue>, JobInfo)>;
+
+#[derive(Clone)]
+pub struct QueueRegistry {
+    queues: Arc<RwLock<HashMap<String, Arc<PrintQueue>>>>,
+    jobs: Arc<Mutex<BTreeMap<u32, JobSnapshot>>>,
+    held: Arc<Mutex<HeldJobs>>,
+    next_job_id: Arc<AtomicU32>,
+}
+
+impl QueueRegistry {
+    pub fn new() -> Self {
+        Self {
+            queues: Arc::new(RwLock::new(HashMap::new())),
+            jobs: Arc::new(Mutex::new(BTreeMap::new())),
+            held: Arc::new(Mutex::new(HashMap::new())),
+            next_job_id: Arc::new(AtomicU32::new(1)),
+        }
+    }
+
+    pub fn add(&self, printer: Printer, backend: Arc<dyn PrinterBackend>, uuid: &str) -> Arc<PrintQueue> {
+        // 检测时已得到的能力优先，否则询问打印后端
+        let capabilities = printer.capabilities.clone().unwrap_or_else(|| {
+            backend.capabilities().unwrap_or_else(|e| {
+                eprintln!("{}", t!("errors.capabilities_query_failed", name = printer.name, error = e.to_string()));
+                PrinterCapabilities::default()
+            })
+        });
+        let state = RwLock::new((printer.status.clone(), printer.state.clone()));
+        let profile = RwLock::new(PrinterProfile::default());
+        let icons = RwLock::new(PrinterIcons::default_icons());
+
+        let mut queues = self.queues.write().unwrap();
+        // 同一打印机重新共享时替换原来的队列
+        queues.retain(|_, q| q.printer.id != printer.id);
+        // 不同 ID 可能转换出相同的队列名 (例如 "a.b" 和 "a_b")，不能互相覆盖
+        let base = queue_name(&printer.id);
+        let mut name = base.clone();
+        let mut n = 2;
+        while queues.contains_key(&name) {
+            name = format!("{}-{}", base, n);
+            n += 1;
+        }
+        if name != base {
+            eprintln!("{}", t!("errors.queue_name_conflict", id = printer.id, name = base, renamed = name));
+        }
+
+        let queue = Arc::new(PrintQueue { name, printer, backend, capabilities, uuid: uuid.to_string(), state, profile, icons });
+        queues.insert(queue.name(), queue.clone());
+        queue
+    }
+
+    pub fn remove(&self, printer_id: &str) -> Option<Arc<PrintQueue>> {
+        let mut queues = self.queues.write().unwrap();
+        let name = queues.values().find(|q| q.printer.id == printer_id)?.name();
+        queues.remove(&name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<PrintQueue>> {
+        self.queues.read().unwrap().get(name).cloned()
+    }
+
+    /// 按打印机 ID 查找队列
+    pub fn for_printer(&self, printer_id: &str) -> Option<Arc<PrintQueue>> {
+        self.queues.read().unwrap().values().find(|q| q.printer.id == printer_id).cloned()
+    }
+
+    pub fn queues(&self) -> Vec<Arc<PrintQueue>> {
+        self.queues.read().unwrap().values().cloned().collect()
+    }
+
+    /// 根据 HTTP 请求路径查找队列。
+    /// 旧客户端可能缓存了不带队列名的 `/ipp/print`，只有一个队列时直接使用它。
+    pub fn resolve(&self, path: &str) -> Option<Arc<PrintQueue>> {
+        let path = path.split('?').next().unwrap_or(path).trim_matches('/');
+        let queues = self.queues.read().unwrap();
+
+        if let Some(queue) = path.strip_prefix("ipp/print/").and_then(|name| queues.get(name)) {
+            return Some(queue.clone());
+        }
+        if queues.len() == 1 {
+            return queues.values().next().cloned();
+        }
+        None
+    }
+
+    /// 保存文档并在后台交给后端，立即返回本地 job-id
+    pub fn submit(&self, queue: &Arc<PrintQueue>, document: Vec<u8>, info: JobInfo) -> Result<u32, BackendError> {
+        let job_id = self.create_job(queue, info);
+        self.send_document(job_id, document, None)?;
+        Ok(job_id)
+    }
+
+    /// Create-Job：先登记任务，等待 Send-Document 送来文档
+    pub fn create_job(&self, queue: &Arc<PrintQueue>, info: JobInfo) -> u32 {
+        let job_id = self.next_job_id.fetch_add(1, Ordering::SeqCst);
+        let created_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        self.prune(created_at);
+
+        self.jobs.lock().unwrap().insert(job_id, JobSnapshot {
+            id: job_id,
+            queue: queue.name(),
+            name: info.name.clone(),
+            user: info.user.clone(),
+            state: JobState::Pending,
+            size: 0,
+            created_at,
+            backend_job_id: None,
+        });
+        self.held.lock().unwrap().insert(job_id, (queue.clone(), info));
+        job_id
+    }
+
+    /// 清理任务表：中止等待文档超时的任务，丢弃结束已久的任务，总数不超过 MAX_JOB_HISTORY
+    fn prune(&self, now: u64) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let mut held = self.held.lock().unwrap();
+
+        held.retain(|id, _| {
+            let Some(job) = jobs.get_mut(id) else { return false };
+            if now.saturating_sub(job.created_at) < HELD_JOB_TIMEOUT {
+                return true;
+            }
+            job.state = JobState::Aborted;
+            false
+        });
+        jobs.retain(|_, job| !job.state.is_finished() || now.saturating_sub(job.created_at) < FINISHED_JOB_RETENTION);
+
+        // 为即将登记的任务留出位置，按 job-id (即创建顺序) 丢弃最早的
+        while jobs.len() >= MAX_JOB_HISTORY {
+            let Some((id, _)) = jobs.pop_first() else { break };
+            held.remove(&id);
+        }
+    }
+
+    /// Send-Document：保存文档并在后台交给后端。每个任务只接受一份文档
+    pub fn send_document(&self, job_id: u32, document: Vec<u8>, document_format: Option<String>) -> Result<(), BackendError> {
+        let (queue, mut info) = self
+            .held
+            .lock()
+            .unwrap()
+            .remove(&job_id)
+            .ok_or_else(|| BackendError::JobNotFound(job_id.to_string()))?;
+        if let Some(format) = document_format {
+            info.document_format = format;
+        }
+
+        let filepath = std::env::temp_dir().join(format!(
+            "airprint_{}_{}.{}",
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis(),
+            job_id,
+            extension_for(&info.document_format)
+        ));
+
+        let write_result = (|| -> std::io::Result<()> {
+            let mut file = File::create(&filepath)?;
+            file.write_all(&document)?;
+            file.sync_all()?;
+            Ok(())
+        })();
+
+        if let Err(e) = write_result {
+            eprintln!("{}", t!("errors.ipp_write_temp_failed", error = e.to_string(), path = format!("{:?}", filepath)));
+            if let Some(stored) = self.jobs.lock().unwrap().get_mut(&job_id) {
+                stored.state = JobState::Aborted;
+            }
+            return Err(e.into());
+        }
+        println!("{}", t!("logs.ipp_temp_file_created", path = format!("{:?}", filepath)));
+
+        if let Some(stored) = self.jobs.lock().unwrap().get_mut(&job_id) {
+            stored.size = document.len();
+        }
+
+        let job = BackendJob {
+            id: job_id,
+            name: info.name,
+            user: info.user,
+            device: info.device,
+            document_format: info.document_format,
+            document: filepath,
+            options: info.options,
+        };
+        let backend = queue.backend.clone();
+        let jobs = self.jobs.clone();
+
+        thread::spawn(move || Self::run_job(backend, job, jobs));
+
+        Ok(())
+    }
+
+    fn run_job(backend: Arc<dyn PrinterBackend>, job: BackendJob, jobs: Arc<Mutex<BTreeMap<u32, JobSnapshot>>>) {
+        let file_name = job.document.file_name().unwrap_or_default().to_string_lossy().to_string();
+        println!("{}", t!("logs.ipp_printing_start", file = file_name, copies = job.options.copies, sides = job.options.sides));
+
+        // 还没交给后端就被取消的任务不再打印
+        let cancelled = jobs.lock().unwrap().get(&job.id).is_some_and(|s| s.state == JobState::Cancelled);
+        if cancelled {
+            println!("{}", t!("logs.job_cancelled_before_submit", id = job.id));
+            let _ = fs::remove_file(&job.document);
+            return;
+        }
+
+        let result = backend.submit(&job);
+
+        // 返回是否已更新；提交期间已被取消的任务保持取消状态
+        let update = |state: JobState, backend_job_id: Option<String>| {
+            match jobs.lock().unwrap().get_mut(&job.id).filter(|s| s.state != JobState::Cancelled) {
+                Some(snapshot) => {
+                    snapshot.state = state;
+                    snapshot.backend_job_id = backend_job_id;
+                    true
+                }
+                None => false,
+            }
+        };
+
+        match result {
+            Ok(backend_job_id) => {
+                println!("{}", t!("logs.backend_job_submitted", backend = backend.name(), id = backend_job_id));
+                // 提交期间被取消：当时还没有后端任务 ID，这里在后端补上取消
+                if !update(JobState::Processing, Some(backend_job_id.clone())) {
+                    if let Err(e) = backend.cancel(&backend_job_id) {
+                        eprintln!("{}", t!("errors.ipp_cancel_failed", id = backend_job_id, error = e.to_string()));
+                    }
+                }
+
+                // 部分后端 (Windows shell 打印) 异步读取文件，稍等再清理
+                thread::sleep(Duration::from_secs(3));
+                if let Err(e) = fs::remove_file(&job.document) {
+                    eprintln!("{}", t!("errors.ipp_cleanup_failed", error = e.to_string(), path = format!("{:?}", job.document)));
+                } else {
+                    println!("{}", t!("logs.ipp_temp_file_cleaned"));
+                }
+            }
+            Err(e) => {
+                eprintln!("{}", t!("errors.backend_submit_failed", backend = backend.name(), error = e.to_string()));
+                eprintln!("{}", t!("errors.ipp_print_failed_keep_file", path = format!("{:?}", job.document)));
+                update(JobState::Aborted, None);
+            }
+        }
+    }
+
+    /// 查询任务，未结束的任务会向后端刷新状态
+    pub fn job(&self, job_id: u32) -> Option<JobSnapshot> {
+        let snapshot = self.jobs.lock().unwrap().get(&job_id).cloned()?;
+        Some(self.refresh(snapshot))
+    }
+
+    pub fn jobs(&self, queue: &str) -> Vec<JobSnapshot> {
+        let snapshots: Vec<JobSnapshot> = self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|j| j.queue == queue)
+            .cloned()
+            .collect();
+        snapshots.into_iter().map(|s| self.refresh(s)).collect()
+    }
+
+    fn refresh(&self, mut snapshot: JobSnapshot) -> JobSnapshot {
+        if snapshot.state.is_finished() {
+            return snapshot;
+        }
+        let (Some(queue), Some(backend_id)) = (self.get(&snapshot.queue), snapshot.backend_job_id.as_ref()) else {
+            return snapshot;
+        };
+
+        // 后端不支持查询时保持本地状态
+        if let Ok(state) = queue.backend.status(backend_id) {
+            snapshot.state = state;
+            if let Some(stored) = self.jobs.lock().unwrap().get_mut(&snapshot.id) {
+                stored.state = state;
+            }
+        }
+        snapshot
+    }
+
+    pub fn cancel(&self, job_id: u32) -> Result<(), BackendError> {
+        let snapshot = self
+            .jobs
+            .lock()
+            .unwrap()
+            .get(&job_id)
+            .cloned()
+            .ok_or_else(|| BackendError::JobNotFound(job_id.to_string()))?;
+
+        if let (Some(queue), Some(backend_id)) = (self.get(&snapshot.queue), snapshot.backend_job_id.as_ref()) {
+            queue.backend.cancel(backend_id)?;
+        }
+        self.held.lock().unwrap().remove(&job_id);
+        if let Some(stored) = self.jobs.lock().unwrap().get_mut(&job_id) {
+            stored.state = JobState::Cancelled;
+        }
+        Ok(())
+    }
+}
+
+impl Default for QueueRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn extension_for(document_format: &str) -> &'static str {
+    match document_format {
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "image/urf" => "urf",
+        "image/pwg-raster" => "pwg",
+        "application/postscript" => "ps",
+        "application/vnd.hp-pcl" => "pcl",
+        "text/plain" => "txt",
+        _ => "pdf",
+    }
+}
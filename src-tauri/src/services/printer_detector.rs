@@ -1,32 +1,106 @@
-use crate::models::{Printer, PrinterStatus};
+use crate::models::{Printer, PrinterDetails, PrinterState, PrinterStatus};
+use crate::services::cups;
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use crate::services::ppd;
+#[cfg(target_os = "linux")]
+use crate::services::usb;
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::path::Path;
 use std::process::Command;
+use std::sync::RwLock;
 // 引入翻译宏
 use rust_i18n::t;
 
-pub struct PrinterDetector;
+/// 检测到的打印机，附带旧版本按行号生成的 ID (用于迁移设置)
+pub struct DetectedPrinter {
+    pub printer: Printer,
+    pub legacy_id: String,
+}
+
+pub struct PrinterDetector {
+    /// 远程 CUPS 服务器 (host[:port])
+    cups_hosts: RwLock<Vec<String>>,
+    /// 本机队列从 PPD / lpoptions 推导的能力
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    capabilities: ppd::CapabilityCache,
+}
 
 impl PrinterDetector {
     pub fn new() -> Self {
-        Self
+        Self {
+            cups_hosts: RwLock::new(Vec::new()),
+            #[cfg(any(target_os = "macos", target_os = "linux"))]
+            capabilities: ppd::CapabilityCache::new(),
+        }
+    }
+
+    /// 更换远程 CUPS 服务器，下次检测生效
+    pub fn set_cups_hosts(&self, hosts: Vec<String>) {
+        if let Ok(mut cups_hosts) = self.cups_hosts.write() {
+            *cups_hosts = hosts;
+        }
     }
 
+    /// 本机打印机在前，之后是各远程 CUPS 服务器上的队列
     pub fn detect(&self) -> Vec<Printer> {
-        // 翻译日志
-        println!("{}", t!("logs.detector_scanning"));
+        let mut printers = self.detect_local();
+        printers.extend(self.detect_remote());
+        printers
+    }
 
+    fn detect_local(&self) -> Vec<Printer> {
         #[cfg(target_os = "windows")]
-        return self.detect_windows();
+        return self.detect_windows().into_iter().map(|d| d.printer).collect();
 
         #[cfg(target_os = "macos")]
-        return self.detect_macos();
+        return self.detect_cups();
 
+        // Linux 上再加入插着但还没有配置为 CUPS 队列的 USB 打印机
         #[cfg(target_os = "linux")]
-        return self.detect_linux();
+        {
+            let mut printers = self.detect_cups();
+            let usb = usb::unconfigured_printers(Path::new(usb::SYSFS_ROOT), Path::new(usb::DEV_ROOT), &printers);
+            printers.extend(usb);
+            printers
+        }
+    }
+
+    /// 远程 CUPS 服务器上的队列，不可达的服务器跳过
+    fn detect_remote(&self) -> Vec<Printer> {
+        let hosts = self.cups_hosts.read().map(|hosts| hosts.clone()).unwrap_or_default();
+        hosts
+            .iter()
+            .flat_map(|host| {
+                cups::remote_printers(host).unwrap_or_else(|e| {
+                    eprintln!("{}", t!("errors.remote_cups_failed", host = host, error = e));
+                    Vec::new()
+                })
+            })
+            .collect()
+    }
+
+    /// 把设置中旧版本的打印机 ID (`mac-printer-0`、`printer-0-HP` 等) 映射为稳定 ID。
+    /// 没有旧 ID 时不扫描打印机
+    pub fn migrate_ids(&self, ids: &[String]) -> HashMap<String, String> {
+        if !ids.iter().any(|id| is_legacy_id(id)) {
+            return HashMap::new();
+        }
+        legacy_id_map(&self.detect_legacy(), ids)
+    }
+
+    /// 按旧版本的方式检测，得到旧 ID
+    fn detect_legacy(&self) -> Vec<DetectedPrinter> {
+        #[cfg(target_os = "windows")]
+        return self.detect_windows();
+
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        return self.detect_lpstat();
     }
 
     /// Windows: 使用 PowerShell 获取打印机列表
     #[cfg(target_os = "windows")]
-    fn detect_windows(&self) -> Vec<Printer> {
+    fn detect_windows(&self) -> Vec<DetectedPrinter> {
         let mut printers = Vec::new();
 
         let output = Command::new("powershell")
@@ -67,10 +141,16 @@ impl PrinterDetector {
                                 status = format!("{:?}", status)
                             ));
                             
-                            printers.push(Printer {
-                                name: name.to_string(),
-                                id: format!("printer-{}-{}", i, name.replace(" ", "-")),
-                                status,
+                            printers.push(DetectedPrinter {
+                                printer: Printer {
+                                    name: name.to_string(),
+                                    id: windows_printer_id(name, port),
+                                    status,
+                                    details: PrinterDetails::default(),
+                                    state: PrinterState::default(),
+                                    capabilities: None,
+                                },
+                                legacy_id: format!("printer-{}-{}", i, name.replace(" ", "-")),
                             });
                         }
                     }
@@ -88,24 +168,32 @@ impl PrinterDetector {
 
     /// Windows 备用方案：wmic
     #[cfg(target_os = "windows")]
-    fn detect_windows_wmic(&self) -> Vec<Printer> {
+    fn detect_windows_wmic(&self) -> Vec<DetectedPrinter> {
         let mut printers = Vec::new();
 
+        // CSV 列按字母排序：Node,Name,PortName
         let output = Command::new("wmic")
-            .args(["printer", "get", "Name", "/format:csv"])
+            .args(["printer", "get", "Name,PortName", "/format:csv"])
             .output();
 
         if let Ok(result) = output {
             let text = String::from_utf8_lossy(&result.stdout);
             for (i, line) in text.lines().skip(1).enumerate() {
                 let parts: Vec<&str> = line.split(',').collect();
-                if parts.len() >= 2 {
-                    let name = parts.last().unwrap_or(&"Unknown").trim();
+                if parts.len() >= 3 {
+                    let name = parts[1].trim();
+                    let port = parts[2].trim();
                     if !name.is_empty() && name != "Name" {
-                        printers.push(Printer {
-                            name: name.to_string(),
-                            id: format!("printer-{}", i),
-                            status: PrinterStatus::Online,
+                        printers.push(DetectedPrinter {
+                            printer: Printer {
+                                name: name.to_string(),
+                                id: windows_printer_id(name, port),
+                                status: PrinterStatus::Online,
+                                details: PrinterDetails::default(),
+                                state: PrinterState::default(),
+                                capabilities: None,
+                            },
+                            legacy_id: format!("printer-{}", i),
                         });
                     }
                 }
@@ -115,48 +203,140 @@ impl PrinterDetector {
         printers
     }
 
-    /// macOS: 使用 lpstat
-    #[cfg(target_os = "macos")]
-    fn detect_macos(&self) -> Vec<Printer> {
-        let mut printers = Vec::new();
+    /// macOS / Linux: 通过 IPP 查询本机 CUPS，CUPS 不可达时退回 lpstat
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    fn detect_cups(&self) -> Vec<Printer> {
+        let mut printers = match cups::get_printers(&cups::local_server()) {
+            Ok(printers) => printers,
+            Err(e) => {
+                eprintln!("{}", t!("errors.cups_query_failed", error = e));
+                self.detect_lpstat().into_iter().map(|d| d.printer).collect()
+            }
+        };
+        self.capabilities.fill(&mut printers, ppd::queue_capabilities);
+        printers
+    }
 
-        if let Ok(output) = Command::new("lpstat").arg("-p").output() {
-            let text = String::from_utf8_lossy(&output.stdout);
-            for (i, line) in text.lines().enumerate() {
-                if line.starts_with("printer ") {
-                    let name = line.split_whitespace().nth(1).unwrap_or("Unknown");
-                    let status = if line.contains("idle") || line.contains("ready") {
-                        PrinterStatus::Online
-                    } else {
-                        PrinterStatus::Offline
-                    };
+    /// `lpstat -p`，固定使用 C locale，输出中的 "idle" 等关键字不随系统语言变化
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    fn detect_lpstat(&self) -> Vec<DetectedPrinter> {
+        match Command::new("lpstat").arg("-p").env("LC_ALL", "C").env("LANG", "C").output() {
+            Ok(output) => parse_lpstat(&String::from_utf8_lossy(&output.stdout)),
+            Err(_) => Vec::new(),
+        }
+    }
+}
 
-                    printers.push(Printer {
-                        name: name.to_string(),
-                        id: format!("mac-printer-{}", i),
-                        status,
-                    });
-                }
-            }
+impl Default for PrinterDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 解析 `lpstat -p` 的输出。ID 由 CUPS 队列名生成，不随打印机顺序变化
+pub fn parse_lpstat(text: &str) -> Vec<DetectedPrinter> {
+    let mut printers = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.starts_with("printer ") {
+            let name = line.split_whitespace().nth(1).unwrap_or("Unknown");
+            // cupsdisable 暂停的队列显示为 "disabled since ..."
+            let status = if line.contains("disabled") {
+                PrinterStatus::Paused
+            } else if line.contains("now printing") {
+                PrinterStatus::Busy
+            } else if line.contains("idle") || line.contains("ready") {
+                PrinterStatus::Online
+            } else {
+                PrinterStatus::Offline
+            };
+
+            printers.push(DetectedPrinter {
+                printer: Printer {
+                    name: name.to_string(),
+                    id: cups_printer_id(name),
+                    status,
+                    details: PrinterDetails::default(),
+                    state: PrinterState::default(),
+                    capabilities: None,
+                },
+                legacy_id: format!("mac-printer-{}", i),
+            });
         }
+    }
+    printers
+}
 
-        printers
+/// CUPS 打印机 ID：队列名在同一台主机上唯一且跨重启不变
+pub fn cups_printer_id(queue: &str) -> String {
+    format!("cups-{}", id_slug(queue))
+}
+
+/// 远程 CUPS 队列 ID 前缀
+pub const REMOTE_PRINTER_ID_PREFIX: &str = "remote-";
+
+/// 远程 CUPS 队列 ID：服务器地址 + 队列名
+pub fn remote_printer_id(host: &str, queue: &str) -> String {
+    format!("{}{}-{}", REMOTE_PRINTER_ID_PREFIX, id_slug(host), id_slug(queue))
+}
+
+/// Windows 打印机 ID：打印机名 + 端口名
+pub fn windows_printer_id(name: &str, port: &str) -> String {
+    format!("win-{}-{}", id_slug(name), id_slug(port))
+}
+
+/// 直接检测到的 USB 打印机 ID 前缀
+pub const USB_PRINTER_ID_PREFIX: &str = "usb-";
+
+/// 未配置为 CUPS 队列的 USB 打印机 ID：型号 + 序列号，没有序列号时用设备节点名 (lp0)
+pub fn usb_printer_id(model: &str, serial: &str) -> String {
+    format!("{}{}-{}", USB_PRINTER_ID_PREFIX, id_slug(model), id_slug(serial))
+}
+
+/// 旧版本的 ID：`mac-printer-{行号}`、`printer-{序号}-{名称}`、`printer-{序号}`
+pub fn is_legacy_id(id: &str) -> bool {
+    let indexed = |rest: &str| {
+        let digits = rest.split('-').next().unwrap_or("");
+        !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+    };
+    match (id.strip_prefix("mac-printer-"), id.strip_prefix("printer-")) {
+        (Some(rest), _) => indexed(rest),
+        (_, Some(rest)) => indexed(rest),
+        _ => false,
     }
+}
 
-    /// Linux: 使用 lpstat
-    #[cfg(target_os = "linux")]
-    fn detect_linux(&self) -> Vec<Printer> {
-        self.detect_macos()
+/// 旧 ID -> 稳定 ID。先按当前顺序生成的旧 ID 匹配；
+/// `printer-{序号}-{名称}` 在顺序变化后仍可按名称匹配
+pub fn legacy_id_map(detected: &[DetectedPrinter], ids: &[String]) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    for id in ids.iter().filter(|id| is_legacy_id(id)) {
+        let by_index = detected.iter().find(|d| d.legacy_id == *id);
+        let by_name = || {
+            let name = id.strip_prefix("printer-")?.split_once('-')?.1;
+            detected.iter().find(|d| d.printer.name.replace(' ', "-") == name)
+        };
+        if let Some(found) = by_index.or_else(by_name) {
+            map.insert(id.clone(), found.printer.id.clone());
+        }
     }
+    map
+}
 
-    pub fn detect_one(&self, id: &str) -> Option<Printer> {
-        let printers = self.detect();
-        
-        // 翻译查找日志
-        println!("{}", t!("logs.detector_searching_id", id = id));
-        let ids: Vec<String> = printers.iter().map(|p| p.id.clone()).collect();
-        println!("{}", t!("logs.detector_available_printers", list = format!("{:?}", ids)));
-        
-        printers.into_iter().find(|p| p.id == id)
+/// ASCII 字母数字、'-'、'_' 原样保留，其余替换为 '_'。
+/// 含非 ASCII 字符时 (如中文打印机名) 追加原名的哈希，避免不同名称得到相同 ID
+fn id_slug(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    if name.is_ascii() {
+        slug
+    } else {
+        format!("{}-{:08x}", slug, fnv1a(name.as_bytes()))
     }
-}
\ No newline at end of file
+}
+
+/// FNV-1a (32 位)，结果不随 Rust 版本变化
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, &b| (hash ^ b as u32).wrapping_mul(0x0100_0193))
+}
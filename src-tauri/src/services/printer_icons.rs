@@ -0,0 +1,155 @@
+// src-tauri/src/services/printer_icons.rs
+//
+// 打印机图标：IPP 服务器以 HTTP 提供 48 / 128 / 512 三种尺寸的 PNG，地址通过
+// printer-icons 属性和 mDNS TXT 告诉客户端。默认图标由应用图标缩放得到，
+// 也可以为每台打印机指定一张 PNG 图片。
+
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+use png::{BitDepth, ColorType, Decoder, Encoder, Transformations};
+use rust_i18n::t;
+
+/// printer-icons 的三种尺寸，按 PWG 5100.13 的顺序 (小、中、大)
+pub const ICON_SIZES: [u32; 3] = [48, 128, 512];
+
+/// 默认图标的来源 (512x512 应用图标)
+const APP_ICON: &[u8] = include_bytes!("../../icons/icon.png");
+
+/// 一台打印机各尺寸的 PNG 图标
+#[derive(Debug, Clone)]
+pub struct PrinterIcons {
+    /// 与 ICON_SIZES 一一对应
+    pngs: Vec<Vec<u8>>,
+}
+
+impl PrinterIcons {
+    /// 由应用图标生成的默认图标，只生成一次
+    pub fn default_icons() -> Arc<PrinterIcons> {
+        static DEFAULT: OnceLock<Arc<PrinterIcons>> = OnceLock::new();
+        DEFAULT
+            .get_or_init(|| Arc::new(Self::from_png(APP_ICON).expect("bundled app icon is a valid PNG")))
+            .clone()
+    }
+
+    /// 把一张 PNG 缩放为各标准尺寸，非正方形的图片居中放在透明背景上
+    pub fn from_png(data: &[u8]) -> Result<Self, String> {
+        let (width, height, rgba) = decode_rgba(data)?;
+        let pngs = ICON_SIZES
+            .iter()
+            .map(|&size| encode_rgba(size, &fit_square(&rgba, width, height, size)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { pngs })
+    }
+
+    /// 用户指定的图片文件
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = std::fs::read(path)
+            .map_err(|e| t!("errors.printer_icon_load_failed", path = path.display().to_string(), error = e.to_string()).to_string())?;
+        Self::from_png(&data)
+            .map_err(|e| t!("errors.printer_icon_load_failed", path = path.display().to_string(), error = e).to_string())
+    }
+
+    /// 指定尺寸的 PNG，不是标准尺寸时为 None
+    pub fn get(&self, size: u32) -> Option<&[u8]> {
+        ICON_SIZES.iter().position(|&s| s == size).map(|i| self.pngs[i].as_slice())
+    }
+}
+
+/// 队列图标的 HTTP 路径 (不带开头的 '/')
+pub fn icon_path(queue: &str, size: u32) -> String {
+    format!("icons/{}/{}.png", queue, size)
+}
+
+/// 从请求路径中解析出 (队列名, 尺寸)，例如 `/icons/cups-HP/128.png`
+pub fn parse_icon_path(path: &str) -> Option<(&str, u32)> {
+    let path = path.split('?').next().unwrap_or(path).trim_start_matches('/');
+    let (queue, file) = path.strip_prefix("icons/")?.split_once('/')?;
+    let size = file.strip_suffix(".png")?.parse().ok()?;
+    (!queue.is_empty()).then_some((queue, size))
+}
+
+/// 各尺寸图标的完整地址。`base` 为打印机所在的 HTTP 地址，例如 `http://192.168.1.5:631/`
+pub fn icon_uris(base: &str, queue: &str) -> Vec<String> {
+    let base = base.trim_end_matches('/');
+    ICON_SIZES.iter().map(|&size| format!("{}/{}", base, icon_path(queue, size))).collect()
+}
+
+/// 解码为 8 位 RGBA
+fn decode_rgba(data: &[u8]) -> Result<(u32, u32, Vec<u8>), String> {
+    let mut decoder = Decoder::new(data);
+    decoder.set_transformations(Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(|e| e.to_string())?;
+    buf.truncate(info.buffer_size());
+
+    let rgba = match info.color_type {
+        ColorType::Rgba => buf,
+        ColorType::Rgb => buf.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        ColorType::GrayscaleAlpha => buf.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        ColorType::Grayscale => buf.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        // normalize_to_color8 已展开调色板
+        ColorType::Indexed => return Err(format!("{:?}", info.color_type)),
+    };
+    Ok((info.width, info.height, rgba))
+}
+
+fn encode_rgba(size: u32, rgba: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut encoder = Encoder::new(&mut out, size, size);
+    encoder.set_color(ColorType::Rgba);
+    encoder.set_depth(BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(rgba).map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+/// 等比缩放到 size x size 以内并居中。每个目标像素取其覆盖的源像素的平均值
+/// (按 alpha 加权，透明边缘不发黑)，放大时退化为最近邻
+fn fit_square(rgba: &[u8], width: u32, height: u32, size: u32) -> Vec<u8> {
+    let scale = size as f64 / width.max(height) as f64;
+    let w = ((width as f64 * scale).round() as u32).clamp(1, size);
+    let h = ((height as f64 * scale).round() as u32).clamp(1, size);
+    let (left, top) = ((size - w) / 2, (size - h) / 2);
+
+    // 目标坐标 i 覆盖的源像素范围 [start, end)
+    let span = |i: u32, dst: u32, src: u32| {
+        let start = (i as u64 * src as u64 / dst as u64) as u32;
+        let end = (((i as u64 + 1) * src as u64).div_ceil(dst as u64) as u32).max(start + 1);
+        (start, end.min(src))
+    };
+
+    let mut out = vec![0u8; (size * size * 4) as usize];
+    for y in 0..h {
+        let (y0, y1) = span(y, h, height);
+        for x in 0..w {
+            let (x0, x1) = span(x, w, width);
+            let mut sum = [0u64; 4];
+            for sy in y0..y1 {
+                for sx in x0..x1 {
+                    let p = &rgba[((sy * width + sx) * 4) as usize..][..4];
+                    let a = p[3] as u64;
+                    sum[0] += p[0] as u64 * a;
+                    sum[1] += p[1] as u64 * a;
+                    sum[2] += p[2] as u64 * a;
+                    sum[3] += a;
+                }
+            }
+            let count = ((y1 - y0) * (x1 - x0)) as u64;
+            let pixel = match sum[3] {
+                0 => [0, 0, 0, 0],
+                alpha => [
+                    (sum[0] / alpha) as u8,
+                    (sum[1] / alpha) as u8,
+                    (sum[2] / alpha) as u8,
+                    (alpha / count) as u8,
+                ],
+            };
+            let offset = (((top + y) * size + left + x) * 4) as usize;
+            out[offset..offset + 4].copy_from_slice(&pixel);
+        }
+    }
+    out
+}
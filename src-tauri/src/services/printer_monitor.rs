@@ -0,0 +1,194 @@
+// src-tauri/src/services/printer_monitor.rs
+//
+// 后台打印机监视：定时重新检测本机打印机并缓存结果，命令直接读取缓存，
+// 打印机增加、移除或状态变化时通知调用方 (前端通过 Tauri 事件实时更新)。
+// 首次检测也在后台线程中进行 (远程 CUPS 服务器可能很久才超时)，完成前缓存为空，
+// 检测到的打印机以 Added 事件通知。
+// 打印机来源可替换，便于测试。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
+
+use rust_i18n::t;
+
+use crate::models::Printer;
+use crate::services::network_monitor::sleep_while_running;
+use crate::services::PrinterDetector;
+
+/// 默认检测间隔
+pub const PRINTER_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 打印机来源
+pub trait PrinterSource: Send + Sync {
+    fn printers(&self) -> Vec<Printer>;
+}
+
+impl PrinterSource for PrinterDetector {
+    fn printers(&self) -> Vec<Printer> {
+        self.detect()
+    }
+}
+
+/// 两次检测之间的变化
+#[derive(Debug, Clone)]
+pub enum PrinterEvent {
+    Added(Printer),
+    Removed(Printer),
+    /// 状态、状态细节 (原因、耗材等) 或系统报告的信息变化，携带新的打印机信息
+    StatusChanged(Printer),
+}
+
+impl PrinterEvent {
+    /// 对应的 Tauri 事件名
+    pub fn name(&self) -> &'static str {
+        match self {
+            PrinterEvent::Added(_) => "printer-added",
+            PrinterEvent::Removed(_) => "printer-removed",
+            PrinterEvent::StatusChanged(_) => "printer-status-changed",
+        }
+    }
+
+    pub fn printer(&self) -> &Printer {
+        match self {
+            PrinterEvent::Added(p) | PrinterEvent::Removed(p) | PrinterEvent::StatusChanged(p) => p,
+        }
+    }
+}
+
+/// 按 ID 比较两次检测结果
+pub fn diff(previous: &[Printer], current: &[Printer]) -> Vec<PrinterEvent> {
+    let mut events: Vec<PrinterEvent> = previous
+        .iter()
+        .filter(|old| !current.iter().any(|p| p.id == old.id))
+        .map(|old| PrinterEvent::Removed(old.clone()))
+        .collect();
+
+    for printer in current {
+        match previous.iter().find(|old| old.id == printer.id) {
+            None => events.push(PrinterEvent::Added(printer.clone())),
+            Some(old) if old.status != printer.status || old.state != printer.state || old.details != printer.details => {
+                events.push(PrinterEvent::StatusChanged(printer.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    events
+}
+
+type EventHandler = dyn Fn(&PrinterEvent) + Send + Sync;
+
+/// 检测结果缓存，后台线程和手动刷新共用
+struct Snapshot {
+    source: Arc<dyn PrinterSource>,
+    printers: RwLock<Vec<Printer>>,
+    /// 首次检测已完成
+    ready: AtomicBool,
+    on_event: Box<EventHandler>,
+    /// 同一时间只检测一次，避免较早的结果覆盖较新的结果
+    scanning: Mutex<()>,
+}
+
+impl Snapshot {
+    /// 重新检测，更新缓存后通知变化
+    fn refresh(&self) -> Vec<Printer> {
+        let _scanning = self.scanning.lock().unwrap();
+        let current = self.source.printers();
+        let events = {
+            let mut printers = self.printers.write().unwrap();
+            let events = diff(&printers, &current);
+            *printers = current.clone();
+            events
+        };
+        self.ready.store(true, Ordering::Relaxed);
+
+        for event in &events {
+            let printer = event.printer();
+            match event {
+                PrinterEvent::Added(_) => println!("{}", t!("logs.printer_added", name = printer.name, id = printer.id)),
+                PrinterEvent::Removed(_) => println!("{}", t!("logs.printer_removed", name = printer.name, id = printer.id)),
+                PrinterEvent::StatusChanged(_) => println!(
+                    "{}",
+                    t!("logs.printer_status_changed", name = printer.name, status = printer.status.as_str())
+                ),
+            }
+            (self.on_event)(event);
+        }
+        current
+    }
+}
+
+pub struct PrinterMonitor {
+    snapshot: Arc<Snapshot>,
+    running: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl PrinterMonitor {
+    /// 在后台线程中立即检测一次，之后每隔 `interval` 重新检测。不等待首次检测完成
+    pub fn start<F>(source: Arc<dyn PrinterSource>, interval: Duration, on_event: F) -> Self
+    where
+        F: Fn(&PrinterEvent) + Send + Sync + 'static,
+    {
+        let snapshot = Arc::new(Snapshot {
+            source,
+            printers: RwLock::new(Vec::new()),
+            ready: AtomicBool::new(false),
+            on_event: Box::new(on_event),
+            scanning: Mutex::new(()),
+        });
+
+        let running = Arc::new(AtomicBool::new(true));
+        let flag = running.clone();
+        let worker = snapshot.clone();
+        let handle = thread::spawn(move || {
+            println!("{}", t!("logs.detector_scanning"));
+            worker.refresh();
+            while sleep_while_running(&flag, interval) {
+                worker.refresh();
+            }
+        });
+
+        Self {
+            snapshot,
+            running,
+            handle: Some(handle),
+        }
+    }
+
+    /// 最近一次检测到的打印机，首次检测完成前为空
+    pub fn printers(&self) -> Vec<Printer> {
+        self.snapshot.printers.read().unwrap().clone()
+    }
+
+    /// 首次检测是否已完成
+    pub fn is_ready(&self) -> bool {
+        self.snapshot.ready.load(Ordering::Relaxed)
+    }
+
+    /// 立即重新检测 (用户点击刷新)，变化同样会通知
+    pub fn refresh(&self) -> Vec<Printer> {
+        println!("{}", t!("logs.detector_scanning"));
+        self.snapshot.refresh()
+    }
+
+    /// 按 ID 查找打印机，缓存中没有时 (刚接入的打印机) 重新检测一次
+    pub fn find(&self, id: &str) -> Option<Printer> {
+        let cached = self.printers().into_iter().find(|p| p.id == id);
+        cached.or_else(|| self.refresh().into_iter().find(|p| p.id == id))
+    }
+
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for PrinterMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
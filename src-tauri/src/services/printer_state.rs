@@ -0,0 +1,113 @@
+// src-tauri/src/services/printer_state.rs
+//
+// 打印机状态与 IPP 属性互相转换：从 CUPS 读取 printer-state、printer-state-reasons、
+// marker-* 等属性，共享时再以同样的属性提供给 IPP 客户端。
+
+use ipp::attribute::IppAttribute;
+use ipp::value::IppValue;
+
+use crate::models::{Marker, PrinterState, PrinterStatus, StateReason};
+use crate::services::ipp::attributes;
+
+/// 推导状态用到的 IPP 打印机属性
+pub const IPP_ATTRIBUTE_NAMES: &[&str] = &[
+    "printer-state",
+    "printer-state-reasons",
+    "printer-is-accepting-jobs",
+    "queued-job-count",
+    "marker-names",
+    "marker-colors",
+    "marker-types",
+    "marker-levels",
+    "marker-low-levels",
+    "marker-high-levels",
+];
+
+/// printer-state (RFC 8011 5.4.11)
+const STATE_IDLE: i32 = 3;
+const STATE_PROCESSING: i32 = 4;
+const STATE_STOPPED: i32 = 5;
+
+/// 由 printer-state 和原因推导状态：stopped 时按 `paused` 区分暂停和故障停止
+pub fn status_from_ipp(state: Option<i32>, reasons: &[StateReason]) -> PrinterStatus {
+    let has = |reason: &str| reasons.iter().any(|r| r.reason == reason);
+    if has("offline") {
+        return PrinterStatus::Offline;
+    }
+    match state {
+        Some(STATE_IDLE) => PrinterStatus::Online,
+        Some(STATE_PROCESSING) => PrinterStatus::Busy,
+        Some(STATE_STOPPED) if has("paused") => PrinterStatus::Paused,
+        Some(STATE_STOPPED) => PrinterStatus::Stopped,
+        _ => PrinterStatus::Offline,
+    }
+}
+
+/// 由打印机的 IPP 属性推导状态和状态细节
+pub fn from_ipp_attributes<'a>(find: impl Fn(&str) -> Option<&'a IppValue>) -> (PrinterStatus, PrinterState) {
+    let strings = |name: &str| find(name).map(attributes::as_strings).unwrap_or_default();
+    let ints = |name: &str| -> Vec<i32> {
+        match find(name) {
+            Some(IppValue::Array(values)) => values.iter().filter_map(attributes::as_int).collect(),
+            Some(value) => attributes::as_int(value).into_iter().collect(),
+            None => Vec::new(),
+        }
+    };
+
+    let reasons: Vec<StateReason> = strings("printer-state-reasons").iter().filter_map(|r| StateReason::parse(r)).collect();
+    let status = status_from_ipp(find("printer-state").and_then(attributes::as_int), &reasons);
+
+    // marker-* 是按下标对应的平行数组，缺少的项按未知处理
+    let (colors, types) = (strings("marker-colors"), strings("marker-types"));
+    let (levels, lows, highs) = (ints("marker-levels"), ints("marker-low-levels"), ints("marker-high-levels"));
+    let markers = strings("marker-names")
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| Marker {
+            name,
+            color: colors.get(i).cloned().unwrap_or_else(|| "none".to_string()),
+            kind: types.get(i).cloned().unwrap_or_default(),
+            level: levels.get(i).copied().unwrap_or(-2),
+            low_level: lows.get(i).copied().unwrap_or(0),
+            high_level: highs.get(i).copied().unwrap_or(100),
+        })
+        .collect();
+
+    let state = PrinterState {
+        accepting_jobs: !matches!(find("printer-is-accepting-jobs"), Some(IppValue::Boolean(false))),
+        active_jobs: find("queued-job-count").and_then(attributes::as_int).unwrap_or(0).max(0) as u32,
+        reasons,
+        markers,
+    };
+    (status, state)
+}
+
+/// 状态相关的 IPP 打印机属性
+pub fn ipp_attributes(status: &PrinterStatus, state: &PrinterState) -> Vec<IppAttribute> {
+    let reasons = if state.reasons.is_empty() {
+        IppValue::Keyword("none".to_string())
+    } else {
+        IppValue::Array(state.reasons.iter().map(|r| IppValue::Keyword(r.keyword.clone())).collect())
+    };
+
+    let mut attrs = vec![
+        IppAttribute::new("printer-state", IppValue::Enum(status.ipp_state())),
+        IppAttribute::new("printer-state-reasons", reasons),
+        IppAttribute::new("printer-is-accepting-jobs", IppValue::Boolean(state.accepting_jobs)),
+    ];
+
+    if !state.markers.is_empty() {
+        let markers = &state.markers;
+        let names = |f: fn(&Marker) -> &String| IppValue::Array(markers.iter().map(|m| IppValue::NameWithoutLanguage(f(m).clone())).collect());
+        let ints = |f: fn(&Marker) -> i32| IppValue::Array(markers.iter().map(|m| IppValue::Integer(f(m))).collect());
+        attrs.extend([
+            IppAttribute::new("marker-names", names(|m| &m.name)),
+            IppAttribute::new("marker-colors", names(|m| &m.color)),
+            IppAttribute::new("marker-types", IppValue::Array(markers.iter().map(|m| IppValue::Keyword(m.kind.clone())).collect())),
+            IppAttribute::new("marker-levels", ints(|m| m.level)),
+            IppAttribute::new("marker-low-levels", ints(|m| m.low_level)),
+            IppAttribute::new("marker-high-levels", ints(|m| m.high_level)),
+        ]);
+    }
+    attrs
+}
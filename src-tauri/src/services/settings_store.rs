@@ -0,0 +1,61 @@
+// src-tauri/src/services/settings_store.rs
+use std::fs;
+use std::path::PathBuf;
+
+use rust_i18n::t;
+
+use crate::models::AppSettings;
+use crate::services::PrinterDetector;
+
+/// 把 AppSettings 读写到 JSON 文件
+pub struct SettingsStore {
+    path: PathBuf,
+    settings: AppSettings,
+}
+
+impl SettingsStore {
+    /// 读取设置文件，不存在或损坏时使用默认值
+    pub fn load(path: PathBuf) -> Self {
+        let settings = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                eprintln!("{}", t!("errors.settings_parse_failed", path = format!("{:?}", path), error = e.to_string()));
+                AppSettings::default()
+            }),
+            Err(_) => AppSettings::default(),
+        };
+
+        Self { path, settings }
+    }
+
+    pub fn get(&self) -> &AppSettings {
+        &self.settings
+    }
+
+    /// 修改设置并立即保存
+    pub fn update<F: FnOnce(&mut AppSettings)>(&mut self, f: F) -> Result<(), String> {
+        f(&mut self.settings);
+        self.save()
+    }
+
+    /// 把旧版本按行号生成的打印机 ID 换成稳定 ID (UUID 等随之迁移)
+    pub fn migrate_printer_ids(&mut self, detector: &PrinterDetector) -> Result<(), String> {
+        let renamed = detector.migrate_ids(&self.settings.printer_ids());
+        if renamed.is_empty() {
+            return Ok(());
+        }
+        for (from, to) in &renamed {
+            println!("{}", t!("logs.printer_id_migrated", from = from, to = to));
+        }
+        self.update(|s| s.rename_printer_ids(&renamed))
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let save_err = |e: String| t!("errors.settings_save_failed", path = format!("{:?}", self.path), error = e).to_string();
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| save_err(e.to_string()))?;
+        }
+        let json = serde_json::to_string_pretty(&self.settings).map_err(|e| save_err(e.to_string()))?;
+        fs::write(&self.path, json).map_err(|e| save_err(e.to_string()))
+    }
+}
@@ -0,0 +1,186 @@
+// src-tauri/src/services/socket_server.rs
+//
+// AppSocket / JetDirect (TCP 9100)：客户端直接把 PCL / PostScript / PDF 数据流写进连接，
+// 连接关闭即一个任务。识别格式后交给目标打印机的队列。
+
+use std::io::{ErrorKind, Read};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use rust_i18n::t;
+
+use crate::services::print_queue::{JobInfo, QueueRegistry, MAX_DOCUMENT_SIZE};
+
+/// 数据流中途停顿超过该时间视为连接失效
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct AppSocketServer {
+    address: String,
+    /// 接收任务的打印机 ID
+    printer_id: String,
+    queues: QueueRegistry,
+    running: Arc<AtomicBool>,
+    /// accept 线程，stop() 等它退出以释放端口
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl AppSocketServer {
+    pub fn new(bind_address: &str, port: u16, printer_id: &str, queues: QueueRegistry) -> Self {
+        Self {
+            address: format!("{}:{}", bind_address, port),
+            printer_id: printer_id.to_string(),
+            queues,
+            running: Arc::new(AtomicBool::new(false)),
+            worker: Mutex::new(None),
+        }
+    }
+
+    pub fn printer_id(&self) -> &str {
+        &self.printer_id
+    }
+
+    pub fn start(&self) -> Result<(), String> {
+        let listener = TcpListener::bind(&self.address)
+            .map_err(|e| t!("errors.socket_server_start_failed", address = self.address, error = e.to_string()).to_string())?;
+        // 非阻塞 accept，便于 stop() 后退出线程
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| t!("errors.socket_server_start_failed", address = self.address, error = e.to_string()).to_string())?;
+
+        println!("{}", t!("logs.socket_server_listening", address = self.address));
+        self.running.store(true, Ordering::Relaxed);
+
+        let running = self.running.clone();
+        let queues = self.queues.clone();
+        let printer_id = self.printer_id.clone();
+
+        let worker = thread::spawn(move || {
+            while running.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let queues = queues.clone();
+                        let printer_id = printer_id.clone();
+                        thread::spawn(move || Self::handle_connection(stream, &printer_id, &queues));
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(Duration::from_millis(200)),
+                    Err(e) => {
+                        eprintln!("{}", t!("errors.socket_accept_failed", error = e.to_string()));
+                        thread::sleep(Duration::from_millis(200));
+                    }
+                }
+            }
+        });
+        *self.worker.lock().unwrap() = Some(worker);
+        Ok(())
+    }
+
+    /// 停止监听。返回时监听端口已关闭，可以立即在同一端口重新启动
+    pub fn stop(&self) {
+        if self.running.swap(false, Ordering::Relaxed) {
+            if let Some(worker) = self.worker.lock().unwrap().take() {
+                let _ = worker.join();
+            }
+            println!("{}", t!("logs.socket_server_stopped", address = self.address));
+        }
+    }
+
+    fn handle_connection(mut stream: TcpStream, printer_id: &str, queues: &QueueRegistry) {
+        let device = stream
+            .peer_addr()
+            .map(|a| a.ip().to_string())
+            .unwrap_or_else(|_| "unknown".to_string());
+
+        let _ = stream.set_nonblocking(false);
+        let _ = stream.set_read_timeout(Some(IDLE_TIMEOUT));
+
+        // 多读一个字节以判断是否超过上限
+        let mut data = Vec::new();
+        if let Err(e) = (&mut stream).take(MAX_DOCUMENT_SIZE as u64 + 1).read_to_end(&mut data) {
+            eprintln!("{}", t!("errors.socket_read_failed", device = device, error = e.to_string()));
+            return;
+        }
+        if data.len() > MAX_DOCUMENT_SIZE {
+            eprintln!("{}", t!("errors.document_too_large", device = device, limit = MAX_DOCUMENT_SIZE));
+            return;
+        }
+        if data.is_empty() {
+            return;
+        }
+
+        let Some(queue) = queues.for_printer(printer_id) else {
+            eprintln!("{}", t!("errors.ipp_queue_not_found", path = printer_id));
+            return;
+        };
+
+        let document_format = detect_format(&data).to_string();
+        println!("{}", t!("logs.socket_job_received", device = device, size = data.len(), format = document_format));
+
+        let info = JobInfo {
+            name: "AppSocket".to_string(),
+            device,
+            document_format,
+            ..JobInfo::default()
+        };
+        if let Err(e) = queues.submit(&queue, data, info) {
+            eprintln!("{}", t!("errors.backend_submit_failed", backend = queue.backend.name(), error = e.to_string()));
+        }
+    }
+}
+
+impl Drop for AppSocketServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// PJL 通用退出符，后面通常跟 @PJL 命令
+const PJL_UEL: &[u8] = b"\x1b%-12345X";
+
+/// 根据数据流开头判断文档格式
+pub fn detect_format(data: &[u8]) -> &'static str {
+    if let Some(rest) = data.strip_prefix(PJL_UEL) {
+        return detect_pjl_language(rest);
+    }
+    if data.starts_with(b"%PDF") {
+        "application/pdf"
+    } else if data.starts_with(b"%!") || data.starts_with(b"\x04%!") {
+        "application/postscript"
+    } else if data.starts_with(b"UNIRAST\0") {
+        "image/urf"
+    } else if data.starts_with(b"RaS2") {
+        "image/pwg-raster"
+    } else if data.starts_with(&[0xFF, 0xD8]) {
+        "image/jpeg"
+    } else if data.starts_with(b"\x1bE") || data.starts_with(b"\x1b&") || data.starts_with(b"\x1b*") {
+        "application/vnd.hp-pcl"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+/// PJL 作业：看 "@PJL ENTER LANGUAGE = ..."，没有则按 PJL 头之后的内容判断
+fn detect_pjl_language(data: &[u8]) -> &'static str {
+    let mut rest = data;
+    while rest.starts_with(b"@PJL") {
+        let end = rest.iter().position(|&b| b == b'\n').map(|i| i + 1).unwrap_or(rest.len());
+        let line = String::from_utf8_lossy(&rest[..end]).to_ascii_uppercase();
+        if line.contains("ENTER LANGUAGE") {
+            let language = line.split('=').nth(1).unwrap_or("").trim();
+            return match language {
+                "PDF" => "application/pdf",
+                "POSTSCRIPT" => "application/postscript",
+                "PCL" | "PCLXL" | "PCL3GUI" => "application/vnd.hp-pcl",
+                _ => "application/octet-stream",
+            };
+        }
+        rest = &rest[end..];
+    }
+
+    match detect_format(rest) {
+        "application/octet-stream" => "application/vnd.hp-pcl",
+        format => format,
+    }
+}
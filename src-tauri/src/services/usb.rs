@@ -0,0 +1,223 @@
+// src-tauri/src/services/usb.rs
+//
+// 直接扫描 sysfs 中的 USB 打印机类设备 (usblp 驱动，/sys/class/usbmisc/lp*)，
+// 发现尚未配置为 CUPS 队列的打印机。型号等信息来自 IEEE 1284 设备 ID。
+// sysfs 和 /dev 的根目录可替换，便于测试。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::models::{Printer, PrinterCapabilities, PrinterDetails, PrinterState, PrinterStatus};
+use crate::services::printer_detector::usb_printer_id;
+
+pub const SYSFS_ROOT: &str = "/sys";
+pub const DEV_ROOT: &str = "/dev";
+
+/// IEEE 1284 命令集 (CMD) 与可直接发送的文档格式
+const COMMAND_SETS: &[(&str, &str)] = &[
+    ("PDF", "application/pdf"),
+    ("POSTSCRIPT", "application/postscript"),
+    ("PCL", "application/vnd.hp-pcl"),
+    ("URF", "image/urf"),
+    ("PWGRASTER", "image/pwg-raster"),
+];
+
+/// 解析后的 IEEE 1284 设备 ID，例如
+/// `MFG:HP;MDL:LaserJet 1020;CMD:ACL;CLS:PRINTER;DES:HP LaserJet 1020;SN:ABC123;`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeviceId {
+    pub manufacturer: String,
+    pub model: String,
+    pub command_set: Vec<String>,
+    pub description: String,
+    pub serial: String,
+}
+
+impl DeviceId {
+    pub fn parse(text: &str) -> Self {
+        let fields: HashMap<String, String> = text
+            .split(';')
+            .filter_map(|field| field.split_once(':'))
+            .map(|(key, value)| (key.trim().to_ascii_uppercase(), value.trim().to_string()))
+            .collect();
+        // 长短两种键名都允许出现
+        let get = |keys: &[&str]| keys.iter().find_map(|k| fields.get(*k)).cloned().unwrap_or_default();
+
+        Self {
+            manufacturer: get(&["MFG", "MANUFACTURER"]),
+            model: get(&["MDL", "MODEL"]),
+            command_set: get(&["CMD", "COMMAND SET"])
+                .split(',')
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect(),
+            description: get(&["DES", "DESCRIPTION"]),
+            serial: get(&["SN", "SERN", "SERIALNUMBER"]),
+        }
+    }
+
+    /// "厂商 型号"，型号中已含厂商名时不重复
+    pub fn make_and_model(&self) -> String {
+        let model = if self.model.is_empty() { &self.description } else { &self.model };
+        if self.manufacturer.is_empty() || model.to_lowercase().starts_with(&self.manufacturer.to_lowercase()) {
+            model.to_string()
+        } else {
+            format!("{} {}", self.manufacturer, model)
+        }
+    }
+
+    /// 与 CUPS usb 后端相同形式的设备地址，用于判断是否已配置为队列
+    pub fn device_uri(&self) -> String {
+        let mut uri = format!("usb://{}/{}", uri_escape(&self.manufacturer), uri_escape(&self.model));
+        if !self.serial.is_empty() {
+            uri.push_str(&format!("?serial={}", uri_escape(&self.serial)));
+        }
+        uri
+    }
+
+    /// 打印机能直接识别的文档格式，raw 打印时原样发送
+    pub fn document_formats(&self) -> Vec<String> {
+        let mut formats: Vec<String> = COMMAND_SETS
+            .iter()
+            .filter(|(cmd, _)| self.command_set.iter().any(|c| c.replace(['-', ' '], "").eq_ignore_ascii_case(cmd)))
+            .map(|(_, format)| format.to_string())
+            .collect();
+        formats.push("application/octet-stream".to_string());
+        formats
+    }
+}
+
+/// sysfs 中的一台 USB 打印机
+#[derive(Debug, Clone)]
+pub struct UsbPrinter {
+    /// 设备节点名，例如 lp0
+    pub node: String,
+    pub device_id: DeviceId,
+    /// 序列号：设备 ID 中没有时读取 USB 设备描述符
+    pub serial: String,
+}
+
+impl UsbPrinter {
+    pub fn id(&self) -> String {
+        let serial = if self.serial.is_empty() { &self.node } else { &self.serial };
+        usb_printer_id(&self.device_id.make_and_model(), serial)
+    }
+
+    /// 设备节点，例如 /dev/usb/lp0
+    pub fn device_path(&self, dev_root: &Path) -> PathBuf {
+        dev_root.join("usb").join(&self.node)
+    }
+
+    pub fn printer(&self, dev_root: &Path) -> Printer {
+        let status = if self.device_path(dev_root).exists() { PrinterStatus::Online } else { PrinterStatus::Offline };
+        let mut device_id = self.device_id.clone();
+        device_id.serial = self.serial.clone();
+
+        Printer {
+            id: self.id(),
+            name: device_id.make_and_model(),
+            status,
+            details: PrinterDetails {
+                make_and_model: device_id.make_and_model(),
+                info: device_id.description.clone(),
+                device_uri: device_id.device_uri(),
+                ..PrinterDetails::default()
+            },
+            state: PrinterState::default(),
+            capabilities: Some(PrinterCapabilities {
+                document_formats: device_id.document_formats(),
+                ..PrinterCapabilities::default()
+            }),
+        }
+    }
+}
+
+/// 列出 `{sysfs_root}/class/usbmisc/lp*`，按节点名排序
+pub fn scan(sysfs_root: &Path) -> Vec<UsbPrinter> {
+    let Ok(entries) = fs::read_dir(sysfs_root.join("class/usbmisc")) else {
+        return Vec::new();
+    };
+
+    let mut printers: Vec<UsbPrinter> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let node = entry.file_name().to_string_lossy().to_string();
+            if !node.starts_with("lp") {
+                return None;
+            }
+            // ieee1284_id 在 USB 接口目录下，序列号在其上一级的 USB 设备目录
+            let interface = entry.path().join("device");
+            let device_id = DeviceId::parse(&fs::read_to_string(interface.join("ieee1284_id")).ok()?);
+            let serial = if device_id.serial.is_empty() {
+                fs::read_to_string(interface.join("../serial")).unwrap_or_default().trim().to_string()
+            } else {
+                device_id.serial.clone()
+            };
+            Some(UsbPrinter { node, device_id, serial })
+        })
+        .collect();
+    printers.sort_by(|a, b| a.node.cmp(&b.node));
+    printers
+}
+
+/// 尚未配置为 CUPS 队列的 USB 打印机 (与已有队列的 device-uri 比较)
+pub fn unconfigured_printers(sysfs_root: &Path, dev_root: &Path, configured: &[Printer]) -> Vec<Printer> {
+    scan(sysfs_root)
+        .iter()
+        .map(|usb| usb.printer(dev_root))
+        .filter(|printer| !configured.iter().any(|p| same_usb_device(&p.details.device_uri, &printer.details.device_uri)))
+        .collect()
+}
+
+/// 按设备 ID 找到当前的设备节点 (重新插拔后节点号可能变化)
+pub fn find_device(sysfs_root: &Path, dev_root: &Path, printer_id: &str) -> Option<PathBuf> {
+    scan(sysfs_root)
+        .into_iter()
+        .find(|usb| usb.id() == printer_id)
+        .map(|usb| usb.device_path(dev_root))
+}
+
+/// 比较两个 usb:// 地址，忽略大小写、转义方式和 serial 以外的参数
+fn same_usb_device(a: &str, b: &str) -> bool {
+    let normalize = |uri: &str| -> Option<String> {
+        let rest = uri.strip_prefix("usb://")?;
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let serial = query.split('&').find(|p| p.starts_with("serial=")).unwrap_or("");
+        Some(format!("{}?{}", uri_unescape(path), uri_unescape(serial)).to_lowercase())
+    };
+    matches!((normalize(a), normalize(b)), (Some(a), Some(b)) if a == b)
+}
+
+/// 转义 URI 路径段中的保留字符和空格
+fn uri_escape(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn uri_unescape(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| s.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(b) => {
+                out.push(b);
+                i += 3;
+            }
+            None => {
+                out.push(if bytes[i] == b'+' { b' ' } else { bytes[i] });
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
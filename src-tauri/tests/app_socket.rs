@@ -0,0 +1,81 @@
+// src-tauri/tests/app_socket.rs
+//
+// AppSocket (RAW 9100)：格式识别，以及停止后立即在同一端口重新监听。
+
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use airprinter::models::{Printer, PrinterStatus};
+use airprinter::services::backend::MockBackend;
+use airprinter::services::socket_server::detect_format;
+use airprinter::services::{AppSocketServer, QueueRegistry};
+
+fn printer() -> Printer {
+    Printer {
+        name: "Office".to_string(),
+        id: "cups-Office".to_string(),
+        status: PrinterStatus::Online,
+        details: Default::default(),
+        state: Default::default(),
+        capabilities: Some(Default::default()),
+    }
+}
+
+/// 发送一个 RAW 任务并等待后端收到
+fn print(port: u16, data: &[u8], mock: &MockBackend, expected: usize) {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream.write_all(data).unwrap();
+    drop(stream);
+    for _ in 0..40 {
+        if mock.jobs().len() >= expected {
+            return;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    panic!("AppSocket job was not submitted");
+}
+
+#[test]
+fn formats_are_detected_from_stream_header() {
+    assert_eq!(detect_format(b"%PDF-1.7\n"), "application/pdf");
+    assert_eq!(detect_format(b"%!PS-Adobe-3.0\n"), "application/postscript");
+    assert_eq!(detect_format(b"\x04%!PS"), "application/postscript");
+    assert_eq!(detect_format(b"\x1bE\x1b&l0O"), "application/vnd.hp-pcl");
+    assert_eq!(detect_format(b"UNIRAST\0\0\0\0\x01"), "image/urf");
+    assert_eq!(detect_format(b"RaS2"), "image/pwg-raster");
+    assert_eq!(detect_format(&[0xFF, 0xD8, 0xFF, 0xE0]), "image/jpeg");
+    assert_eq!(detect_format(b"plain text"), "application/octet-stream");
+
+    // PJL 头：优先看 ENTER LANGUAGE，否则看 PJL 之后的数据，未知时按 PCL
+    assert_eq!(detect_format(b"\x1b%-12345X@PJL JOB\r\n@PJL ENTER LANGUAGE = POSTSCRIPT\r\n%!PS"), "application/postscript");
+    assert_eq!(detect_format(b"\x1b%-12345X@PJL ENTER LANGUAGE=PDF\n%PDF"), "application/pdf");
+    assert_eq!(detect_format(b"\x1b%-12345X@PJL JOB\r\n%PDF-1.7"), "application/pdf");
+    assert_eq!(detect_format(b"\x1b%-12345X@PJL JOB\r\n\x00\x01"), "application/vnd.hp-pcl");
+}
+
+#[test]
+fn jobs_are_queued_and_port_is_reusable_after_stop() {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let queues = QueueRegistry::new();
+    let mock = MockBackend::new();
+    queues.add(printer(), Arc::new(mock.clone()), "0b6e6c7a-1c1f-4b8e-9d5e-3f0c4d2a7b11");
+
+    let server = AppSocketServer::new("127.0.0.1", port, "cups-Office", queues.clone());
+    server.start().unwrap();
+    print(port, b"\x1b%-12345X@PJL ENTER LANGUAGE = POSTSCRIPT\r\n%!PS\nshowpage\n", &mock, 1);
+    let job = &mock.jobs()[0];
+    assert_eq!(job.job.document_format, "application/postscript");
+    assert_eq!(job.job.name, "AppSocket");
+    assert_eq!(job.job.device, "127.0.0.1");
+
+    // 修改设置时先停止再在同一端口启动
+    server.stop();
+    let restarted = AppSocketServer::new("127.0.0.1", port, "cups-Office", queues);
+    restarted.start().expect("port still in use after stop()");
+    print(port, b"%PDF-1.7\n", &mock, 2);
+    assert_eq!(mock.jobs()[1].data, b"%PDF-1.7\n");
+    restarted.stop();
+}
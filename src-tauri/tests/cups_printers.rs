@@ -0,0 +1,140 @@
+// src-tauri/tests/cups_printers.rs
+//
+// CUPS-Get-Printers：用本地的替身 IPP 服务器代替 CUPS，检查请求和解析出的打印机。
+
+use std::thread;
+
+use airprinter::models::{PrinterStatus, ReasonSeverity, StateReason};
+use airprinter::services::{cups, printer_state};
+use ipp::attribute::{IppAttribute, IppAttributeGroup};
+use ipp::model::{DelimiterTag, IppVersion, Operation, StatusCode};
+use ipp::parser::IppParser;
+use ipp::request::IppRequestResponse;
+use ipp::value::IppValue;
+use tiny_http::{Header, Response, Server};
+
+fn printer_group(attrs: Vec<(&str, IppValue)>) -> IppAttributeGroup {
+    let mut group = IppAttributeGroup::new(DelimiterTag::PrinterAttributes);
+    for (name, value) in attrs {
+        group.attributes_mut().insert(name.to_string(), IppAttribute::new(name, value));
+    }
+    group
+}
+
+fn text(s: &str) -> IppValue {
+    IppValue::TextWithoutLanguage(s.to_string())
+}
+
+/// 处理一个请求并返回两台打印机
+fn serve_once(server: Server) -> thread::JoinHandle<Vec<String>> {
+    thread::spawn(move || {
+        let mut request = server.recv().unwrap();
+        let mut body = Vec::new();
+        request.as_reader().read_to_end(&mut body).unwrap();
+        let parsed = IppParser::new(std::io::Cursor::new(body)).parse().unwrap();
+        assert_eq!(parsed.header().operation_or_status, Operation::CupsGetPrinters as u16);
+        let requested = parsed
+            .attributes()
+            .groups_of(DelimiterTag::OperationAttributes)
+            .find_map(|g| g.attributes().get("requested-attributes").cloned())
+            .map(|a| match a.value() {
+                IppValue::Array(values) => values.iter().map(|v| v.to_string()).collect(),
+                other => vec![other.to_string()],
+            })
+            .unwrap_or_default();
+
+        let mut response = IppRequestResponse::new_response(IppVersion::v1_1(), StatusCode::SuccessfulOk, parsed.header().request_id);
+        response.attributes_mut().groups_mut().push(printer_group(vec![
+            ("printer-name", IppValue::NameWithoutLanguage("HP_LaserJet".to_string())),
+            ("printer-state", IppValue::Enum(3)),
+            ("printer-state-reasons", IppValue::Keyword("none".to_string())),
+            ("printer-is-accepting-jobs", IppValue::Boolean(true)),
+            ("printer-make-and-model", text("HP LaserJet Pro M404")),
+            ("printer-location", text("二楼前台")),
+            ("printer-info", text("Reception")),
+            ("device-uri", IppValue::Uri("usb://HP/LaserJet?serial=123".to_string())),
+            ("queued-job-count", IppValue::Integer(0)),
+        ]));
+        response.attributes_mut().groups_mut().push(printer_group(vec![
+            ("printer-name", IppValue::NameWithoutLanguage("Brother_HL".to_string())),
+            ("printer-state", IppValue::Enum(5)),
+            (
+                "printer-state-reasons",
+                IppValue::Array(vec![
+                    IppValue::Keyword("paused".to_string()),
+                    IppValue::Keyword("toner-low-warning".to_string()),
+                ]),
+            ),
+            ("printer-is-accepting-jobs", IppValue::Boolean(false)),
+            ("queued-job-count", IppValue::Integer(2)),
+            ("marker-names", IppValue::Array(vec![IppValue::NameWithoutLanguage("Black Toner".to_string())])),
+            ("marker-colors", IppValue::Array(vec![IppValue::NameWithoutLanguage("#000000".to_string())])),
+            ("marker-types", IppValue::Array(vec![IppValue::Keyword("toner".to_string())])),
+            ("marker-levels", IppValue::Array(vec![IppValue::Integer(8)])),
+            ("marker-low-levels", IppValue::Array(vec![IppValue::Integer(10)])),
+        ]));
+
+        let header = Header::from_bytes("Content-Type", "application/ipp").unwrap();
+        request.respond(Response::from_data(response.to_bytes().to_vec()).with_header(header)).unwrap();
+        requested
+    })
+}
+
+#[test]
+fn cups_get_printers_is_parsed_without_lpstat() {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let address = server.server_addr().to_ip().unwrap();
+    let handle = serve_once(server);
+
+    let printers = cups::get_printers(&address.to_string()).expect("CUPS-Get-Printers failed");
+    let requested = handle.join().unwrap();
+    assert!(requested.contains(&"printer-state-reasons".to_string()));
+    assert!(requested.contains(&"device-uri".to_string()));
+    assert!(requested.contains(&"marker-levels".to_string()));
+
+    assert_eq!(printers.len(), 2);
+    let hp = &printers[0];
+    assert_eq!((hp.name.as_str(), hp.id.as_str()), ("HP_LaserJet", "cups-HP_LaserJet"));
+    assert!(matches!(hp.status, PrinterStatus::Online));
+    assert_eq!(hp.details.make_and_model, "HP LaserJet Pro M404");
+    assert_eq!(hp.details.location, "二楼前台");
+    assert_eq!(hp.details.info, "Reception");
+    assert_eq!(hp.details.device_uri, "usb://HP/LaserJet?serial=123");
+    assert!(hp.state.reasons.is_empty() && hp.state.accepting_jobs);
+
+    // stopped + paused 是暂停，不是故障
+    let brother = &printers[1];
+    assert!(matches!(brother.status, PrinterStatus::Paused));
+    assert!(!brother.state.accepting_jobs);
+    assert_eq!(brother.state.active_jobs, 2);
+    let reasons: Vec<(&str, ReasonSeverity)> = brother.state.reasons.iter().map(|r| (r.reason.as_str(), r.severity)).collect();
+    assert_eq!(reasons, vec![("paused", ReasonSeverity::Report), ("toner-low", ReasonSeverity::Warning)]);
+    let toner = &brother.state.markers[0];
+    assert_eq!((toner.name.as_str(), toner.color.as_str(), toner.kind.as_str(), toner.level), ("Black Toner", "#000000", "toner", 8));
+    assert!(toner.is_low());
+
+    // 共享时以同样的属性提供给 IPP 客户端
+    let attrs = printer_state::ipp_attributes(&brother.status, &brother.state);
+    let find = |name: &str| attrs.iter().find(|a| a.name() == name).map(|a| a.value().clone());
+    assert_eq!(find("printer-state"), Some(IppValue::Enum(5)));
+    assert_eq!(find("marker-levels"), Some(IppValue::Array(vec![IppValue::Integer(8)])));
+    assert_eq!(
+        find("printer-state-reasons"),
+        Some(IppValue::Array(vec![IppValue::Keyword("paused".to_string()), IppValue::Keyword("toner-low-warning".to_string())]))
+    );
+}
+
+#[test]
+fn stopped_without_paused_is_a_fault() {
+    let reasons: Vec<StateReason> = ["media-empty-error", "offline-report"].iter().filter_map(|r| StateReason::parse(r)).collect();
+    assert_eq!(printer_state::status_from_ipp(Some(5), &reasons[..1]), PrinterStatus::Stopped);
+    assert_eq!(printer_state::status_from_ipp(Some(3), &reasons), PrinterStatus::Offline);
+    assert_eq!(printer_state::status_from_ipp(Some(4), &[]), PrinterStatus::Busy);
+    assert!(StateReason::parse("none").is_none());
+}
+
+#[test]
+fn unreachable_cups_is_an_error() {
+    // 端口 9 (discard) 上没有 CUPS
+    assert!(cups::get_printers("127.0.0.1:9").is_err());
+}
@@ -0,0 +1,80 @@
+// src-tauri/tests/dns_sd_export.rs
+//
+// 单播 DNS-SD 导出：区域文件内容和 DNS UPDATE 报文结构。
+
+use std::collections::HashMap;
+
+use airprinter::services::dns_sd_export::{dns_update, records, zone_file, RecordData};
+use mdns_sd::ServiceInfo;
+
+fn service() -> ServiceInfo {
+    let mut txt = HashMap::new();
+    txt.insert("rp".to_string(), "ipp/print/hp".to_string());
+    txt.insert("txtvers".to_string(), "1".to_string());
+    ServiceInfo::new(
+        "_universal._sub._ipp._tcp.local.",
+        "HP LaserJet @ Reception",
+        "airprinter-hp.local.",
+        "192.168.1.10,fe80::1,fd00::10",
+        631,
+        txt,
+    )
+    .unwrap()
+}
+
+#[test]
+fn zone_file_contains_dns_sd_records() {
+    let records = records(&[service()], "printers.example.com.").unwrap();
+    let zone = zone_file(&records);
+
+    for line in [
+        "b._dns-sd._udp.printers.example.com. 3600 IN PTR printers.example.com.",
+        "_ipp._tcp.printers.example.com. 3600 IN PTR HP\\ LaserJet\\ \\@\\ Reception._ipp._tcp.printers.example.com.",
+        "_universal._sub._ipp._tcp.printers.example.com. 3600 IN PTR HP\\ LaserJet\\ \\@\\ Reception._ipp._tcp.printers.example.com.",
+        "HP\\ LaserJet\\ \\@\\ Reception._ipp._tcp.printers.example.com. 3600 IN SRV 0 0 631 airprinter-hp.printers.example.com.",
+        "HP\\ LaserJet\\ \\@\\ Reception._ipp._tcp.printers.example.com. 3600 IN TXT \"txtvers=1\" \"rp=ipp/print/hp\"",
+        "airprinter-hp.printers.example.com. 3600 IN A 192.168.1.10",
+        "airprinter-hp.printers.example.com. 3600 IN AAAA fd00::10",
+    ] {
+        assert!(zone.lines().any(|l| l == line), "missing: {}\n{}", line, zone);
+    }
+    // 链路本地地址不导出
+    assert!(!zone.contains("fe80"));
+}
+
+#[test]
+fn dns_update_message_layout() {
+    let records = records(&[service()], "printers.example.com").unwrap();
+    let message = dns_update(&records, "example.com").unwrap();
+
+    // opcode = UPDATE，一个 zone，更新段 = 删除 (实例名 + 主机名) + 全部记录
+    assert_eq!((u16::from_be_bytes([message[2], message[3]]) >> 11) & 0xf, 5);
+    assert_eq!(u16::from_be_bytes([message[4], message[5]]), 1);
+    assert_eq!(u16::from_be_bytes([message[8], message[9]]) as usize, 2 + records.len());
+    assert_eq!(&message[12..25], b"\x07example\x03com\x00");
+
+    assert!(records.iter().any(|r| matches!(r.data, RecordData::Srv { port: 631, .. })));
+    assert!(dns_update(&records, "bad domain").is_err());
+}
+
+#[test]
+fn every_subtype_is_exported_once() {
+    // 同一实例的两个子类型各是一个 ServiceInfo
+    let print = ServiceInfo::new(
+        "_print._sub._ipp._tcp.local.",
+        "HP LaserJet @ Reception",
+        "airprinter-hp.local.",
+        "192.168.1.10",
+        631,
+        service().get_properties().iter().map(|p| (p.key().to_string(), p.val_str().to_string())).collect::<HashMap<_, _>>(),
+    )
+    .unwrap();
+    let zone = zone_file(&records(&[service(), print], "printers.example.com.").unwrap());
+
+    let count = |prefix: &str| zone.lines().filter(|l| l.starts_with(prefix)).count();
+    assert_eq!(count("_universal._sub._ipp._tcp.printers.example.com. "), 1);
+    assert_eq!(count("_print._sub._ipp._tcp.printers.example.com. "), 1);
+    assert_eq!(count("_ipp._tcp.printers.example.com. "), 1);
+    assert_eq!(zone.lines().filter(|l| l.contains(" IN SRV ")).count(), 1);
+    assert_eq!(zone.lines().filter(|l| l.contains(" IN A ")).count(), 1);
+}
@@ -0,0 +1,125 @@
+// src-tauri/tests/folder_printer.rs
+//
+// "保存到文件夹" 虚拟打印机：文件名模板展开，以及 URF / JPEG 到 PDF 的转换。
+
+use std::path::PathBuf;
+
+use airprinter::models::VirtualPrinterConfig;
+use airprinter::services::backend::{BackendJob, FolderBackend, PrintOptions, PrinterBackend};
+use airprinter::services::pdf;
+
+fn job(name: &str, user: &str) -> BackendJob {
+    BackendJob {
+        id: 7,
+        name: name.to_string(),
+        user: user.to_string(),
+        device: "192.168.1.20".to_string(),
+        document_format: "image/urf".to_string(),
+        document: PathBuf::new(),
+        options: PrintOptions::default(),
+    }
+}
+
+/// URF 文件：UNIRAST 头 + 每页 32 字节页头 + 行数据
+fn urf(pages: &[(u8, u32, u32, u32, &[u8])]) -> Vec<u8> {
+    let mut data = b"UNIRAST\0".to_vec();
+    data.extend((pages.len() as u32).to_be_bytes());
+    for &(bpp, width, height, dpi, lines) in pages {
+        let mut header = vec![bpp, 1, 0, 0];
+        header.extend([0u8; 8]);
+        header.extend(width.to_be_bytes());
+        header.extend(height.to_be_bytes());
+        header.extend(dpi.to_be_bytes());
+        header.extend([0u8; 8]);
+        data.extend(header);
+        data.extend_from_slice(lines);
+    }
+    data
+}
+
+#[test]
+fn file_name_template_is_expanded_and_sanitized() {
+    let name = FolderBackend::render_file_name("{job}-{user}-{device}-{id}", &job("Report", "alice"));
+    assert_eq!(name, "Report-alice-192.168.1.20-7");
+
+    // {timestamp} 为 YYYYMMDD-HHMMSS
+    let name = FolderBackend::render_file_name("{timestamp}", &job("Report", "alice"));
+    assert_eq!(name.len(), 15);
+    assert!(name.chars().enumerate().all(|(i, c)| if i == 8 { c == '-' } else { c.is_ascii_digit() }));
+
+    // 路径分隔符和非法字符被替换，不能跳出输出目录
+    let name = FolderBackend::render_file_name("{job}", &job("../../etc/passwd", "alice"));
+    assert_eq!(name, "_.._etc_passwd");
+    assert!(!name.contains('/'));
+    let name = FolderBackend::render_file_name("{user}:{job}", &job("a\\b?c", "bob"));
+    assert_eq!(name, "bob_a_b_c");
+
+    // 展开为空时回退到 job-<id>
+    assert_eq!(FolderBackend::render_file_name("{job}", &job(" .. ", "alice")), "job-7");
+}
+
+#[test]
+fn urf_and_jpeg_are_converted_to_pdf() {
+    // 4x2 RGB @ 72 dpi：一行重复两次，两个红色像素 + 两个字面像素
+    let line: &[u8] = &[1, 1, 255, 0, 0, 255, 0, 255, 0, 0, 0, 255];
+    let out = pdf::to_pdf("image/urf", &urf(&[(24, 4, 2, 72, line)])).unwrap();
+    let text = String::from_utf8_lossy(&out);
+    assert!(out.starts_with(b"%PDF-1.4"));
+    assert!(text.contains("/MediaBox [0 0 4.00 2.00]"));
+    assert!(text.contains("/Width 4 /Height 2 /ColorSpace /DeviceRGB"));
+    assert!(text.contains("/Length 24"));
+    assert!(text.ends_with("%%EOF\n"));
+
+    // 按文件头识别格式
+    let gray: &[u8] = &[0, 0x80];
+    let out = pdf::to_pdf("application/octet-stream", &urf(&[(8, 3, 1, 300, gray), (8, 3, 1, 300, gray)])).unwrap();
+    assert!(String::from_utf8_lossy(&out).contains("/Count 2"));
+
+    // 只有 SOF0 段的最小 JPEG，嵌入后保留原始数据
+    let jpeg = [
+        0xFF, 0xD8, 0xFF, 0xE0, 0, 16, b'J', b'F', b'I', b'F', 0, 1, 1, 0, 0, 1, 0, 1, 0, 0, 0xFF, 0xC0, 0, 17, 8, 0, 10,
+        0, 20, 3, 1, 0x22, 0, 2, 0x11, 1, 3, 0x11, 1, 0xFF, 0xD9,
+    ];
+    let out = pdf::to_pdf("image/jpeg", &jpeg).unwrap();
+    let text = String::from_utf8_lossy(&out);
+    assert!(text.contains("/Width 20 /Height 10 /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode"));
+    assert!(out.windows(jpeg.len()).any(|w| w == jpeg));
+
+    assert!(pdf::to_pdf("text/plain", b"hello").is_err());
+}
+
+#[test]
+fn malformed_urf_headers_are_rejected() {
+    let line: &[u8] = &[0, 0x80];
+    // 宽或高为 0、分辨率为 0 或过高
+    assert!(pdf::to_pdf("image/urf", &urf(&[(8, 0, 1, 300, line)])).is_err());
+    assert!(pdf::to_pdf("image/urf", &urf(&[(8, 1, 0, 300, line)])).is_err());
+    assert!(pdf::to_pdf("image/urf", &urf(&[(8, 1, 1, 0, line)])).is_err());
+    assert!(pdf::to_pdf("image/urf", &urf(&[(8, 1, 1, 100_000, line)])).is_err());
+    // 超出最大纸张尺寸，不会尝试分配
+    assert!(pdf::to_pdf("image/urf", &urf(&[(24, u32::MAX, u32::MAX, 1200, line)])).is_err());
+    assert!(pdf::to_pdf("image/urf", &urf(&[(24, 300 * 15, 10, 300, line)])).is_err());
+    // 数据截断
+    assert!(pdf::to_pdf("image/urf", &urf(&[(8, 2, 2, 300, &[0])])).is_err());
+    assert!(pdf::to_pdf("image/jpeg", &[0xFF, 0xD8, 0xFF, 0xC0, 0, 17, 8, 0, 0, 0, 0, 3, 0, 0]).is_err());
+}
+
+#[test]
+fn folder_backend_writes_unique_pdfs() {
+    let dir = tempfile::tempdir().unwrap();
+    let backend = FolderBackend::new(VirtualPrinterConfig {
+        output_dir: dir.path().to_string_lossy().to_string(),
+        file_name_template: "{job}".to_string(),
+        ..Default::default()
+    });
+
+    let document = dir.path().join("upload.urf");
+    std::fs::write(&document, urf(&[(8, 1, 1, 300, &[0, 0x80])])).unwrap();
+    let job = BackendJob { document, ..job("scan", "alice") };
+
+    let first = backend.submit(&job).unwrap();
+    let second = backend.submit(&job).unwrap();
+    assert_eq!(PathBuf::from(&first), dir.path().join("scan.pdf"));
+    assert_eq!(PathBuf::from(&second), dir.path().join("scan (2).pdf"));
+    assert!(std::fs::read(&first).unwrap().starts_with(b"%PDF"));
+}
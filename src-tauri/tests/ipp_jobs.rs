@@ -0,0 +1,205 @@
+// src-tauri/tests/ipp_jobs.rs
+//
+// IPP 任务流程：Print-Job / Get-Job-Attributes / Cancel-Job 经 IppServer 到 MockBackend，
+// 以及队列之间的隔离、队列名冲突、任务表清理和提交期间取消的任务。
+
+use std::io::Cursor;
+use std::net::TcpListener;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use airprinter::models::{Printer, PrinterStatus};
+use airprinter::models::PrinterCapabilities;
+use airprinter::services::backend::{BackendError, BackendJob, JobState, MockBackend, PrinterBackend};
+use airprinter::services::ipp::attributes;
+use airprinter::services::print_queue::{JobInfo, MAX_JOB_HISTORY};
+use airprinter::services::{IppServer, QueueRegistry};
+use ipp::prelude::*;
+
+fn printer(id: &str) -> Printer {
+    Printer {
+        name: id.to_string(),
+        id: id.to_string(),
+        status: PrinterStatus::Online,
+        details: Default::default(),
+        state: Default::default(),
+        capabilities: Some(Default::default()),
+    }
+}
+
+fn job_attr(response: &IppRequestResponse, name: &str) -> Option<String> {
+    attributes::find(response, DelimiterTag::JobAttributes, name).map(|v| v.to_string())
+}
+
+fn wait_for_jobs(mock: &MockBackend, count: usize) {
+    for _ in 0..40 {
+        if mock.jobs().len() >= count {
+            return;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    panic!("job was not submitted to the backend");
+}
+
+#[test]
+fn print_get_and_cancel_job_through_mock_backend() {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let queues = QueueRegistry::new();
+    let (office, lab) = (MockBackend::new(), MockBackend::new());
+    let office_queue = queues.add(printer("cups-Office"), Arc::new(office.clone()), "0b6e6c7a-1c1f-4b8e-9d5e-3f0c4d2a7b11");
+    let lab_queue = queues.add(printer("cups-Lab"), Arc::new(lab.clone()), "9a0e1c57-54d2-4f0e-8d9e-1b2c3d4e5f60");
+    IppServer::new("127.0.0.1", port, queues.clone()).start();
+
+    let office_uri: Uri = format!("ipp://127.0.0.1:{}/{}", port, office_queue.resource_path()).parse().unwrap();
+    let lab_uri: Uri = format!("ipp://127.0.0.1:{}/{}", port, lab_queue.resource_path()).parse().unwrap();
+    let client = IppClient::new(office_uri.clone());
+
+    (0..20)
+        .find_map(|_| {
+            client.send(IppOperationBuilder::get_printer_attributes(office_uri.clone()).build()).ok().or_else(|| {
+                thread::sleep(Duration::from_millis(50));
+                None
+            })
+        })
+        .expect("IPP server did not answer");
+
+    let print_job = IppOperationBuilder::print_job(office_uri.clone(), IppPayload::new(Cursor::new(b"%PDF-1.7 report".to_vec())))
+        .job_title("report")
+        .user_name("alice")
+        .attribute(IppAttribute::new("copies", IppValue::Integer(2)))
+        .attribute(IppAttribute::new("sides", IppValue::Keyword("two-sided-long-edge".to_string())))
+        .build();
+    let response = client.send(print_job).unwrap();
+    assert!(response.header().status_code().is_success());
+    let job_id: i32 = job_attr(&response, "job-id").unwrap().parse().unwrap();
+
+    // 后端收到的任务
+    wait_for_jobs(&office, 1);
+    let recorded = &office.jobs()[0];
+    assert_eq!(recorded.data, b"%PDF-1.7 report");
+    assert_eq!(recorded.job.id, job_id as u32);
+    assert_eq!(recorded.job.name, "report");
+    assert_eq!(recorded.job.user, "alice");
+    assert_eq!(recorded.job.device, "127.0.0.1");
+    assert_eq!(recorded.job.document_format, "application/pdf");
+    assert_eq!((recorded.job.options.copies, recorded.job.options.sides.as_str()), (2, "two-sided-long-edge"));
+    assert!(lab.jobs().is_empty());
+
+    office.set_state(&format!("mock-{}", job_id), JobState::Processing);
+    let response = client.send(IppOperationBuilder::get_job_attributes(office_uri.clone(), job_id).build()).unwrap();
+    assert!(response.header().status_code().is_success());
+    assert_eq!(job_attr(&response, "job-state").as_deref(), Some("5"));
+    assert_eq!(job_attr(&response, "job-name").as_deref(), Some("report"));
+    assert_eq!(job_attr(&response, "job-originating-user-name").as_deref(), Some("alice"));
+
+    // 其它队列的 printer-uri 看不到也取消不了这个任务
+    let lab_client = IppClient::new(lab_uri.clone());
+    let response = lab_client.send(IppOperationBuilder::get_job_attributes(lab_uri.clone(), job_id).build()).unwrap();
+    assert_eq!(response.header().status_code(), StatusCode::ClientErrorNotFound);
+    let response = lab_client.send(IppOperationBuilder::cancel_job(lab_uri, job_id).build()).unwrap();
+    assert_eq!(response.header().status_code(), StatusCode::ClientErrorNotFound);
+    assert_eq!(office.jobs()[0].state, JobState::Processing);
+
+    let response = client.send(IppOperationBuilder::cancel_job(office_uri.clone(), job_id).build()).unwrap();
+    assert!(response.header().status_code().is_success());
+    assert_eq!(office.jobs()[0].state, JobState::Cancelled);
+    let response = client.send(IppOperationBuilder::get_job_attributes(office_uri.clone(), job_id).build()).unwrap();
+    assert_eq!(job_attr(&response, "job-state").as_deref(), Some("7"));
+
+    // 不存在的任务
+    let response = client.send(IppOperationBuilder::get_job_attributes(office_uri, 9999).build()).unwrap();
+    assert_eq!(response.header().status_code(), StatusCode::ClientErrorNotFound);
+}
+
+#[test]
+fn colliding_queue_names_are_made_unique() {
+    let queues = QueueRegistry::new();
+    let first = queues.add(printer("cups-HP.Pro"), Arc::new(MockBackend::new()), "0b6e6c7a-1c1f-4b8e-9d5e-3f0c4d2a7b11");
+    let second = queues.add(printer("cups-HP_Pro"), Arc::new(MockBackend::new()), "9a0e1c57-54d2-4f0e-8d9e-1b2c3d4e5f60");
+    assert_eq!(first.name(), "cups-HP_Pro");
+    assert_eq!(second.name(), "cups-HP_Pro-2");
+    assert_eq!(queues.queues().len(), 2);
+    assert_eq!(queues.for_printer("cups-HP.Pro").unwrap().name(), "cups-HP_Pro");
+    assert_eq!(queues.get("cups-HP_Pro-2").unwrap().printer.id, "cups-HP_Pro");
+
+    // 重新共享同一打印机时替换原来的队列
+    queues.add(printer("cups-HP_Pro"), Arc::new(MockBackend::new()), "9a0e1c57-54d2-4f0e-8d9e-1b2c3d4e5f60");
+    assert_eq!(queues.queues().len(), 2);
+
+    assert_eq!(queues.remove("cups-HP.Pro").unwrap().name(), "cups-HP_Pro");
+    assert!(queues.remove("cups-HP.Pro").is_none());
+    assert_eq!(queues.queues().len(), 1);
+}
+
+#[test]
+fn job_table_is_bounded() {
+    let queues = QueueRegistry::new();
+    let queue = queues.add(printer("cups-Office"), Arc::new(MockBackend::new()), "0b6e6c7a-1c1f-4b8e-9d5e-3f0c4d2a7b11");
+
+    // 只 Create-Job 不发送文档
+    let first = queues.create_job(&queue, JobInfo::default());
+    for _ in 0..MAX_JOB_HISTORY + 10 {
+        queues.create_job(&queue, JobInfo::default());
+    }
+    assert_eq!(queues.jobs(&queue.name()).len(), MAX_JOB_HISTORY);
+    assert!(queues.job(first).is_none());
+    // 被丢弃的任务也不再等待文档
+    assert!(queues.send_document(first, b"%PDF".to_vec(), None).is_err());
+}
+
+/// 提交时先通知测试，等测试放行后才交给 MockBackend
+struct GatedBackend {
+    inner: MockBackend,
+    entered: Mutex<mpsc::Sender<()>>,
+    release: Mutex<mpsc::Receiver<()>>,
+}
+
+impl PrinterBackend for GatedBackend {
+    fn name(&self) -> &'static str {
+        "gated"
+    }
+
+    fn submit(&self, job: &BackendJob) -> Result<String, BackendError> {
+        self.entered.lock().unwrap().send(()).unwrap();
+        self.release.lock().unwrap().recv().unwrap();
+        self.inner.submit(job)
+    }
+
+    fn status(&self, job_id: &str) -> Result<JobState, BackendError> {
+        self.inner.status(job_id)
+    }
+
+    fn cancel(&self, job_id: &str) -> Result<(), BackendError> {
+        self.inner.cancel(job_id)
+    }
+
+    fn capabilities(&self) -> Result<PrinterCapabilities, BackendError> {
+        self.inner.capabilities()
+    }
+}
+
+#[test]
+fn job_cancelled_during_submit_is_cancelled_on_the_backend() {
+    let mock = MockBackend::new();
+    let (entered_tx, entered) = mpsc::channel();
+    let (release, release_rx) = mpsc::channel();
+    let backend = GatedBackend { inner: mock.clone(), entered: Mutex::new(entered_tx), release: Mutex::new(release_rx) };
+    let queues = QueueRegistry::new();
+    let queue = queues.add(printer("cups-Office"), Arc::new(backend), "0b6e6c7a-1c1f-4b8e-9d5e-3f0c4d2a7b11");
+
+    let job_id = queues.submit(&queue, b"%PDF-1.7".to_vec(), JobInfo::default()).unwrap();
+    entered.recv_timeout(Duration::from_secs(5)).expect("job was not submitted");
+
+    // 后端还没有返回任务 ID 时取消，只能在本地标记
+    queues.cancel(job_id).unwrap();
+    release.send(()).unwrap();
+
+    wait_for_jobs(&mock, 1);
+    let cancelled = (0..40).any(|_| {
+        thread::sleep(Duration::from_millis(50));
+        mock.jobs()[0].state == JobState::Cancelled
+    });
+    assert!(cancelled, "job was printed after being cancelled");
+    assert_eq!(queues.job(job_id).unwrap().state, JobState::Cancelled);
+}
@@ -0,0 +1,191 @@
+// src-tauri/tests/ipp_proxy.rs
+//
+// IPP 代理：IppServer 经 IppProxyBackend 镜像替身打印机的属性并转发任务，
+// 描述本服务器自身的属性保持本地值；下游查询失败时缓存失败结果；纸张选项传给 IPP 和 `lp` 两种后端，
+// `lp` 固定在 C locale 下运行。
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use airprinter::models::{Printer, PrinterStatus};
+use airprinter::services::backend::{BackendJob, IppProxyBackend, LpBackend, PrintOptions, PrinterBackend};
+use airprinter::services::ipp::attributes;
+use airprinter::services::{IppServer, QueueRegistry};
+use ipp::parser::IppParser;
+use ipp::prelude::*;
+use tiny_http::{Header, Response, Server};
+
+const DOWNSTREAM_UUID: &str = "urn:uuid:11111111-2222-4333-8444-555555555555";
+
+fn group(tag: DelimiterTag, attrs: Vec<(&str, IppValue)>) -> IppAttributeGroup {
+    let mut group = IppAttributeGroup::new(tag);
+    for (name, value) in attrs {
+        group.attributes_mut().insert(name.to_string(), IppAttribute::new(name, value));
+    }
+    group
+}
+
+/// 替身下游打印机：只接受 ipps 和用户名认证。收到的 Print-Job 以 (作业属性, 文档) 发回
+fn serve_printer(server: Server, jobs: mpsc::Sender<(HashMap<String, String>, Vec<u8>)>) {
+    thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let mut body = Vec::new();
+            request.as_reader().read_to_end(&mut body).unwrap();
+            let parsed = IppParser::new(Cursor::new(body)).parse().unwrap();
+            let (operation, request_id) = (parsed.header().operation_or_status, parsed.header().request_id);
+
+            let mut response = IppRequestResponse::new_response(IppVersion::v1_1(), StatusCode::SuccessfulOk, request_id);
+            if operation == Operation::GetPrinterAttributes as u16 {
+                response.attributes_mut().groups_mut().push(group(DelimiterTag::PrinterAttributes, vec![
+                    ("printer-uri-supported", IppValue::Uri("ipps://canon.local:443/ipp/print".to_string())),
+                    ("uri-security-supported", IppValue::Keyword("tls".to_string())),
+                    ("uri-authentication-supported", IppValue::Keyword("requesting-user-name".to_string())),
+                    ("printer-uuid", IppValue::Uri(DOWNSTREAM_UUID.to_string())),
+                    ("printer-make-and-model", IppValue::TextWithoutLanguage("Canon G7000 series".to_string())),
+                    ("color-supported", IppValue::Boolean(true)),
+                ]));
+            } else if operation == Operation::PrintJob as u16 {
+                let options = parsed
+                    .attributes()
+                    .groups_of(DelimiterTag::JobAttributes)
+                    .flat_map(|g| g.attributes().values())
+                    .map(|a| (a.name().to_string(), a.value().to_string()))
+                    .collect();
+                let mut document = Vec::new();
+                parsed.into_payload().read_to_end(&mut document).unwrap();
+                jobs.send((options, document)).unwrap();
+                response.attributes_mut().groups_mut().push(group(DelimiterTag::JobAttributes, vec![("job-id", IppValue::Integer(42))]));
+            }
+
+            let header = Header::from_bytes("Content-Type", "application/ipp").unwrap();
+            request.respond(Response::from_data(response.to_bytes().to_vec()).with_header(header)).unwrap();
+        }
+    });
+}
+
+fn printer_attr(response: &IppRequestResponse, name: &str) -> Option<String> {
+    attributes::find(response, DelimiterTag::PrinterAttributes, name).map(|v| v.to_string())
+}
+
+#[test]
+fn proxy_mirrors_downstream_printer_and_forwards_jobs() {
+    let downstream = Server::http("127.0.0.1:0").unwrap();
+    let downstream_port = downstream.server_addr().to_ip().unwrap().port();
+    let (sender, jobs) = mpsc::channel();
+    serve_printer(downstream, sender);
+
+    let proxy = IppProxyBackend::new(&format!("ipp://127.0.0.1:{}/ipp/print", downstream_port));
+    let printer = Printer {
+        name: "Canon Lab".to_string(),
+        id: "proxy-canon".to_string(),
+        status: PrinterStatus::Online,
+        details: Default::default(),
+        state: Default::default(),
+        capabilities: None,
+    };
+    let queues = QueueRegistry::new();
+    let queue = queues.add(printer, Arc::new(proxy), "0b6e6c7a-1c1f-4b8e-9d5e-3f0c4d2a7b11");
+    assert!(queue.capabilities.color);
+
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    IppServer::new("127.0.0.1", port, queues).start();
+    let uri: Uri = format!("ipp://127.0.0.1:{}/{}", port, queue.resource_path()).parse().unwrap();
+    let client = IppClient::new(uri.clone());
+
+    let response = (0..20)
+        .find_map(|_| {
+            client.send(IppOperationBuilder::get_printer_attributes(uri.clone()).build()).ok().or_else(|| {
+                thread::sleep(Duration::from_millis(50));
+                None
+            })
+        })
+        .expect("IPP server did not answer");
+
+    // 下游打印机的属性被镜像
+    assert_eq!(printer_attr(&response, "printer-make-and-model").as_deref(), Some("Canon G7000 series"));
+    assert_eq!(printer_attr(&response, "color-supported").as_deref(), Some("true"));
+    // 描述本服务器的属性使用本地值：客户端只能用不加密、不认证的 ipp:// 连接代理
+    assert_eq!(printer_attr(&response, "printer-uri-supported"), Some(uri.to_string()));
+    assert_eq!(printer_attr(&response, "uri-security-supported").as_deref(), Some("none"));
+    assert_eq!(printer_attr(&response, "uri-authentication-supported").as_deref(), Some("none"));
+    assert_eq!(printer_attr(&response, "printer-uuid"), Some(queue.uuid_urn()));
+
+    let print_job = IppOperationBuilder::print_job(uri.clone(), IppPayload::new(Cursor::new(b"%PDF-1.7 photo".to_vec())))
+        .job_title("photo")
+        .user_name("alice")
+        .attribute(IppAttribute::new("copies", IppValue::Integer(2)))
+        .attribute(IppAttribute::new("media", IppValue::Keyword("iso_a5_148x210mm".to_string())))
+        .build();
+    let response = client.send(print_job).unwrap();
+    assert!(response.header().status_code().is_success());
+
+    let (options, document) = jobs.recv_timeout(Duration::from_secs(5)).expect("job was not forwarded");
+    assert_eq!(document, b"%PDF-1.7 photo");
+    assert_eq!(options["media"], "iso_a5_148x210mm");
+    assert_eq!(options["copies"], "2");
+    assert_eq!(options["sides"], "one-sided");
+}
+
+#[test]
+fn failed_downstream_query_is_cached() {
+    // 替身下游打印机：每个请求都返回 HTTP 500
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let port = server.server_addr().to_ip().unwrap().port();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            counter.fetch_add(1, Ordering::SeqCst);
+            request.respond(Response::empty(500)).unwrap();
+        }
+    });
+
+    let proxy = IppProxyBackend::new(&format!("ipp://127.0.0.1:{}/ipp/print", port));
+    assert!(proxy.printer_attributes().is_err());
+    // 失败结果在短时间内直接返回，不再访问下游
+    assert!(proxy.printer_attributes().is_err());
+    assert!(proxy.capabilities().is_err());
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn lp_backend_passes_media() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // 替身 `lp`：记录参数，输出 CUPS 的任务 ID
+    let dir = tempfile::tempdir().unwrap();
+    let args = dir.path().join("args");
+    let script = dir.path().join("lp");
+    std::fs::write(&script, format!("#!/bin/sh\necho \"$@\" > {}\necho \"$LC_ALL\" >> {}\necho 'request id is Office-7 (1 file(s))'\n", args.display(), args.display())).unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = std::env::var("PATH").unwrap_or_default();
+    std::env::set_var("PATH", format!("{}:{}", dir.path().display(), path));
+
+    let job = |media: &str| BackendJob {
+        id: 7,
+        name: "photo".to_string(),
+        user: "alice".to_string(),
+        device: "192.168.1.20".to_string(),
+        document_format: "application/pdf".to_string(),
+        document: PathBuf::from("/tmp/photo.pdf"),
+        options: PrintOptions { media: media.to_string(), ..PrintOptions::default() },
+    };
+    let backend = LpBackend::new("Office");
+
+    assert_eq!(backend.submit(&job("na_letter_8.5x11in")).unwrap(), "Office-7");
+    let recorded = std::fs::read_to_string(&args).unwrap();
+    assert!(recorded.contains("-o media=na_letter_8.5x11in"), "{}", recorded);
+    // 固定 C locale，任务 ID 的解析不随系统语言变化
+    assert!(recorded.lines().any(|line| line == "C"), "{}", recorded);
+
+    // 未指定纸张时交给打印机的默认值
+    backend.submit(&job("")).unwrap();
+    assert!(!std::fs::read_to_string(&args).unwrap().contains("media="));
+}
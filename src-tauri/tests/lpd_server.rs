@@ -0,0 +1,177 @@
+// src-tauri/tests/lpd_server.rs
+//
+// LPD (RFC 1179)：按协议收发接收任务、查询队列和删除任务，后端用 MockBackend。
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use airprinter::models::{Printer, PrinterStatus};
+use airprinter::services::backend::{JobState, MockBackend};
+use airprinter::services::print_queue::MAX_DOCUMENT_SIZE;
+use airprinter::services::{LpdServer, QueueRegistry};
+
+fn printer() -> Printer {
+    Printer {
+        name: "Lab Printer".to_string(),
+        id: "cups-Lab".to_string(),
+        status: PrinterStatus::Online,
+        details: Default::default(),
+        state: Default::default(),
+        capabilities: Some(Default::default()),
+    }
+}
+
+fn start() -> (u16, LpdServer, QueueRegistry, MockBackend) {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let queues = QueueRegistry::new();
+    let mock = MockBackend::new();
+    queues.add(printer(), Arc::new(mock.clone()), "0b6e6c7a-1c1f-4b8e-9d5e-3f0c4d2a7b11");
+    let server = LpdServer::new("127.0.0.1", port, queues.clone());
+    server.start().unwrap();
+    (port, server, queues, mock)
+}
+
+/// 读一个应答字节：0 为确认
+fn reply(stream: &mut TcpStream) -> u8 {
+    let mut byte = [0xFFu8; 1];
+    stream.read_exact(&mut byte).unwrap();
+    byte[0]
+}
+
+/// 发送子命令和文件内容，返回 (子命令应答, 文件应答)
+fn send_file(stream: &mut TcpStream, subcommand: u8, name: &str, content: &[u8]) -> (u8, u8) {
+    stream.write_all(&[subcommand]).unwrap();
+    writeln!(stream, "{} {}", content.len(), name).unwrap();
+    let accepted = reply(stream);
+    if accepted != 0 {
+        return (accepted, 1);
+    }
+    stream.write_all(content).unwrap();
+    stream.write_all(&[0]).unwrap();
+    (accepted, reply(stream))
+}
+
+/// 02 接收任务：控制文件 + 一个数据文件
+fn print(port: u16, queue: &str, user: &str, data: &[u8]) {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    writeln!(stream, "\x02{}", queue).unwrap();
+    assert_eq!(reply(&mut stream), 0);
+    let control = format!("Hlaptop\nP{}\nJquarterly report\nldfA001laptop\nldfA001laptop\nNreport.ps\n", user);
+    assert_eq!(send_file(&mut stream, 0x02, "cfA001laptop", control.as_bytes()), (0, 0));
+    assert_eq!(send_file(&mut stream, 0x03, "dfA001laptop", data), (0, 0));
+}
+
+/// 03 / 04 / 05 命令，读到连接关闭
+fn command(port: u16, line: &str) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream.write_all(line.as_bytes()).unwrap();
+    let mut out = String::new();
+    stream.read_to_string(&mut out).unwrap();
+    out
+}
+
+fn wait_for_jobs(mock: &MockBackend, count: usize) {
+    for _ in 0..40 {
+        if mock.jobs().len() >= count {
+            return;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    panic!("LPD job was not submitted");
+}
+
+#[test]
+fn receive_job_parses_control_file() {
+    let (port, server, _, mock) = start();
+    print(port, "cups-Lab", "alice", b"%!PS-Adobe-3.0\nshowpage\n");
+    wait_for_jobs(&mock, 1);
+
+    let job = &mock.jobs()[0];
+    assert_eq!(job.job.user, "alice");
+    assert_eq!(job.job.name, "quarterly report");
+    assert_eq!(job.job.device, "127.0.0.1");
+    assert_eq!(job.job.options.copies, 2);
+    assert_eq!(job.job.document_format, "application/postscript");
+    assert_eq!(job.data, b"%!PS-Adobe-3.0\nshowpage\n");
+
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream.write_all(b"\x02missing\n").unwrap();
+    // 只有一个共享打印机时任意队列名都指向它
+    assert_eq!(reply(&mut stream), 0);
+    server.stop();
+}
+
+#[test]
+fn oversized_files_are_refused() {
+    let (port, server, _, mock) = start();
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream.write_all(b"\x02cups-Lab\n").unwrap();
+    assert_eq!(reply(&mut stream), 0);
+
+    // 声明的大小超过上限时不确认，也不分配内存
+    writeln!(stream, "\x03{} dfA002laptop", MAX_DOCUMENT_SIZE + 1).unwrap();
+    assert_eq!(reply(&mut stream), 1);
+    writeln!(stream, "\x02{} cfA002laptop", 1024 * 1024).unwrap();
+    assert_eq!(reply(&mut stream), 1);
+    drop(stream);
+
+    thread::sleep(Duration::from_millis(200));
+    assert!(mock.jobs().is_empty());
+    server.stop();
+}
+
+#[test]
+fn overlong_command_line_closes_the_connection() {
+    let (port, server, _, _) = start();
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    // 服务端读到上限即断开，之后的写入可能被重置
+    let _ = stream.write_all(format!("\x03{}\n", "a".repeat(64 * 1024)).as_bytes());
+    let mut out = Vec::new();
+    let _ = stream.read_to_end(&mut out);
+    assert!(out.is_empty());
+
+    // 服务仍然可用
+    assert!(command(port, "\x03cups-Lab\n").contains("no entries"));
+    server.stop();
+}
+
+#[test]
+fn queue_state_and_remove_jobs() {
+    let (port, server, queues, mock) = start();
+    print(port, "Lab Printer", "alice", b"%PDF-1.7 alice");
+    print(port, "cups-Lab", "bob", b"%PDF-1.7 bob");
+    wait_for_jobs(&mock, 2);
+    // 让任务保持在打印中
+    for job in mock.jobs() {
+        mock.set_state(&format!("mock-{}", job.job.id), JobState::Processing);
+    }
+    let ids: Vec<u32> = mock.jobs().iter().map(|j| j.job.id).collect();
+
+    let short = command(port, "\x03cups-Lab\n");
+    assert!(short.starts_with("Rank   Owner"));
+    assert!(short.contains("active") && short.contains("alice") && short.contains("bob"));
+    assert!(short.contains("quarterly report"));
+
+    let long = command(port, &format!("\x04cups-Lab {}\n", ids[1]));
+    assert!(long.starts_with("bob: active"));
+    assert!(!long.contains("alice"));
+    assert!(command(port, "\x03cups-Lab carol\n").contains("no entries"));
+
+    // 只能删除自己的任务，root 可以删除任何人的
+    command(port, &format!("\x05cups-Lab bob {}\n", ids[0]));
+    assert_eq!(queues.job(ids[0]).unwrap().state, JobState::Processing);
+    command(port, "\x05cups-Lab alice\n");
+    assert_eq!(queues.job(ids[0]).unwrap().state, JobState::Cancelled);
+    command(port, "\x05cups-Lab root bob\n");
+    assert_eq!(queues.job(ids[1]).unwrap().state, JobState::Cancelled);
+    assert!(command(port, "\x03cups-Lab\n").contains("no entries"));
+
+    // 停止后可以立即在同一端口重新启动
+    server.stop();
+    let restarted = LpdServer::new("127.0.0.1", port, queues);
+    restarted.start().expect("port still in use after stop()");
+    restarted.stop();
+}
@@ -0,0 +1,175 @@
+// src-tauri/tests/mdns_loopback.rs
+//
+// 本机回环测试：用 MdnsBroadcaster 广播，再用 mdns-sd 浏览，
+// 检查子类型和 TXT 记录对客户端可见，重发通告时不会撤回服务，注册后出现的同名服务会让本机改名。
+// 需要一个可用的组播网卡。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use airprinter::models::{MdnsConfig, Printer, PrinterCapabilities, PrinterDetails, PrinterStatus};
+use airprinter::services::backend::MockBackend;
+use airprinter::services::{MdnsBackend, MdnsBroadcaster, QueueRegistry};
+use mdns_sd::{Receiver, ServiceDaemon, ServiceEvent, ServiceInfo};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 从浏览结果中等待名称包含 `instance` 的事件
+fn wait_for<T>(receiver: &Receiver<ServiceEvent>, instance: &str, mut matches: impl FnMut(ServiceEvent) -> Option<T>) -> T {
+    let deadline = Instant::now() + TIMEOUT;
+    while Instant::now() < deadline {
+        if let Ok(event) = receiver.recv_timeout(Duration::from_millis(200)) {
+            if let Some(found) = matches(event) {
+                return found;
+            }
+        }
+    }
+    panic!("no matching mDNS event for {}", instance);
+}
+
+fn resolve(browser: &ServiceDaemon, ty_domain: &str, instance: &str) -> ServiceInfo {
+    let receiver = browser.browse(ty_domain).expect("browse failed");
+    let info = wait_for(&receiver, instance, |event| match event {
+        ServiceEvent::ServiceResolved(info) if info.get_fullname().contains(instance) => Some(info),
+        _ => None,
+    });
+    let _ = browser.stop_browse(ty_domain);
+    info
+}
+
+#[test]
+fn airprint_subtypes_and_txt_are_visible() {
+    let instance = format!("loopback-{}", std::process::id());
+    let uuid = "8d5a37a4-3d1e-4c3b-9b8f-6f2d0c1a7e55";
+    let queue = QueueRegistry::new().add(
+        Printer { name: instance.clone(), id: "test-printer".to_string(), status: PrinterStatus::Online, details: PrinterDetails::default(), state: Default::default(), capabilities: None },
+        Arc::new(MockBackend::with_capabilities(PrinterCapabilities {
+            document_formats: vec!["application/pdf".to_string(), "image/urf".to_string()],
+            ..PrinterCapabilities::default()
+        })),
+        uuid,
+    );
+
+    let mut broadcaster = MdnsBroadcaster::new().expect("mDNS daemon");
+    broadcaster.set_config(MdnsConfig {
+        print_subtype: false,
+        ipps_port: Some(8631),
+        ..MdnsConfig::default()
+    });
+    broadcaster
+        .broadcast_airprint(&queue, 631)
+        .expect("broadcast failed");
+
+    let browser = ServiceDaemon::new().expect("browser daemon");
+
+    // iOS 浏览的 _universal 子类型
+    let ipp = resolve(&browser, "_universal._sub._ipp._tcp.local.", &instance);
+    assert_eq!(ipp.get_subtype().as_deref(), Some("_universal._sub._ipp._tcp.local."));
+    assert_eq!(ipp.get_port(), 631);
+    assert_eq!(ipp.get_property_val_str("rp"), Some("ipp/print/test-printer"));
+    assert_eq!(ipp.get_property_val_str("ty"), Some(instance.as_str()));
+    assert_eq!(ipp.get_property_val_str("UUID"), Some(uuid));
+    assert!(ipp.get_property_val_str("URF").is_some());
+    assert!(ipp.get_property_val_str("pdl").unwrap_or("").contains("image/urf"));
+
+    // 普通 _ipp._tcp 浏览同样可见，保持浏览以便之后收到 goodbye
+    let plain_browse = browser.browse("_ipp._tcp.local.").expect("browse failed");
+    let plain = wait_for(&plain_browse, &instance, |event| match event {
+        ServiceEvent::ServiceResolved(info) if info.get_fullname().contains(&instance) => Some(info),
+        _ => None,
+    });
+    assert_eq!(plain.get_fullname(), ipp.get_fullname());
+
+    // 启用后的 _ipps._tcp
+    let ipps = resolve(&browser, "_universal._sub._ipps._tcp.local.", &instance);
+    assert_eq!(ipps.get_port(), 8631);
+    assert_eq!(ipps.get_property_val_str("TLS"), Some("1.2"));
+    assert_eq!(ipps.get_property_val_str("rp"), Some("ipp/print/test-printer"));
+
+    // 撤回后客户端收到 goodbye
+    broadcaster.withdraw("test-printer");
+    wait_for(&plain_browse, &instance, |event| match event {
+        ServiceEvent::ServiceRemoved(_, fullname) if fullname.contains(&instance) => Some(()),
+        _ => None,
+    });
+
+    let _ = browser.shutdown();
+}
+
+#[test]
+fn announcement_refresh_does_not_withdraw() {
+    let instance = format!("refresh-{}", std::process::id());
+    let queue = QueueRegistry::new().add(
+        Printer { name: instance.clone(), id: "refresh-printer".to_string(), status: PrinterStatus::Online, details: PrinterDetails::default(), state: Default::default(), capabilities: None },
+        Arc::new(MockBackend::new()),
+        "5f0e1d2c-8b7a-4c6d-9e5f-1a2b3c4d5e6f",
+    );
+
+    let mut broadcaster = MdnsBroadcaster::new().expect("mDNS daemon");
+    broadcaster.set_config(MdnsConfig {
+        announce_interval_secs: 1,
+        ..MdnsConfig::default()
+    });
+    broadcaster
+        .broadcast_airprint(&queue, 631)
+        .expect("broadcast failed");
+
+    let browser = ServiceDaemon::new().expect("browser daemon");
+    let receiver = browser.browse("_ipp._tcp.local.").expect("browse failed");
+    wait_for(&receiver, &instance, |event| match event {
+        ServiceEvent::ServiceResolved(info) if info.get_fullname().contains(&instance) => Some(()),
+        _ => None,
+    });
+
+    // 经过几次重发通告，客户端不应收到 goodbye
+    let deadline = Instant::now() + Duration::from_secs(3);
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        if let Ok(ServiceEvent::ServiceRemoved(_, fullname)) = receiver.recv_timeout(remaining) {
+            assert!(!fullname.contains(&instance), "service withdrawn during refresh");
+        }
+    }
+
+    // stop 等待心跳线程退出
+    let started = Instant::now();
+    broadcaster.stop();
+    assert!(started.elapsed() < Duration::from_secs(5));
+    let _ = browser.shutdown();
+}
+
+#[test]
+fn later_name_conflict_renames_the_printer() {
+    let instance = format!("conflict-{}", std::process::id());
+    let queue = QueueRegistry::new().add(
+        Printer { name: instance.clone(), id: "conflict-printer".to_string(), status: PrinterStatus::Online, details: PrinterDetails::default(), state: Default::default(), capabilities: None },
+        Arc::new(MockBackend::new()),
+        "2c4e6a8b-1d3f-4a5b-8c7d-9e0f1a2b3c4d",
+    );
+
+    let mut broadcaster = MdnsBroadcaster::new().expect("mDNS daemon");
+    broadcaster
+        .broadcast_airprint(&queue, 631)
+        .expect("broadcast failed");
+    let fullname = format!("{}._ipp._tcp.local.", instance);
+    assert!(broadcaster.services().iter().any(|s| s.get_fullname() == fullname));
+
+    // 注册之后另一台主机通告同名打印机
+    let peer = ServiceDaemon::new().expect("peer daemon");
+    let other = ServiceInfo::new("_ipp._tcp.local.", &instance, "peer-printer.local.", "", 631, HashMap::<String, String>::new())
+        .expect("service info")
+        .enable_addr_auto();
+    peer.register(other).expect("peer register failed");
+
+    let renamed = format!("{} (2)._ipp._tcp.local.", instance);
+    let deadline = Instant::now() + TIMEOUT;
+    while !broadcaster.services().iter().any(|s| s.get_fullname() == renamed) {
+        assert!(Instant::now() < deadline, "printer was not renamed: {:?}", broadcaster.services().iter().map(|s| s.get_fullname().to_string()).collect::<Vec<_>>());
+        thread::sleep(Duration::from_millis(200));
+    }
+    // 同一打印机的其他服务一起改名
+    assert!(broadcaster.services().iter().all(|s| !s.get_fullname().starts_with(&format!("{}.", instance))));
+
+    broadcaster.stop();
+    let _ = peer.shutdown();
+}
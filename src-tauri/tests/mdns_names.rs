@@ -0,0 +1,49 @@
+// src-tauri/tests/mdns_names.rs
+//
+// DNS-SD 实例名：去掉控制字符、替换 '.'，按字符边界截断到 63 字节，
+// 重名时加 " (n)" 后缀且总长仍不超过 63 字节。
+
+use airprinter::services::mdns_broadcaster::{instance_name, truncate_utf8, with_suffix};
+
+#[test]
+fn instance_name_replaces_dots_and_control_characters() {
+    assert_eq!(instance_name("HP LaserJet @ Reception"), "HP LaserJet @ Reception");
+    assert_eq!(instance_name("hp.lab.example"), "hp-lab-example");
+    assert_eq!(instance_name(" Office\tPrinter\n "), "OfficePrinter");
+    assert_eq!(instance_name("\u{7}"), "AirPrinter");
+}
+
+#[test]
+fn instance_name_truncates_on_character_boundary() {
+    // 'a' + 21 个 3 字节汉字 = 64 字节，截断时不能拆开最后一个汉字
+    let name = instance_name(&format!("a{}", "打".repeat(21)));
+    assert_eq!(name, format!("a{}", "打".repeat(20)));
+    assert_eq!(name.len(), 61);
+
+    assert_eq!(instance_name(&"x".repeat(70)).len(), 63);
+}
+
+#[test]
+fn suffixes_fit_in_a_label() {
+    assert_eq!(with_suffix("HP LaserJet", 1), "HP LaserJet");
+    assert_eq!(with_suffix("HP LaserJet", 2), "HP LaserJet (2)");
+    assert_eq!(with_suffix("HP LaserJet", 99), "HP LaserJet (99)");
+
+    // 原名截断为 59 字节以内再加 " (2)"
+    let base = "打".repeat(21);
+    let renamed = with_suffix(&base, 2);
+    assert_eq!(renamed, format!("{} (2)", "打".repeat(19)));
+    assert!(renamed.len() <= 63);
+
+    let renamed = with_suffix(&"x".repeat(63), 10);
+    assert_eq!(renamed.len(), 63);
+    assert!(renamed.ends_with("x (10)"));
+}
+
+#[test]
+fn truncate_utf8_keeps_whole_characters() {
+    assert_eq!(truncate_utf8("aé", 2), "a");
+    assert_eq!(truncate_utf8("aé", 3), "aé");
+    assert_eq!(truncate_utf8("打印机", 8), "打印");
+    assert_eq!(truncate_utf8("打印机", 0), "");
+}
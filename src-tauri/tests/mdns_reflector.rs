@@ -0,0 +1,292 @@
+// src-tauri/tests/mdns_reflector.rs
+//
+// mDNS 反射：选择规则、源 / 目标网卡，以及把发现的打印机改为经本机 IPP 代理重新广播。
+// 浏览和广播通过注入的 MdnsBackend 完成，不需要组播网卡。
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use airprinter::models::{MdnsConfig, ReflectorConfig};
+use airprinter::services::mdns_backend::ServiceBrowser;
+use airprinter::services::{MdnsBackend, MdnsReflector, QueueRegistry};
+use ipp::attribute::{IppAttribute, IppAttributeGroup};
+use ipp::model::{DelimiterTag, IppVersion, StatusCode};
+use ipp::parser::IppParser;
+use ipp::request::IppRequestResponse;
+use ipp::value::IppValue;
+use mdns_sd::{ServiceEvent, ServiceInfo};
+use tiny_http::{Header, Response, Server};
+
+const IPP: &str = "_ipp._tcp.local.";
+const IPPS: &str = "_ipps._tcp.local.";
+const CANON_UUID: &str = "6f1c2d3e-4a5b-4c6d-8e7f-90a1b2c3d4e5";
+
+/// 注入的后端记录下的调用
+#[derive(Default)]
+struct Recorded {
+    /// (浏览的服务类型, 源网卡)
+    browsed: Option<(Vec<String>, String)>,
+    config: Option<MdnsConfig>,
+    /// 服务类型 -> (实例名, 端口, TXT)
+    registered: HashMap<String, (String, u16, HashMap<String, String>)>,
+    withdrawn: Vec<String>,
+}
+
+struct FakeMdns {
+    config: MdnsConfig,
+    recorded: Arc<Mutex<Recorded>>,
+    events: Mutex<Option<Receiver<ServiceEvent>>>,
+}
+
+struct FakeBrowser(Receiver<ServiceEvent>);
+
+impl ServiceBrowser for FakeBrowser {
+    fn recv_timeout(&self, timeout: Duration) -> Option<ServiceEvent> {
+        self.0.recv_timeout(timeout).ok()
+    }
+
+    fn stop(&mut self) {}
+}
+
+impl MdnsBackend for FakeMdns {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    fn config(&self) -> &MdnsConfig {
+        &self.config
+    }
+
+    fn set_config(&mut self, config: MdnsConfig) {
+        self.recorded.lock().unwrap().config = Some(config.clone());
+        self.config = config;
+    }
+
+    fn admin_url(&self) -> Result<String, String> {
+        Ok("http://192.168.2.1:631/".to_string())
+    }
+
+    fn register_service(
+        &mut self,
+        _printer_id: &str,
+        service_type: &str,
+        _subtypes: &[&str],
+        printer_name: &str,
+        port: u16,
+        txt_records: HashMap<String, String>,
+    ) -> Result<(), String> {
+        let service = (printer_name.to_string(), port, txt_records);
+        self.recorded.lock().unwrap().registered.insert(service_type.to_string(), service);
+        Ok(())
+    }
+
+    fn withdraw_service(&mut self, _printer_id: &str, service_type: &str) {
+        self.recorded.lock().unwrap().registered.remove(service_type);
+    }
+
+    fn withdraw(&mut self, printer_id: &str) {
+        let mut recorded = self.recorded.lock().unwrap();
+        recorded.registered.clear();
+        recorded.withdrawn.push(printer_id.to_string());
+    }
+
+    fn is_registered(&self, _printer_id: &str) -> bool {
+        !self.recorded.lock().unwrap().registered.is_empty()
+    }
+
+    fn services(&self) -> Vec<ServiceInfo> {
+        Vec::new()
+    }
+
+    fn stop(&mut self) {}
+
+    fn browse(&self, service_types: &[&str], interface: &str) -> Result<Box<dyn ServiceBrowser>, String> {
+        let types = service_types.iter().map(|t| t.to_string()).collect();
+        self.recorded.lock().unwrap().browsed = Some((types, interface.to_string()));
+        let events = self.events.lock().unwrap().take().ok_or("browse called twice")?;
+        Ok(Box::new(FakeBrowser(events)))
+    }
+
+    fn is_own_host(&self, host_name: &str) -> bool {
+        host_name.starts_with("airprinter-")
+    }
+}
+
+fn fake_mdns() -> (FakeMdns, Arc<Mutex<Recorded>>, Sender<ServiceEvent>) {
+    let (sender, events) = mpsc::channel();
+    let recorded = Arc::new(Mutex::new(Recorded::default()));
+    let fake = FakeMdns { config: MdnsConfig::default(), recorded: recorded.clone(), events: Mutex::new(Some(events)) };
+    (fake, recorded, sender)
+}
+
+/// 源网卡上发现的打印机服务
+fn printer_info(ty: &str, instance: &str, host: &str, port: u16) -> ServiceInfo {
+    let mut txt = HashMap::new();
+    txt.insert("rp".to_string(), "ipp/print".to_string());
+    txt.insert("ty".to_string(), "Canon G7000 series".to_string());
+    txt.insert("UUID".to_string(), CANON_UUID.to_string());
+    ServiceInfo::new(ty, instance, host, "127.0.0.1", port, txt).unwrap()
+}
+
+/// 替身原打印机：记录请求路径，Get-Printer-Attributes 返回一台彩色打印机
+fn serve_printer(server: Server) -> Arc<Mutex<Vec<String>>> {
+    let paths = Arc::new(Mutex::new(Vec::new()));
+    let recorded = paths.clone();
+    thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            recorded.lock().unwrap().push(request.url().to_string());
+            let mut body = Vec::new();
+            request.as_reader().read_to_end(&mut body).unwrap();
+            let parsed = IppParser::new(std::io::Cursor::new(body)).parse().unwrap();
+            let mut response = IppRequestResponse::new_response(IppVersion::v1_1(), StatusCode::SuccessfulOk, parsed.header().request_id);
+
+            let mut group = IppAttributeGroup::new(DelimiterTag::PrinterAttributes);
+            group
+                .attributes_mut()
+                .insert("color-supported".to_string(), IppAttribute::new("color-supported", IppValue::Boolean(true)));
+            response.attributes_mut().groups_mut().push(group);
+
+            let header = Header::from_bytes("Content-Type", "application/ipp").unwrap();
+            request.respond(Response::from_data(response.to_bytes().to_vec()).with_header(header)).unwrap();
+        }
+    });
+    paths
+}
+
+fn wait_until(mut done: impl FnMut() -> bool) {
+    for _ in 0..60 {
+        if done() {
+            return;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    panic!("reflector did not handle the events");
+}
+
+#[test]
+fn selection_rules_match_printer_names() {
+    let config = ReflectorConfig {
+        include: vec!["HP*".to_string(), "*lab*".to_string()],
+        exclude: vec!["*guest*".to_string()],
+        ..Default::default()
+    };
+    assert!(config.selects("HP LaserJet 400"));
+    // 不区分大小写
+    assert!(config.selects("hp laserjet"));
+    assert!(config.selects("Canon Lab 2"));
+    assert!(!config.selects("Canon Office"));
+    // 排除优先
+    assert!(!config.selects("HP Guest Printer"));
+
+    let exact = ReflectorConfig { include: vec!["Office".to_string(), "A*B*C".to_string()], ..Default::default() };
+    assert!(exact.selects("office"));
+    assert!(!exact.selects("Office 2"));
+    assert!(exact.selects("a-b-c"));
+    assert!(!exact.selects("a-c-b"));
+
+    // include 为空时不反射任何打印机
+    assert!(!ReflectorConfig::default().selects("HP LaserJet"));
+}
+
+#[test]
+fn browses_source_and_advertises_on_target_interface() {
+    let mdns = MdnsConfig { interfaces: vec!["eth0".to_string()], ..MdnsConfig::default() };
+    let config = ReflectorConfig {
+        enabled: true,
+        source_interface: "eth1".to_string(),
+        target_interface: "wlan0".to_string(),
+        include: vec!["*".to_string()],
+        exclude: Vec::new(),
+    };
+    let (fake, recorded, _events) = fake_mdns();
+    let mut reflector = MdnsReflector::with_backend(config, mdns.clone(), QueueRegistry::new(), Box::new(fake)).unwrap();
+    {
+        let recorded = recorded.lock().unwrap();
+        let (types, interface) = recorded.browsed.clone().unwrap();
+        assert_eq!(types, vec![IPP.to_string(), IPPS.to_string()]);
+        assert_eq!(interface, "eth1");
+        assert_eq!(recorded.config.as_ref().unwrap().interfaces, vec!["wlan0".to_string()]);
+    }
+    reflector.stop();
+
+    // 未指定网卡时浏览全部网卡，广播沿用 mDNS 设置中的网卡
+    let (fake, recorded, _events) = fake_mdns();
+    let mut reflector = MdnsReflector::with_backend(ReflectorConfig::default(), mdns, QueueRegistry::new(), Box::new(fake)).unwrap();
+    {
+        let recorded = recorded.lock().unwrap();
+        assert_eq!(recorded.browsed.as_ref().unwrap().1, "");
+        assert_eq!(recorded.config.as_ref().unwrap().interfaces, vec!["eth0".to_string()]);
+    }
+    reflector.stop();
+}
+
+#[test]
+fn discovered_printer_is_readvertised_through_the_ipp_proxy() {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let port = server.server_addr().to_ip().unwrap().port();
+    let paths = serve_printer(server);
+
+    let queues = QueueRegistry::new();
+    let config = ReflectorConfig {
+        enabled: true,
+        include: vec!["Canon*".to_string()],
+        exclude: vec!["*Guest*".to_string()],
+        ..Default::default()
+    };
+    let (fake, recorded, events) = fake_mdns();
+    let mut reflector = MdnsReflector::with_backend(config, MdnsConfig::default(), queues.clone(), Box::new(fake)).unwrap();
+
+    events.send(ServiceEvent::ServiceResolved(printer_info(IPP, "Canon Lab", "canon-lab.local.", port))).unwrap();
+    // 同一打印机的 IPPS 只列出，不重复反射
+    events.send(ServiceEvent::ServiceResolved(printer_info(IPPS, "Canon Lab", "canon-lab.local.", 443))).unwrap();
+    events.send(ServiceEvent::ServiceResolved(printer_info(IPP, "Canon Guest", "canon-guest.local.", port))).unwrap();
+    // 自己广播的服务不反射
+    events.send(ServiceEvent::ServiceResolved(printer_info(IPP, "Canon Lab (2)", "airprinter-reflect.local.", 631))).unwrap();
+    wait_until(|| reflector.discovered().len() == 3);
+
+    let discovered = reflector.discovered();
+    let summary: Vec<(&str, &str, bool, bool)> =
+        discovered.iter().map(|p| (p.name.as_str(), p.service_type.as_str(), p.selected, p.reflected)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Canon Guest", "_ipp._tcp", false, false),
+            ("Canon Lab", "_ipp._tcp", true, true),
+            ("Canon Lab", "_ipps._tcp", true, false),
+        ]
+    );
+    assert_eq!(discovered[1].uri, format!("ipp://127.0.0.1:{}/ipp/print", port));
+    assert_eq!(discovered[2].uri, "ipps://127.0.0.1:443/ipp/print");
+    assert_eq!(discovered[1].model, "Canon G7000 series");
+
+    // 目标网卡上广播的是本机 631 端口上的代理队列，沿用原打印机的 UUID
+    let all = queues.queues();
+    assert_eq!(all.len(), 1);
+    let queue = &all[0];
+    assert_eq!(queue.printer.name, "Canon Lab");
+    assert_eq!(queue.uuid, CANON_UUID);
+    assert_eq!(queue.backend.name(), "ipp-proxy");
+    {
+        let recorded = recorded.lock().unwrap();
+        let (name, advertised_port, txt) = &recorded.registered[IPP];
+        assert_eq!((name.as_str(), *advertised_port), ("Canon Lab", 631));
+        assert_eq!(txt["rp"], queue.resource_path());
+        assert_eq!(txt["UUID"], CANON_UUID);
+        assert_eq!(txt["adminurl"], "http://192.168.2.1:631/");
+    }
+
+    // 代理把请求转发给原打印机
+    let attributes = queue.backend.printer_attributes().unwrap();
+    assert!(attributes.iter().any(|a| a.name() == "color-supported"));
+    assert!(paths.lock().unwrap().iter().all(|p| p == "/ipp/print"));
+    assert!(queue.capabilities.color);
+
+    // 原打印机消失后撤回广播并删除代理队列
+    events.send(ServiceEvent::ServiceRemoved(IPP.to_string(), format!("Canon Lab.{}", IPP))).unwrap();
+    wait_until(|| queues.queues().is_empty());
+    assert_eq!(recorded.lock().unwrap().withdrawn, vec![queue.printer.id.clone()]);
+    reflector.stop();
+}
@@ -0,0 +1,125 @@
+// src-tauri/tests/network_change.rs
+//
+// 注入网卡来源，模拟地址变化 (DHCP 续租 / 切换 Wi-Fi)，
+// 检查 MdnsBroadcaster 用新地址重新广播，以及默认排除虚拟网卡。需要一个可用的组播网卡。
+
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use airprinter::models::{Printer, PrinterDetails, PrinterStatus};
+use airprinter::services::backend::MockBackend;
+use airprinter::services::network_monitor::{advertised_addresses, NetworkInterface};
+use airprinter::services::{InterfaceProvider, MdnsBackend, MdnsBroadcaster, NetworkMonitor, QueueRegistry};
+use local_ip_address::local_ip;
+use mdns_sd::{ServiceDaemon, ServiceEvent};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 可在测试中修改的网卡列表
+struct FakeInterfaces(Mutex<Vec<NetworkInterface>>);
+
+impl FakeInterfaces {
+    fn new(addr: IpAddr) -> Self {
+        Self(Mutex::new(vec![interface(addr)]))
+    }
+
+    fn set(&self, addr: IpAddr) {
+        *self.0.lock().unwrap() = vec![interface(addr)];
+    }
+}
+
+impl InterfaceProvider for FakeInterfaces {
+    fn interfaces(&self) -> Result<Vec<NetworkInterface>, String> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+}
+
+fn interface(addr: IpAddr) -> NetworkInterface {
+    NetworkInterface { name: "eth0".to_string(), addr }
+}
+
+/// 同一网段内的另一个地址，组播包才会被发出
+fn neighbour(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V4(v4) => {
+            let [a, b, c, d] = v4.octets();
+            IpAddr::from([a, b, c, if d == 254 { 253 } else { d + 1 }])
+        }
+        other => other,
+    }
+}
+
+#[test]
+fn monitor_reports_address_change() {
+    let first: IpAddr = "10.0.0.2".parse().unwrap();
+    let second: IpAddr = "10.0.0.3".parse().unwrap();
+    let provider = Arc::new(FakeInterfaces::new(first));
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut monitor = NetworkMonitor::start(provider.clone(), Duration::from_millis(50), move |change| {
+        let _ = sender.send((change.previous.clone(), change.current.clone()));
+    });
+
+    provider.set(second);
+    let change = receiver.recv_timeout(TIMEOUT).expect("no network change reported");
+    assert_eq!(change, (vec![interface(first)], vec![interface(second)]));
+
+    monitor.stop();
+}
+
+#[test]
+fn services_are_readvertised_with_new_address() {
+    let first = local_ip().expect("local ip");
+    let second = neighbour(first);
+    let provider = Arc::new(FakeInterfaces::new(first));
+
+    let instance = format!("netchange-{}", std::process::id());
+    let queue = QueueRegistry::new().add(
+        Printer { name: instance.clone(), id: "net-printer".to_string(), status: PrinterStatus::Online, details: PrinterDetails::default(), state: Default::default(), capabilities: None },
+        Arc::new(MockBackend::new()),
+        "0b7d2c2e-2f51-4a47-8a8e-3c1f5a0c9d11",
+    );
+
+    let mut broadcaster = MdnsBroadcaster::with_provider(provider.clone(), Duration::from_millis(100)).expect("mDNS daemon");
+    broadcaster.broadcast_airprint(&queue, 631).expect("broadcast failed");
+
+    // 地址变化后，客户端应解析到新地址和新的 adminurl
+    provider.set(second);
+
+    let browser = ServiceDaemon::new().expect("browser daemon");
+    let receiver = browser.browse("_ipp._tcp.local.").expect("browse failed");
+    let admin_url = format!("http://{}:631/", second);
+    let deadline = Instant::now() + TIMEOUT;
+    let mut found = false;
+    while Instant::now() < deadline && !found {
+        if let Ok(ServiceEvent::ServiceResolved(info)) = receiver.recv_timeout(Duration::from_millis(200)) {
+            found = info.get_fullname().contains(&instance)
+                && info.get_addresses().contains(&second)
+                && info.get_property_val_str("adminurl") == Some(admin_url.as_str());
+        }
+    }
+    assert!(found, "service not re-advertised at {}", second);
+
+    broadcaster.stop();
+    let _ = browser.shutdown();
+}
+
+#[test]
+fn virtual_adapters_are_excluded_by_default() {
+    let interfaces = vec![
+        NetworkInterface { name: "eth0".to_string(), addr: "192.168.1.10".parse().unwrap() },
+        NetworkInterface { name: "wlan0".to_string(), addr: "fd00::10".parse().unwrap() },
+        NetworkInterface { name: "docker0".to_string(), addr: "172.17.0.1".parse().unwrap() },
+        NetworkInterface { name: "tun0".to_string(), addr: "10.8.0.2".parse().unwrap() },
+        NetworkInterface { name: "lo".to_string(), addr: "127.0.0.1".parse().unwrap() },
+    ];
+
+    // A 记录在前，AAAA 在后
+    let expected: Vec<IpAddr> = vec!["192.168.1.10".parse().unwrap(), "fd00::10".parse().unwrap()];
+    assert_eq!(advertised_addresses(&interfaces, &[]), expected);
+
+    // 用户选择的网卡即使是 VPN 也会广播
+    let selected = vec!["tun0".to_string()];
+    assert_eq!(advertised_addresses(&interfaces, &selected), vec!["10.8.0.2".parse::<IpAddr>().unwrap()]);
+}
@@ -0,0 +1,206 @@
+// src-tauri/tests/printer_capabilities.rs
+//
+// 打印能力的三种来源：PPD 文件、`lpoptions -l` 输出、CUPS 的 IPP 属性，以及查询失败时的保守默认值。
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use airprinter::models::{Printer, PrinterCapabilities, PrinterDetails, PrinterStatus};
+use airprinter::services::backend::{CupsIppBackend, LpBackend, PrinterBackend};
+use airprinter::services::ppd::{parse_lpoptions, parse_ppd, CapabilityCache};
+use airprinter::services::{capabilities, cups, QueueRegistry};
+use ipp::attribute::{IppAttribute, IppAttributeGroup};
+use ipp::model::{DelimiterTag, IppVersion, StatusCode};
+use ipp::parser::IppParser;
+use ipp::request::IppRequestResponse;
+use ipp::value::IppValue;
+use tiny_http::{Header, Response, Server};
+
+const PPD: &str = r#"*PPD-Adobe: "4.3"
+*ModelName: "Brother HL-L2350DW"
+*ColorDevice: False
+*DefaultResolution: 600dpi
+*OpenUI *Resolution/Resolution: PickOne
+*Resolution 600dpi/600 dpi: "<</HWResolution[600 600]>>setpagedevice"
+*Resolution 1200x600dpi/HQ 1200: "<</HWResolution[1200 600]>>setpagedevice"
+*CloseUI: *Resolution
+*OpenUI *Duplex/Two-Sided: PickOne
+*DefaultDuplex: None
+*Duplex None/Off: ""
+*Duplex DuplexNoTumble/Long Edge: ""
+*CloseUI: *Duplex
+*OpenUI *PageSize/Media Size: PickOne
+*DefaultPageSize: A4
+*PageSize A4/A4: "<</PageSize[595 842]>>setpagedevice"
+*PageSize Legal/Legal: "<</PageSize[612 1008]>>setpagedevice"
+*PageSize Env10/Envelope #10: "<</PageSize[297 684]>>setpagedevice"
+*CloseUI: *PageSize
+*PaperDimension A4/A4: "595 842"
+*PaperDimension Legal/Legal: "612 1008"
+*PaperDimension Env10/Envelope #10: "297 684"
+"#;
+
+#[test]
+fn ppd_capabilities() {
+    let caps = parse_ppd(PPD);
+    assert!(!caps.color);
+    assert!(caps.duplex);
+    assert_eq!(caps.resolutions, vec![600]);
+    assert_eq!(caps.media, vec!["iso_a4_210x297mm", "na_legal_8.5x14in", "om_env10_105x241mm"]);
+    assert_eq!(caps.max_paper, "legal-A4");
+}
+
+#[test]
+fn capability_cache_queries_each_queue_once() {
+    let queue = |name: &str, model: &str, capabilities: Option<PrinterCapabilities>| Printer {
+        name: name.to_string(),
+        id: format!("cups-{}", name),
+        status: PrinterStatus::Online,
+        details: PrinterDetails { make_and_model: model.to_string(), ..Default::default() },
+        state: Default::default(),
+        capabilities,
+    };
+    let lookups = AtomicUsize::new(0);
+    let lookup = |_: &str| {
+        lookups.fetch_add(1, Ordering::SeqCst);
+        Some(parse_ppd(PPD))
+    };
+    let cache = CapabilityCache::new();
+
+    // 已有 IPP 能力的队列不查询
+    let mut printers = vec![queue("Office", "Brother HL-L2350DW", None), queue("Lab", "HP", Some(PrinterCapabilities::default()))];
+    cache.fill(&mut printers, lookup);
+    cache.fill(&mut printers, lookup);
+    assert_eq!(lookups.load(Ordering::SeqCst), 1);
+    assert_eq!(printers[0].capabilities, Some(parse_ppd(PPD)));
+
+    // 换了驱动 (型号变化) 重新查询
+    let mut printers = vec![queue("Office", "Brother HL-L2370DW", None)];
+    cache.fill(&mut printers, lookup);
+    assert_eq!(lookups.load(Ordering::SeqCst), 2);
+
+    // 删除后重新添加的同名队列重新查询
+    cache.fill(&mut [], lookup);
+    let mut printers = vec![queue("Office", "Brother HL-L2370DW", None)];
+    cache.fill(&mut printers, lookup);
+    assert_eq!(lookups.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn lpoptions_capabilities() {
+    let text = "\
+ColorModel/Color Mode: Gray *RGB
+Duplex/2-Sided Printing: *None DuplexNoTumble DuplexTumble
+PageSize/Media Size: *A4 A4.Fullbleed A3 A4Rotated Letter Custom.WIDTHxHEIGHT
+Resolution/Resolution: 300dpi *600dpi
+";
+    let caps = parse_lpoptions(text);
+    assert!(caps.color);
+    assert!(caps.duplex);
+    assert_eq!(caps.media, vec!["iso_a4_210x297mm", "iso_a3_297x420mm", "na_letter_8.5x11in"]);
+    assert_eq!(caps.max_paper, "isoC-A2");
+    assert_eq!(caps.resolutions, vec![300, 600]);
+
+    let mono = parse_lpoptions("ColorModel/Output Mode: *Gray\nDuplex/2-Sided: *None\n");
+    assert!(!mono.color && !mono.duplex);
+}
+
+#[test]
+fn cups_ipp_attributes_become_capabilities() {
+    let mut attrs = HashMap::new();
+    let mut add = |name: &str, value: IppValue| {
+        attrs.insert(name.to_string(), IppAttribute::new(name, value));
+    };
+    add("printer-name", IppValue::NameWithoutLanguage("Canon".to_string()));
+    add("color-supported", IppValue::Boolean(false));
+    add("sides-supported", IppValue::Array(vec![
+        IppValue::Keyword("one-sided".to_string()),
+        IppValue::Keyword("two-sided-long-edge".to_string()),
+    ]));
+    add("media-supported", IppValue::Array(vec![
+        IppValue::Keyword("na_letter_8.5x11in".to_string()),
+        IppValue::Keyword("iso_a4_210x297mm".to_string()),
+    ]));
+    add("printer-resolution-supported", IppValue::Resolution { cross_feed: 600, feed: 600, units: 3 });
+
+    let printer = cups::printer_from_attributes(&attrs).unwrap();
+    let caps = printer.capabilities.expect("capabilities from IPP");
+    assert!(!caps.color && caps.duplex);
+    assert_eq!(caps.resolutions, vec![600]);
+    assert_eq!(caps.media, vec!["na_letter_8.5x11in", "iso_a4_210x297mm"]);
+
+    // 共享时 media-default 优先 A4
+    let ipp = capabilities::ipp_attributes(&caps);
+    let find = |name: &str| ipp.iter().find(|a| a.name() == name).map(|a| a.value().to_string());
+    assert_eq!(find("media-default").as_deref(), Some("iso_a4_210x297mm"));
+    assert!(find("media-supported").is_some());
+
+    // 没有任何能力属性的 raw 队列留给 PPD / lpoptions
+    attrs.retain(|name, _| name == "printer-name");
+    assert!(cups::printer_from_attributes(&attrs).unwrap().capabilities.is_none());
+}
+
+/// 替身 CUPS：Get-Printer-Attributes 返回一台黑白、双面、接受 PDF 和 URF 的打印机
+fn serve_cups(server: Server) {
+    thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let mut body = Vec::new();
+            request.as_reader().read_to_end(&mut body).unwrap();
+            let parsed = IppParser::new(std::io::Cursor::new(body)).parse().unwrap();
+            let mut response = IppRequestResponse::new_response(IppVersion::v1_1(), StatusCode::SuccessfulOk, parsed.header().request_id);
+
+            let mut group = IppAttributeGroup::new(DelimiterTag::PrinterAttributes);
+            let keywords = |values: &[&str]| IppValue::Array(values.iter().map(|v| IppValue::Keyword(v.to_string())).collect());
+            for (name, value) in [
+                ("color-supported", IppValue::Boolean(false)),
+                ("sides-supported", keywords(&["one-sided", "two-sided-long-edge"])),
+                ("document-format-supported", IppValue::Array(vec![
+                    IppValue::MimeMediaType("application/pdf".to_string()),
+                    IppValue::MimeMediaType("image/urf".to_string()),
+                ])),
+            ] {
+                group.attributes_mut().insert(name.to_string(), IppAttribute::new(name, value));
+            }
+            response.attributes_mut().groups_mut().push(group);
+
+            let header = Header::from_bytes("Content-Type", "application/ipp").unwrap();
+            request.respond(Response::from_data(response.to_bytes().to_vec()).with_header(header)).unwrap();
+        }
+    });
+}
+
+#[test]
+fn unknown_capabilities_are_advertised_conservatively() {
+    // 查询失败时不声明彩色、双面，也不声明 PDF 以外的格式
+    let caps = PrinterCapabilities::default();
+    let txt: HashMap<String, String> = capabilities::txt_records(&caps).into_iter().collect();
+    assert_eq!((txt["Color"].as_str(), txt["Duplex"].as_str(), txt["pdl"].as_str()), ("F", "F", "application/pdf"));
+    assert!(!txt["URF"].contains("DM1") && !txt["URF"].contains("SRGB24"));
+
+    let unreachable = CupsIppBackend::new("ipp://127.0.0.1:9/printers/Office");
+    let printer = Printer {
+        name: "Office".to_string(),
+        id: "cups-Office".to_string(),
+        status: PrinterStatus::Online,
+        details: Default::default(),
+        state: Default::default(),
+        capabilities: None,
+    };
+    let queue = QueueRegistry::new().add(printer, Arc::new(unreachable), "0b6e6c7a-1c1f-4b8e-9d5e-3f0c4d2a7b11");
+    assert_eq!(queue.capabilities, PrinterCapabilities::default());
+}
+
+#[test]
+fn lp_backend_queries_cups_for_capabilities() {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let address = server.server_addr().to_ip().unwrap().to_string();
+    serve_cups(server);
+    std::env::set_var("CUPS_SERVER", &address);
+
+    let caps = LpBackend::new("Office").capabilities().expect("capabilities from CUPS");
+    assert!(!caps.color);
+    assert!(caps.duplex);
+    assert_eq!(caps.document_formats, vec!["application/pdf", "image/urf"]);
+}
@@ -0,0 +1,115 @@
+// src-tauri/tests/printer_icons.rs
+//
+// 打印机图标：检查缩放结果、HTTP 路径和 printer-icons 属性。
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use airprinter::models::{Printer, PrinterDetails, PrinterProfile, PrinterStatus};
+use airprinter::services::backend::MockBackend;
+use airprinter::services::ipp::attributes;
+use airprinter::services::printer_icons::{self, PrinterIcons, ICON_SIZES};
+use airprinter::services::{IppServer, QueueRegistry};
+use ipp::prelude::*;
+
+/// 解码为 (宽, 高, RGBA)
+fn decode(data: &[u8]) -> (u32, u32, Vec<u8>) {
+    let mut reader = png::Decoder::new(data).read_info().unwrap();
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    buf.truncate(info.buffer_size());
+    (info.width, info.height, buf)
+}
+
+/// 纯色 RGB 图片
+fn solid_png(width: u32, height: u32, rgb: [u8; 3]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let data: Vec<u8> = (0..width * height).flat_map(|_| rgb).collect();
+    encoder.write_header().unwrap().write_image_data(&data).unwrap();
+    out
+}
+
+fn pixel(rgba: &[u8], size: u32, x: u32, y: u32) -> [u8; 4] {
+    let offset = ((y * size + x) * 4) as usize;
+    rgba[offset..offset + 4].try_into().unwrap()
+}
+
+#[test]
+fn default_and_custom_icons_have_standard_sizes() {
+    let defaults = PrinterIcons::default_icons();
+    for size in ICON_SIZES {
+        let (w, h, _) = decode(defaults.get(size).unwrap());
+        assert_eq!((w, h), (size, size));
+    }
+    assert!(defaults.get(64).is_none());
+
+    // 横向图片居中，上下留透明边
+    let icons = PrinterIcons::from_png(&solid_png(200, 100, [200, 30, 30])).unwrap();
+    let (_, _, rgba) = decode(icons.get(48).unwrap());
+    assert_eq!(pixel(&rgba, 48, 24, 24), [200, 30, 30, 255]);
+    assert_eq!(pixel(&rgba, 48, 24, 2), [0, 0, 0, 0]);
+    let (_, _, rgba) = decode(icons.get(512).unwrap());
+    assert_eq!(pixel(&rgba, 512, 0, 256), [200, 30, 30, 255]);
+
+    assert!(PrinterIcons::from_png(b"not a png").is_err());
+    assert_eq!(printer_icons::parse_icon_path("/icons/cups-HP/128.png?v=2"), Some(("cups-HP", 128)));
+    assert_eq!(printer_icons::parse_icon_path("/icons/128.png"), None);
+}
+
+#[test]
+fn icons_are_served_and_reported() {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let queues = QueueRegistry::new();
+    let printer = Printer {
+        name: "HP".to_string(),
+        id: "cups-HP".to_string(),
+        status: PrinterStatus::Online,
+        details: PrinterDetails::default(),
+        state: Default::default(),
+        capabilities: Some(Default::default()),
+    };
+    let queue = queues.add(printer, Arc::new(MockBackend::new()), "0b6e6c7a-1c1f-4b8e-9d5e-3f0c4d2a7b11");
+    IppServer::new("127.0.0.1", port, queues.clone()).start();
+
+    // 用户图片
+    let dir = tempfile::tempdir().unwrap();
+    let icon = dir.path().join("reception.png");
+    std::fs::write(&icon, solid_png(64, 64, [10, 120, 220])).unwrap();
+    queue.set_profile(PrinterProfile { icon: icon.to_string_lossy().to_string(), ..Default::default() });
+
+    let get = |path: &str| -> Option<(String, Vec<u8>)> {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).ok()?;
+        write!(stream, "GET {} HTTP/1.1\r\nHost: 127.0.0.1:{}\r\nConnection: close\r\n\r\n", path, port).ok()?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).ok()?;
+        let split = response.windows(4).position(|w| w == b"\r\n\r\n")?;
+        Some((String::from_utf8_lossy(&response[..split]).to_string(), response[split + 4..].to_vec()))
+    };
+    let (head, body) = (0..20)
+        .find_map(|_| {
+            get("/icons/cups-HP/128.png").or_else(|| {
+                thread::sleep(Duration::from_millis(50));
+                None
+            })
+        })
+        .expect("IPP server did not answer");
+    assert!(head.starts_with("HTTP/1.1 200"));
+    assert!(head.to_lowercase().contains("content-type: image/png"));
+    let (w, _, rgba) = decode(&body);
+    assert_eq!(w, 128);
+    assert_eq!(pixel(&rgba, 128, 64, 64), [10, 120, 220, 255]);
+    assert!(get("/icons/cups-HP/64.png").unwrap().0.starts_with("HTTP/1.1 404"));
+    assert!(get("/icons/missing/128.png").unwrap().0.starts_with("HTTP/1.1 404"));
+
+    let uri: Uri = format!("ipp://127.0.0.1:{}/{}", port, queue.resource_path()).parse().unwrap();
+    let response = IppClient::new(uri.clone()).send(IppOperationBuilder::get_printer_attributes(uri).build()).unwrap();
+    let icons = attributes::find(&response, DelimiterTag::PrinterAttributes, "printer-icons").map(attributes::as_strings);
+    assert_eq!(icons, Some(ICON_SIZES.iter().map(|s| format!("http://127.0.0.1:{}/icons/cups-HP/{}.png", port, s)).collect()));
+}
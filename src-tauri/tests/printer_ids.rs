@@ -0,0 +1,71 @@
+// src-tauri/tests/printer_ids.rs
+//
+// 打印机 ID 由队列名 / 打印机名 + 端口生成，不随顺序变化；旧版本按行号生成的 ID 可迁移。
+
+use std::collections::HashMap;
+
+use airprinter::models::AppSettings;
+use airprinter::services::printer_detector::{
+    cups_printer_id, is_legacy_id, legacy_id_map, parse_lpstat, windows_printer_id, DetectedPrinter,
+};
+
+const LPSTAT: &str = "\
+printer HP_LaserJet is idle.  enabled since Mon 01 Jan 2024 09:00:00 AM
+printer Brother_HL is idle.  enabled since Mon 01 Jan 2024 09:00:00 AM
+printer Canon_G3000 disabled since Mon 01 Jan 2024 09:00:00 AM -
+\tPaused
+";
+
+fn ids(printers: &[DetectedPrinter]) -> Vec<(String, String)> {
+    printers.iter().map(|d| (d.printer.name.clone(), d.printer.id.clone())).collect()
+}
+
+#[test]
+fn cups_ids_survive_reordering() {
+    let before = parse_lpstat(LPSTAT);
+    let mut lines: Vec<&str> = LPSTAT.lines().collect();
+    lines.swap(0, 1);
+    lines.remove(2); // Canon 移除
+    let after = parse_lpstat(&lines.join("\n"));
+
+    assert_eq!(before[0].printer.id, "cups-HP_LaserJet");
+    for printer in ids(&after) {
+        assert!(ids(&before).contains(&printer));
+    }
+}
+
+#[test]
+fn windows_ids_use_name_and_port() {
+    assert_eq!(windows_printer_id("HP LaserJet 1020", "USB001"), "win-HP_LaserJet_1020-USB001");
+    assert_ne!(windows_printer_id("HP LaserJet 1020", "USB001"), windows_printer_id("HP LaserJet 1020", "USB002"));
+
+    // 非 ASCII 名称不能互相冲突
+    let a = windows_printer_id("财务打印机", "USB001");
+    let b = windows_printer_id("前台打印机", "USB001");
+    assert_ne!(a, b);
+    assert!(a.is_ascii() && b.is_ascii());
+    assert_eq!(a, windows_printer_id("财务打印机", "USB001"));
+    assert_eq!(cups_printer_id("Office"), "cups-Office");
+}
+
+#[test]
+fn legacy_ids_are_migrated() {
+    assert!(is_legacy_id("mac-printer-0"));
+    assert!(is_legacy_id("printer-3-HP-LaserJet"));
+    assert!(is_legacy_id("printer-3"));
+    assert!(!is_legacy_id("cups-printer-1"));
+    assert!(!is_legacy_id("virtual-pdf"));
+
+    let detected = parse_lpstat(LPSTAT);
+    let legacy = vec!["mac-printer-1".to_string(), "mac-printer-9".to_string(), "proxy-x".to_string()];
+    let map = legacy_id_map(&detected, &legacy);
+    assert_eq!(map, HashMap::from([("mac-printer-1".to_string(), "cups-Brother_HL".to_string())]));
+
+    let mut settings = AppSettings::default();
+    settings.printer_uuids.insert("mac-printer-1".to_string(), "uuid-brother".to_string());
+    settings.app_socket.printer_id = "mac-printer-1".to_string();
+    settings.rename_printer_ids(&map);
+    assert_eq!(settings.printer_uuids.get("cups-Brother_HL").map(String::as_str), Some("uuid-brother"));
+    assert!(!settings.printer_uuids.contains_key("mac-printer-1"));
+    assert_eq!(settings.app_socket.printer_id, "cups-Brother_HL");
+}
@@ -0,0 +1,102 @@
+// src-tauri/tests/printer_monitor.rs
+//
+// 后台打印机监视：用可修改的替身来源代替系统检测，检查缓存和事件。
+
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use airprinter::models::{Printer, PrinterDetails, PrinterStatus, StateReason};
+use airprinter::services::printer_monitor::{diff, PrinterSource};
+use airprinter::services::PrinterMonitor;
+
+struct FakeSource(Mutex<Vec<Printer>>);
+
+impl PrinterSource for FakeSource {
+    fn printers(&self) -> Vec<Printer> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+fn printer(id: &str, status: PrinterStatus) -> Printer {
+    Printer { name: id.to_uppercase(), id: id.to_string(), status, details: PrinterDetails::default(), state: Default::default(), capabilities: None }
+}
+
+#[test]
+fn diff_reports_added_removed_and_status_changes() {
+    let before = vec![printer("a", PrinterStatus::Online), printer("b", PrinterStatus::Online), printer("c", PrinterStatus::Online)];
+    let mut paused = printer("c", PrinterStatus::Online);
+    paused.state.reasons = StateReason::parse("paused").into_iter().collect();
+    let after = vec![printer("d", PrinterStatus::Online), printer("b", PrinterStatus::Offline), paused, printer("a", PrinterStatus::Online)];
+
+    let events: Vec<(&str, String)> = diff(&before, &after).iter().map(|e| (e.name(), e.printer().id.clone())).collect();
+    assert_eq!(events, vec![
+        ("printer-added", "d".to_string()),
+        ("printer-status-changed", "b".to_string()),
+        ("printer-status-changed", "c".to_string()),
+    ]);
+
+    let events: Vec<&str> = diff(&after, &after[1..]).iter().map(|e| e.name()).collect();
+    assert_eq!(events, vec!["printer-removed"]);
+}
+
+#[test]
+fn monitor_caches_and_emits_events() {
+    let source = Arc::new(FakeSource(Mutex::new(vec![printer("a", PrinterStatus::Online)])));
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+    let monitor = PrinterMonitor::start(source.clone(), Duration::from_millis(50), move |event| {
+        tx.lock().unwrap().send((event.name(), event.printer().id.clone())).unwrap();
+    });
+
+    // 首次检测在后台进行，检测到的打印机以 Added 事件通知
+    assert_eq!(rx.recv_timeout(Duration::from_secs(2)).unwrap(), ("printer-added", "a".to_string()));
+    assert!(monitor.is_ready());
+    assert_eq!(monitor.printers().len(), 1);
+    assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+
+    source.0.lock().unwrap().push(printer("b", PrinterStatus::Online));
+    assert_eq!(rx.recv_timeout(Duration::from_secs(2)).unwrap(), ("printer-added", "b".to_string()));
+    assert_eq!(monitor.printers().len(), 2);
+
+    source.0.lock().unwrap()[0].status = PrinterStatus::Busy;
+    assert_eq!(rx.recv_timeout(Duration::from_secs(2)).unwrap(), ("printer-status-changed", "a".to_string()));
+
+    // 缓存中没有的 ID 会立即重新检测
+    source.0.lock().unwrap().remove(1);
+    source.0.lock().unwrap().push(printer("c", PrinterStatus::Online));
+    assert_eq!(monitor.find("c").map(|p| p.name), Some("C".to_string()));
+    let mut events: Vec<_> = (0..2).map(|_| rx.recv_timeout(Duration::from_secs(2)).unwrap()).collect();
+    events.sort();
+    assert_eq!(events, vec![("printer-added", "c".to_string()), ("printer-removed", "b".to_string())]);
+    assert!(monitor.find("missing").is_none());
+}
+
+/// 检测很慢的来源 (不可达的远程 CUPS 服务器)
+struct SlowSource(Duration);
+
+impl PrinterSource for SlowSource {
+    fn printers(&self) -> Vec<Printer> {
+        thread::sleep(self.0);
+        vec![printer("remote", PrinterStatus::Online)]
+    }
+}
+
+#[test]
+fn start_does_not_wait_for_first_detection() {
+    let started = Instant::now();
+    let monitor = PrinterMonitor::start(Arc::new(SlowSource(Duration::from_millis(500))), Duration::from_secs(60), |_| {});
+    assert!(started.elapsed() < Duration::from_millis(200));
+
+    // 首次检测完成前缓存为空
+    assert!(!monitor.is_ready());
+    assert!(monitor.printers().is_empty());
+
+    let ready = (0..20).find(|_| monitor.is_ready() || {
+        thread::sleep(Duration::from_millis(100));
+        false
+    });
+    assert!(ready.is_some(), "first detection did not finish");
+    assert_eq!(monitor.printers().len(), 1);
+}
@@ -0,0 +1,151 @@
+// src-tauri/tests/printer_profile.rs
+//
+// 共享打印机的显示信息：检查 mDNS TXT (`ty`、`note`) 和 Get-Printer-Attributes 中的值。
+
+use std::collections::HashMap;
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use airprinter::models::{MdnsConfig, Printer, PrinterDetails, PrinterProfile, PrinterStatus};
+use airprinter::services::backend::MockBackend;
+use airprinter::services::ipp::attributes;
+use airprinter::services::mdns_broadcaster::IPP_SERVICE;
+use airprinter::services::{IppServer, MdnsBackend, QueueRegistry};
+use ipp::prelude::*;
+use mdns_sd::ServiceInfo;
+
+/// 只记录注册内容的 mDNS 后端：(实例名, TXT)
+#[derive(Default)]
+struct RecordingMdns {
+    config: MdnsConfig,
+    registered: HashMap<String, (String, HashMap<String, String>)>,
+}
+
+impl MdnsBackend for RecordingMdns {
+    fn name(&self) -> &'static str {
+        "recording"
+    }
+
+    fn config(&self) -> &MdnsConfig {
+        &self.config
+    }
+
+    fn set_config(&mut self, config: MdnsConfig) {
+        self.config = config;
+    }
+
+    fn admin_url(&self) -> Result<String, String> {
+        Ok("http://127.0.0.1:631/".to_string())
+    }
+
+    fn register_service(
+        &mut self,
+        _printer_id: &str,
+        service_type: &str,
+        _subtypes: &[&str],
+        printer_name: &str,
+        _port: u16,
+        txt_records: HashMap<String, String>,
+    ) -> Result<(), String> {
+        self.registered.insert(service_type.to_string(), (printer_name.to_string(), txt_records));
+        Ok(())
+    }
+
+    fn withdraw_service(&mut self, _printer_id: &str, service_type: &str) {
+        self.registered.remove(service_type);
+    }
+
+    fn withdraw(&mut self, _printer_id: &str) {
+        self.registered.clear();
+    }
+
+    fn is_registered(&self, _printer_id: &str) -> bool {
+        !self.registered.is_empty()
+    }
+
+    fn services(&self) -> Vec<ServiceInfo> {
+        Vec::new()
+    }
+
+    fn stop(&mut self) {
+        self.registered.clear();
+    }
+}
+
+fn printer() -> Printer {
+    Printer {
+        name: "HP_LaserJet_Pro_M404".to_string(),
+        id: "cups-HP_LaserJet_Pro_M404".to_string(),
+        status: PrinterStatus::Online,
+        details: PrinterDetails { location: "Room 2F".to_string(), ..Default::default() },
+        state: Default::default(),
+        capabilities: Some(Default::default()),
+    }
+}
+
+fn profile() -> PrinterProfile {
+    PrinterProfile {
+        display_name: "前台打印机".to_string(),
+        location: "二楼前台".to_string(),
+        info: "Reception laser printer".to_string(),
+        geo_location: "geo:31.2304,121.4737".to_string(),
+        more_info: "https://intranet.example.com/printers/reception".to_string(),
+        icon: String::new(),
+    }
+}
+
+#[test]
+fn mdns_txt_uses_display_name_and_location() {
+    let queues = QueueRegistry::new();
+    let queue = queues.add(printer(), Arc::new(MockBackend::new()), "0b6e6c7a-1c1f-4b8e-9d5e-3f0c4d2a7b11");
+    let mut mdns = RecordingMdns::default();
+
+    // 没有自定义信息时使用队列名和系统报告的位置
+    mdns.broadcast_airprint(&queue, 631).unwrap();
+    let (name, txt) = &mdns.registered[IPP_SERVICE];
+    assert_eq!((name.as_str(), txt["ty"].as_str(), txt["note"].as_str()), ("HP_LaserJet_Pro_M404", "HP_LaserJet_Pro_M404", "Room 2F"));
+    assert_eq!(txt["printer-icons"], "http://127.0.0.1:631/icons/cups-HP_LaserJet_Pro_M404/512.png");
+
+    queue.set_profile(profile());
+    mdns.broadcast_airprint(&queue, 631).unwrap();
+    let (name, txt) = &mdns.registered[IPP_SERVICE];
+    assert_eq!((name.as_str(), txt["ty"].as_str(), txt["note"].as_str()), ("前台打印机", "前台打印机", "二楼前台"));
+}
+
+#[test]
+fn get_printer_attributes_reports_profile() {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let queues = QueueRegistry::new();
+    let queue = queues.add(printer(), Arc::new(MockBackend::new()), "0b6e6c7a-1c1f-4b8e-9d5e-3f0c4d2a7b11");
+    IppServer::new("127.0.0.1", port, queues.clone()).start();
+
+    let uri: Uri = format!("ipp://127.0.0.1:{}/{}", port, queue.resource_path()).parse().unwrap();
+    let query = || {
+        let client = IppClient::new(uri.clone());
+        let response = (0..20)
+            .find_map(|_| {
+                client.send(IppOperationBuilder::get_printer_attributes(uri.clone()).build()).ok().or_else(|| {
+                    thread::sleep(Duration::from_millis(50));
+                    None
+                })
+            })
+            .expect("IPP server did not answer");
+        move |name: &str| attributes::find(&response, DelimiterTag::PrinterAttributes, name).map(|v| v.to_string())
+    };
+
+    let find = query();
+    assert_eq!(find("printer-name").as_deref(), Some("HP_LaserJet_Pro_M404"));
+    assert_eq!(find("printer-location").as_deref(), Some("Room 2F"));
+    assert_eq!(find("printer-info").as_deref(), Some("HP_LaserJet_Pro_M404"));
+    assert_eq!(find("printer-geo-location"), None);
+
+    queue.set_profile(profile());
+    let find = query();
+    assert_eq!(find("printer-name").as_deref(), Some("前台打印机"));
+    assert_eq!(find("printer-location").as_deref(), Some("二楼前台"));
+    assert_eq!(find("printer-info").as_deref(), Some("Reception laser printer"));
+    assert_eq!(find("printer-geo-location").as_deref(), Some("geo:31.2304,121.4737"));
+    assert_eq!(find("printer-more-info").as_deref(), Some("https://intranet.example.com/printers/reception"));
+}
@@ -0,0 +1,114 @@
+// src-tauri/tests/remote_cups.rs
+//
+// 远程 CUPS 服务器：用本地的替身 IPP 服务器代替打印服务器，检查队列列表和任务转发。
+
+use std::io::Read;
+use std::sync::mpsc;
+use std::thread;
+
+use airprinter::models::PrinterStatus;
+use airprinter::services::backend::{self, BackendJob, BackendKind, JobState, PrintOptions};
+use airprinter::services::cups;
+use ipp::attribute::{IppAttribute, IppAttributeGroup};
+use ipp::model::{DelimiterTag, IppVersion, Operation, StatusCode};
+use ipp::parser::IppParser;
+use ipp::request::IppRequestResponse;
+use ipp::value::IppValue;
+use tiny_http::{Header, Response, Server};
+
+fn group(tag: DelimiterTag, attrs: Vec<(&str, IppValue)>) -> IppAttributeGroup {
+    let mut group = IppAttributeGroup::new(tag);
+    for (name, value) in attrs {
+        group.attributes_mut().insert(name.to_string(), IppAttribute::new(name, value));
+    }
+    group
+}
+
+/// 替身打印服务器：一个队列 Office，收到的 Print-Job 以 (请求路径, 文档) 发回
+fn serve(server: Server, jobs: mpsc::Sender<(String, Vec<u8>)>) {
+    thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let path = request.url().to_string();
+            let mut body = Vec::new();
+            request.as_reader().read_to_end(&mut body).unwrap();
+            let parsed = IppParser::new(std::io::Cursor::new(body)).parse().unwrap();
+            let (operation, request_id) = (parsed.header().operation_or_status, parsed.header().request_id);
+
+            let mut response = IppRequestResponse::new_response(IppVersion::v1_1(), StatusCode::SuccessfulOk, request_id);
+            let groups = response.attributes_mut().groups_mut();
+            if operation == Operation::CupsGetPrinters as u16 {
+                groups.push(group(DelimiterTag::PrinterAttributes, vec![
+                    ("printer-name", IppValue::NameWithoutLanguage("Office".to_string())),
+                    ("printer-state", IppValue::Enum(3)),
+                    ("printer-make-and-model", IppValue::TextWithoutLanguage("Kyocera ECOSYS P2040dn".to_string())),
+                    ("document-format-supported", IppValue::MimeMediaType("application/pdf".to_string())),
+                ]));
+            } else if operation == Operation::PrintJob as u16 {
+                let mut document = Vec::new();
+                parsed.into_payload().read_to_end(&mut document).unwrap();
+                jobs.send((path, document)).unwrap();
+                groups.push(group(DelimiterTag::JobAttributes, vec![("job-id", IppValue::Integer(42))]));
+            } else if operation == Operation::GetJobAttributes as u16 {
+                groups.push(group(DelimiterTag::JobAttributes, vec![("job-state", IppValue::Enum(9))]));
+            }
+
+            let header = Header::from_bytes("Content-Type", "application/ipp").unwrap();
+            request.respond(Response::from_data(response.to_bytes().to_vec()).with_header(header)).unwrap();
+        }
+    });
+}
+
+#[test]
+fn remote_queues_are_listed_and_jobs_forwarded() {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let host = server.server_addr().to_ip().unwrap().to_string();
+    let (tx, rx) = mpsc::channel();
+    serve(server, tx);
+
+    let printers = cups::remote_printers(&host).expect("remote CUPS-Get-Printers failed");
+    assert_eq!(printers.len(), 1);
+    let office = &printers[0];
+    assert_eq!(office.name, "Office");
+    assert_eq!(office.id, format!("remote-{}-Office", host.replace(['.', ':'], "_")));
+    assert_eq!(office.details.origin, host);
+    assert_eq!(office.details.make_and_model, "Kyocera ECOSYS P2040dn");
+    assert!(matches!(office.status, PrinterStatus::Online));
+
+    // 远程队列默认经 IPP 转发，lp 只能用于本机队列
+    assert_eq!(BackendKind::default_for(office), BackendKind::CupsIpp);
+    assert!(backend::create(BackendKind::Lp, office).is_err());
+
+    let dir = tempfile::tempdir().unwrap();
+    let document = dir.path().join("job.pdf");
+    std::fs::write(&document, b"%PDF-1.4 remote").unwrap();
+    let job = BackendJob {
+        id: 1,
+        name: "report".to_string(),
+        user: "alice".to_string(),
+        device: "192.168.1.20".to_string(),
+        document_format: "application/pdf".to_string(),
+        document,
+        options: PrintOptions::default(),
+    };
+
+    let backend = backend::create(BackendKind::default_for(office), office).unwrap();
+    let job_id = backend.submit(&job).unwrap();
+    assert_eq!(job_id, "42");
+    assert_eq!(rx.recv().unwrap(), ("/printers/Office".to_string(), b"%PDF-1.4 remote".to_vec()));
+    assert_eq!(backend.status(&job_id).unwrap(), JobState::Completed);
+}
+
+#[test]
+fn cups_hosts_are_normalized() {
+    assert_eq!(cups::parse_host(" print.example.com "), Some("print.example.com".to_string()));
+    assert_eq!(cups::parse_host("ipp://10.0.0.5:8631/"), Some("10.0.0.5:8631".to_string()));
+    assert_eq!(cups::parse_host("[fd00::5]:631"), Some("[fd00::5]:631".to_string()));
+    assert_eq!(cups::parse_host(""), None);
+    assert_eq!(cups::parse_host("host/printers/Office"), None);
+    assert_eq!(cups::parse_host("print server"), None);
+}
+
+#[test]
+fn unreachable_remote_cups_is_an_error() {
+    assert!(cups::remote_printers("127.0.0.1:9").is_err());
+}
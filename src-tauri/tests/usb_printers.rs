@@ -0,0 +1,106 @@
+// src-tauri/tests/usb_printers.rs
+//
+// 直接检测 USB 打印机：在临时目录中搭一棵假的 sysfs / dev，检查解析、去重和 raw 输出。
+
+use std::fs;
+use std::path::Path;
+
+use airprinter::models::{Printer, PrinterDetails, PrinterStatus};
+use airprinter::services::backend::{BackendJob, BackendKind, PrintOptions, PrinterBackend, UsbRawBackend};
+use airprinter::services::usb::{self, DeviceId};
+
+const HP_ID: &str = "MFG:HP;MDL:LaserJet Pro M404;CMD:PJL,PCL,POSTSCRIPT,PDF;CLS:PRINTER;DES:HP LaserJet Pro M404n;SN:PHB123;";
+const BROTHER_ID: &str = "MANUFACTURER:Brother;COMMAND SET:PJL,PCL,PCLXL;MODEL:HL-L2350DW series;CLASS:PRINTER;";
+
+/// class/usbmisc/<node>/device/ieee1284_id，USB 序列号放在上一级目录
+fn add_device(sysfs: &Path, dev: &Path, node: &str, device_id: &str, serial: Option<&str>) {
+    let dir = sysfs.join("class/usbmisc").join(node);
+    fs::create_dir_all(dir.join("device")).unwrap();
+    fs::write(dir.join("device/ieee1284_id"), device_id).unwrap();
+    if let Some(serial) = serial {
+        fs::write(dir.join("serial"), format!("{}\n", serial)).unwrap();
+    }
+    fs::create_dir_all(dev.join("usb")).unwrap();
+    fs::write(dev.join("usb").join(node), b"").unwrap();
+}
+
+#[test]
+fn ieee1284_device_id_is_parsed() {
+    let hp = DeviceId::parse(HP_ID);
+    assert_eq!(hp.make_and_model(), "HP LaserJet Pro M404");
+    assert_eq!(hp.device_uri(), "usb://HP/LaserJet%20Pro%20M404?serial=PHB123");
+    assert_eq!(hp.document_formats(), vec![
+        "application/pdf",
+        "application/postscript",
+        "application/vnd.hp-pcl",
+        "application/octet-stream",
+    ]);
+
+    let brother = DeviceId::parse(BROTHER_ID);
+    assert_eq!(brother.make_and_model(), "Brother HL-L2350DW series");
+    assert!(brother.serial.is_empty());
+}
+
+#[test]
+fn sysfs_scan_skips_configured_queues() {
+    let root = tempfile::tempdir().unwrap();
+    let (sysfs, dev) = (root.path().join("sys"), root.path().join("dev"));
+    add_device(&sysfs, &dev, "lp0", HP_ID, None);
+    add_device(&sysfs, &dev, "lp1", BROTHER_ID, Some("E78123"));
+    // 不是打印机的 usbmisc 设备
+    fs::create_dir_all(sysfs.join("class/usbmisc/hiddev0")).unwrap();
+
+    let printers = usb::unconfigured_printers(&sysfs, &dev, &[]);
+    assert_eq!(printers.len(), 2);
+    assert_eq!(printers[0].id, "usb-HP_LaserJet_Pro_M404-PHB123");
+    assert_eq!(printers[1].name, "Brother HL-L2350DW series");
+    assert_eq!(printers[1].details.device_uri, "usb://Brother/HL-L2350DW%20series?serial=E78123");
+    assert!(matches!(printers[1].status, PrinterStatus::Online));
+
+    // HP 已经是 CUPS 队列 (CUPS 对空格的转义方式不同)
+    let queue = Printer {
+        name: "HP_LaserJet".to_string(),
+        id: "cups-HP_LaserJet".to_string(),
+        status: PrinterStatus::Online,
+        details: PrinterDetails { device_uri: "usb://HP/LaserJet+Pro+M404?serial=PHB123&interface=1".to_string(), ..Default::default() },
+        state: Default::default(),
+        capabilities: None,
+    };
+    let printers = usb::unconfigured_printers(&sysfs, &dev, &[queue]);
+    assert_eq!(printers.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(), vec!["usb-Brother_HL-L2350DW_series-E78123"]);
+    assert_eq!(BackendKind::default_for(&printers[0]), BackendKind::UsbRaw);
+}
+
+#[test]
+fn raw_backend_writes_to_current_device_node() {
+    let root = tempfile::tempdir().unwrap();
+    let (sysfs, dev) = (root.path().join("sys"), root.path().join("dev"));
+    add_device(&sysfs, &dev, "lp3", HP_ID, None);
+    let printer = usb::unconfigured_printers(&sysfs, &dev, &[]).remove(0);
+
+    let document = root.path().join("job.pdf");
+    fs::write(&document, b"%PDF-1.4 test").unwrap();
+    let mut job = BackendJob {
+        id: 7,
+        name: "test".to_string(),
+        user: "alice".to_string(),
+        device: "127.0.0.1".to_string(),
+        document_format: "application/pdf".to_string(),
+        document: document.clone(),
+        options: PrintOptions::default(),
+    };
+
+    let backend = UsbRawBackend::with_roots(&printer.id, printer.capabilities.clone().unwrap(), &sysfs, &dev);
+    let job_id = backend.submit(&job).unwrap();
+    assert_eq!(fs::read(dev.join("usb/lp3")).unwrap(), b"%PDF-1.4 test");
+    assert!(backend.status(&job_id).unwrap().is_finished());
+
+    // 打印机不认识的格式不发送
+    job.document_format = "image/jpeg".to_string();
+    assert!(backend.submit(&job).is_err());
+
+    // 拔掉后提交失败
+    fs::remove_dir_all(sysfs.join("class/usbmisc/lp3")).unwrap();
+    job.document_format = "application/pdf".to_string();
+    assert!(backend.submit(&job).is_err());
+}
@@ -1,12 +1,22 @@
-import { printerApi, Printer } from "../services/printerService";
+import { printerApi, Printer, PrinterEventName } from "../services/printerService";
 import i18n from '../i18n';
 
+// 状态对应的文字和徽章样式
+const STATUS_BADGES: Record<string, { key: string; badge: string; shareable: boolean }> = {
+  online: { key: 'status.online', badge: 'badge-success', shareable: true },
+  busy: { key: 'status.busy', badge: 'badge-info', shareable: true },
+  paused: { key: 'status.paused', badge: 'badge-warning', shareable: true },
+  stopped: { key: 'status.stopped', badge: 'badge-error', shareable: false },
+  offline: { key: 'status.offline', badge: 'badge-error', shareable: false },
+};
+
 export class PrinterList {
   private container: HTMLElement;
   private listContainer: HTMLElement;
   private refreshBtn: HTMLButtonElement;
   private printers: Printer[] = [];
   private sharedPrinterIds: Set<string> = new Set();
+  private unlistenPrinterEvents?: () => void;
   
   // 定义事件处理函数，以便后续移除监听
   private handleLanguageChange = () => {
@@ -28,12 +38,31 @@ export class PrinterList {
     // ✅ 修复：直接绑定，不需要接收返回值
     i18n.on('languageChanged', this.handleLanguageChange);
 
-    this.load();
+    // 先订阅再读取缓存：后台首次检测完成前缓存为空，检测到的打印机随后以事件送达
+    this.subscribe().then(() => this.load());
   }
 
   // ✅ 修复：使用 off 方法移除监听
   public destroy() {
     i18n.off('languageChanged', this.handleLanguageChange);
+    this.unlistenPrinterEvents?.();
+  }
+
+  // 后台检测到打印机变化时直接更新列表，无需手动刷新
+  private async subscribe() {
+    this.unlistenPrinterEvents = await printerApi.onPrinterEvent((event, printer) => this.applyEvent(event, printer));
+  }
+
+  private applyEvent(event: PrinterEventName, printer: Printer) {
+    const index = this.printers.findIndex(p => p.id === printer.id);
+    if (event === "printer-removed") {
+      if (index >= 0) this.printers.splice(index, 1);
+    } else if (index >= 0) {
+      this.printers[index] = printer;
+    } else {
+      this.printers.push(printer);
+    }
+    this.renderList();
   }
 
   private render() {
@@ -68,17 +97,17 @@ export class PrinterList {
   }
 
   private bindEvents() {
-    this.refreshBtn.addEventListener("click", () => this.load());
+    this.refreshBtn.addEventListener("click", () => this.load(true));
   }
 
-  async load() {
+  async load(refresh = false) {
     this.setLoading(true);
     const loadingTextEl = document.getElementById('pl-loading-text');
     if(loadingTextEl) loadingTextEl.textContent = i18n.t('common.loading');
 
     try {
       const [printers, shared] = await Promise.all([
-        printerApi.getList(),
+        refresh ? printerApi.refresh() : printerApi.getList(),
         printerApi.getSharedList()
       ]);
       
@@ -101,19 +130,19 @@ export class PrinterList {
 
     this.listContainer.innerHTML = this.printers.map(p => {
       const statusStr = (p.status || '').toString().toLowerCase();
-      const isOnline = statusStr === 'online';
+      const status = STATUS_BADGES[statusStr] ?? STATUS_BADGES.offline;
       const isShared = this.sharedPrinterIds.has(p.id);
       
-      const statusKey = isOnline ? 'status.online' : 'status.offline';
-      const statusText = i18n.t(statusKey);
+      const statusText = i18n.t(status.key);
       
-      const badgeClass = isOnline ? 'badge-success' : 'badge-error';
+      const badgeClass = status.badge;
       
       const shareKey = isShared ? 'actions.stop_sharing' : 'actions.share';
       const btnText = i18n.t(shareKey);
       
       const btnClass = isShared ? 'btn-error' : 'btn-primary';
-      const btnDisabled = !isOnline && !isShared;
+      // 暂停和忙碌的打印机仍可接收任务
+      const btnDisabled = !status.shareable && !isShared;
       
       return `
         <div class="flex items-center justify-between p-3 bg-base-200 rounded-lg">
@@ -122,6 +151,8 @@ export class PrinterList {
             <div>
               <div class="font-bold">${p.name}</div>
               <div class="text-xs text-gray-500">ID: ${p.id}</div>
+              ${p.details?.origin ? `<div class="text-xs text-gray-500">${i18n.t('printers.origin', { host: p.details.origin })}</div>` : ''}
+              ${this.renderStateHints(p)}
             </div>
           </div>
           <div class="flex items-center gap-2">
@@ -149,6 +180,30 @@ export class PrinterList {
     });
   }
 
+  // "碳粉不足"、"缺纸" 等提示：警告及以上的原因、余量不足的耗材、排队任务数
+  private renderStateHints(p: Printer): string {
+    const state = p.state;
+    if (!state) return '';
+
+    const hints = state.reasons
+      .filter(r => r.severity !== 'report')
+      .map(r => {
+        const text = i18n.t(`reasons.${r.reason}`, { defaultValue: r.reason.replace(/-/g, ' ') });
+        return `<span class="badge badge-xs ${r.severity === 'error' ? 'badge-error' : 'badge-warning'}">${text}</span>`;
+      });
+    hints.push(...state.markers
+      .filter(m => m.level >= 0 && m.level <= m.low_level)
+      .map(m => `<span class="badge badge-xs badge-warning">${i18n.t('printers.marker_low', { name: m.name, level: m.level })}</span>`));
+    if (!state.accepting_jobs) {
+      hints.push(`<span class="badge badge-xs badge-ghost">${i18n.t('status.not_accepting')}</span>`);
+    }
+    if (state.active_jobs > 0) {
+      hints.push(`<span class="text-xs text-gray-500">${i18n.t('printers.active_jobs', { count: state.active_jobs })}</span>`);
+    }
+
+    return hints.length ? `<div class="flex flex-wrap gap-1 mt-1">${hints.join('')}</div>` : '';
+  }
+
   private async handleShare(printerId: string, isShared: boolean, btn: HTMLButtonElement) {
     btn.disabled = true;
     
@@ -1,12 +1,137 @@
 // src/services/printerService.ts
 import { invoke } from "@tauri-apps/api/core"; 
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
 import { logService } from "./logService";
 import i18n from "../i18n"; // 确保引入
 
 export interface Printer {
   name: string;
   id: string;
-  status: "online" | "offline" | "busy" | string;
+  status: "online" | "offline" | "busy" | "paused" | "stopped" | string;
+  details?: PrinterDetails;
+  state?: PrinterState;
+  capabilities?: PrinterCapabilities | null;
+}
+
+// 系统报告的打印机信息 (CUPS)，未知时为空字符串
+export interface PrinterDetails {
+  make_and_model: string;
+  location: string;
+  info: string;
+  device_uri: string;
+  // 远程 CUPS 服务器 (host[:port])，本机打印机为空
+  origin: string;
+}
+
+// 运行状态细节：printer-state-reasons、任务数、耗材
+export interface PrinterState {
+  accepting_jobs: boolean;
+  active_jobs: number;
+  reasons: StateReason[];
+  markers: Marker[];
+}
+
+export interface StateReason {
+  /** 原始关键字，例如 toner-low-warning */
+  keyword: string;
+  /** 去掉严重程度后缀，例如 toner-low */
+  reason: string;
+  severity: "report" | "warning" | "error";
+}
+
+export interface Marker {
+  name: string;
+  /** #RRGGBB，多色耗材为多个颜色相连，未知为 none */
+  color: string;
+  kind: string;
+  /** 剩余百分比；负数表示未知 */
+  level: number;
+  low_level: number;
+  high_level: number;
+}
+
+// 检测到的打印能力 (CUPS IPP 属性、PPD 或 lpoptions)
+export interface PrinterCapabilities {
+  color: boolean;
+  duplex: boolean;
+  document_formats: string[];
+  max_paper: string;
+  resolutions: number[];
+  media: string[];
+}
+
+// 虚拟 "保存到文件夹" 打印机设置
+export interface VirtualPrinterConfig {
+  enabled: boolean;
+  name: string;
+  output_dir: string;
+  file_name_template: string;
+}
+
+export interface AppSocketConfig {
+  enabled: boolean;
+  port: number;
+  printer_id: string;
+}
+
+export interface LpdConfig {
+  enabled: boolean;
+  port: number;
+}
+
+export interface MdnsConfig {
+  print_subtype: boolean;
+  ipps_port: number | null;
+  /** 广播的网卡名，为空时使用全部非 VPN / 虚拟网卡 */
+  interfaces: string[];
+  /** 重发 mDNS 通告的间隔 (秒)，0 表示只在注册时通告 */
+  announce_interval_secs: number;
+}
+
+export interface ReflectorConfig {
+  enabled: boolean;
+  /** 浏览的网卡名，为空时浏览全部网卡 */
+  source_interface: string;
+  /** 重新广播的网卡名，为空时使用 mDNS 设置中的网卡 */
+  target_interface: string;
+  /** 要反射的打印机名称，支持 * 通配 */
+  include: string[];
+  exclude: string[];
+}
+
+export interface DiscoveredPrinter {
+  name: string;
+  service_type: string;
+  host: string;
+  addresses: string[];
+  port: number;
+  uri: string;
+  model: string;
+  selected: boolean;
+  reflected: boolean;
+}
+
+export interface NetworkInterface {
+  name: string;
+  addr: string;
+}
+
+// 后台打印机监视发出的事件
+export type PrinterEventName = "printer-added" | "printer-removed" | "printer-status-changed";
+
+export interface ProxyPrinterConfig {
+  name: string;
+  uri: string;
+}
+
+// 共享时对外显示的信息，为空的项使用系统报告的值
+export interface PrinterProfile {
+  display_name: string;
+  location: string;
+  info: string;
+  geo_location: string; // geo:纬度,经度
+  more_info: string;
+  icon: string; // PNG 图片路径，为空时使用默认图标
 }
 
 export const printerApi = {
@@ -28,6 +153,28 @@ export const printerApi = {
     }
   },
 
+  /** 立即重新检测打印机 (get_printers 只读取后台缓存) */
+  async refresh(): Promise<Printer[]> {
+    logService.add(i18n.t('logs.fetching_printers'), "info");
+    try {
+        const printers = await invoke<Printer[]>("refresh_printers");
+        logService.add(i18n.t('logs.found_printers', { count: printers.length }), "success");
+        return printers;
+    } catch (error) {
+        logService.add(i18n.t('errors.fetch_failed', { error: String(error) }), "error");
+        throw error;
+    }
+  },
+
+  /** 订阅打印机增加 / 移除 / 状态变化，返回取消订阅函数 */
+  async onPrinterEvent(handler: (event: PrinterEventName, printer: Printer) => void): Promise<UnlistenFn> {
+    const names: PrinterEventName[] = ["printer-added", "printer-removed", "printer-status-changed"];
+    const unlisteners = await Promise.all(
+      names.map(name => listen<Printer>(name, e => handler(name, e.payload)))
+    );
+    return () => unlisteners.forEach(unlisten => unlisten());
+  },
+
   async share(printerId: string): Promise<string> {
     // ✅ 修改这里
     console.log(i18n.t('debug.sharing_request', { id: printerId }));
@@ -55,5 +202,89 @@ export const printerApi = {
   async unshare(printerId: string): Promise<void> {
       await invoke("unshare_printer", { printerId });
       logService.add(i18n.t('logs.stopped_sharing', { id: printerId }), "info");
+  },
+
+  async getVirtualPrinterConfig(): Promise<VirtualPrinterConfig> {
+      return await invoke<VirtualPrinterConfig>("get_virtual_printer_config");
+  },
+
+  async setVirtualPrinterConfig(config: VirtualPrinterConfig): Promise<void> {
+      await invoke("set_virtual_printer_config", { config });
+  },
+
+  // 远程 CUPS 服务器 (host 或 host:port)
+  async getCupsHosts(): Promise<string[]> {
+      return await invoke<string[]>("get_cups_hosts");
+  },
+
+  async setCupsHosts(hosts: string[]): Promise<void> {
+      await invoke("set_cups_hosts", { hosts });
+  },
+
+  async getPrinterProfiles(): Promise<Record<string, PrinterProfile>> {
+      return await invoke<Record<string, PrinterProfile>>("get_printer_profiles");
+  },
+
+  async setPrinterProfile(printerId: string, profile: PrinterProfile): Promise<void> {
+      await invoke("set_printer_profile", { printerId, profile });
+  },
+
+  async getProxyPrinters(): Promise<ProxyPrinterConfig[]> {
+      return await invoke<ProxyPrinterConfig[]>("get_proxy_printers");
+  },
+
+  async setProxyPrinters(printers: ProxyPrinterConfig[]): Promise<void> {
+      await invoke("set_proxy_printers", { printers });
+  },
+
+  async getAppSocketConfig(): Promise<AppSocketConfig> {
+      return await invoke<AppSocketConfig>("get_app_socket_config");
+  },
+
+  async setAppSocketConfig(config: AppSocketConfig): Promise<void> {
+      await invoke("set_app_socket_config", { config });
+  },
+
+  async getLpdConfig(): Promise<LpdConfig> {
+      return await invoke<LpdConfig>("get_lpd_config");
+  },
+
+  async setLpdConfig(config: LpdConfig): Promise<void> {
+      await invoke("set_lpd_config", { config });
+  },
+
+  async getMdnsConfig(): Promise<MdnsConfig> {
+      return await invoke<MdnsConfig>("get_mdns_config");
+  },
+
+  async setMdnsConfig(config: MdnsConfig): Promise<void> {
+      await invoke("set_mdns_config", { config });
+  },
+
+  async getNetworkInterfaces(): Promise<NetworkInterface[]> {
+      return await invoke<NetworkInterface[]>("get_network_interfaces");
+  },
+
+  /** 导出共享打印机的 DNS-SD 区域文件片段，例如 domain = "printers.example.com" */
+  async exportDnsSdZone(domain: string): Promise<string> {
+      return await invoke<string>("export_dns_sd_zone", { domain });
+  },
+
+  /** 导出 DNS UPDATE (RFC 2136) 报文，zone 为要更新的区域 */
+  async getReflectorConfig(): Promise<ReflectorConfig> {
+      return await invoke<ReflectorConfig>("get_reflector_config");
+  },
+
+  async setReflectorConfig(config: ReflectorConfig): Promise<void> {
+      await invoke("set_reflector_config", { config });
+  },
+
+  async getDiscoveredPrinters(): Promise<DiscoveredPrinter[]> {
+      return await invoke<DiscoveredPrinter[]>("get_discovered_printers");
+  },
+
+  async exportDnsSdUpdate(domain: string, zone: string): Promise<Uint8Array> {
+      const bytes = await invoke<number[]>("export_dns_sd_update", { domain, zone });
+      return new Uint8Array(bytes);
   }
 };
\ No newline at end of file